@@ -19,6 +19,15 @@ extern "C" {
   fn v8__V8__SetFlagsFromString(flags: *const u8, length: usize);
   fn v8__V8__SetEntropySource(callback: EntropySource);
   fn v8__V8__GetVersion() -> *const c_char;
+  fn v8__V8__InitializeICU(icu_data_file: *const c_char) -> bool;
+  fn v8__V8__InitializeICUDefaultLocation(
+    exec_path: *const c_char,
+    icu_data_file: *const c_char,
+  ) -> bool;
+  fn v8__V8__InitializeExternalStartupData(directory_path: *const c_char);
+  fn v8__V8__InitializeExternalStartupDataFromFile(
+    snapshot_blob: *const c_char,
+  );
   fn v8__V8__InitializePlatform(platform: *mut Platform);
   fn v8__V8__Initialize();
   fn v8__V8__Dispose() -> bool;
@@ -174,6 +183,65 @@ pub fn get_version() -> &'static str {
   c_str.to_str().unwrap()
 }
 
+/// Initializes ICU from the data file at `icu_data_file`, or from the
+/// default location if `None` is passed. This should be invoked before V8 is
+/// initialized. Returns false if the data could not be loaded.
+///
+/// This only has an effect in builds that load ICU data from a file (the
+/// `icu_use_data_file` gn arg). Builds with linked-in ICU data ignore the
+/// path and always return true; use `icu::set_common_data_70()` to provide
+/// the data yourself in that case.
+pub fn initialize_icu(icu_data_file: Option<&str>) -> bool {
+  let icu_data_file = icu_data_file.map(|s| CString::new(s).unwrap());
+  unsafe {
+    v8__V8__InitializeICU(
+      icu_data_file
+        .as_ref()
+        .map_or(std::ptr::null(), |s| s.as_ptr()),
+    )
+  }
+}
+
+/// Like `initialize_icu()`, but when `icu_data_file` is `None` the data file
+/// (`icudtl.dat`) is looked up in the directory that contains the executable
+/// at `exec_path`.
+pub fn initialize_icu_default_location(
+  exec_path: &str,
+  icu_data_file: Option<&str>,
+) -> bool {
+  let exec_path = CString::new(exec_path).unwrap();
+  let icu_data_file = icu_data_file.map(|s| CString::new(s).unwrap());
+  unsafe {
+    v8__V8__InitializeICUDefaultLocation(
+      exec_path.as_ptr(),
+      icu_data_file
+        .as_ref()
+        .map_or(std::ptr::null(), |s| s.as_ptr()),
+    )
+  }
+}
+
+/// Initializes the external startup data from `snapshot_blob.bin` in
+/// `directory_path`. This should be invoked before V8 is initialized.
+///
+/// This only has an effect in builds that don't embed the snapshot in the
+/// binary (the `v8_use_external_startup_data` gn arg), otherwise it does
+/// nothing. V8 no longer has a separate natives blob, so the snapshot blob is
+/// the only file that is loaded.
+pub fn initialize_external_startup_data(directory_path: &str) {
+  let directory_path = CString::new(directory_path).unwrap();
+  unsafe { v8__V8__InitializeExternalStartupData(directory_path.as_ptr()) }
+}
+
+/// Like `initialize_external_startup_data()`, but takes the path of the
+/// snapshot blob itself.
+pub fn initialize_external_startup_data_from_file(snapshot_blob: &str) {
+  let snapshot_blob = CString::new(snapshot_blob).unwrap();
+  unsafe {
+    v8__V8__InitializeExternalStartupDataFromFile(snapshot_blob.as_ptr())
+  }
+}
+
 /// Sets the v8::Platform to use. This should be invoked before V8 is
 /// initialized.
 pub fn initialize_platform(platform: SharedRef<Platform>) {
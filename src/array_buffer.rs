@@ -16,6 +16,7 @@ use crate::support::SharedRef;
 use crate::support::UniquePtr;
 use crate::support::UniqueRef;
 use crate::ArrayBuffer;
+use crate::Context;
 use crate::HandleScope;
 use crate::Isolate;
 use crate::Local;
@@ -35,9 +36,19 @@ extern "C" {
     isolate: *mut Isolate,
     backing_store: *const SharedRef<BackingStore>,
   ) -> *const ArrayBuffer;
+  fn v8__ArrayBuffer__NewResizable(
+    context: *const Context,
+    byte_length: usize,
+    max_byte_length: usize,
+  ) -> *const ArrayBuffer;
   fn v8__ArrayBuffer__Detach(this: *const ArrayBuffer);
   fn v8__ArrayBuffer__IsDetachable(this: *const ArrayBuffer) -> bool;
+  fn v8__ArrayBuffer__WasDetached(this: *const ArrayBuffer) -> bool;
   fn v8__ArrayBuffer__ByteLength(this: *const ArrayBuffer) -> usize;
+  fn v8__ArrayBuffer__MaxByteLength(this: *const ArrayBuffer) -> usize;
+  fn v8__ArrayBuffer__IsResizableByUserJavaScript(
+    this: *const ArrayBuffer,
+  ) -> bool;
   fn v8__ArrayBuffer__GetBackingStore(
     this: *const ArrayBuffer,
   ) -> SharedRef<BackingStore>;
@@ -55,6 +66,10 @@ extern "C" {
   fn v8__BackingStore__Data(this: *const BackingStore) -> *mut c_void;
   fn v8__BackingStore__ByteLength(this: *const BackingStore) -> usize;
   fn v8__BackingStore__IsShared(this: *const BackingStore) -> bool;
+  fn v8__BackingStore__MaxByteLength(this: *const BackingStore) -> usize;
+  fn v8__BackingStore__IsResizableByUserJavaScript(
+    this: *const BackingStore,
+  ) -> bool;
   fn v8__BackingStore__DELETE(this: *mut BackingStore);
 
   fn std__shared_ptr__v8__BackingStore__COPY(
@@ -290,6 +305,20 @@ impl BackingStore {
   pub fn is_shared(&self) -> bool {
     unsafe { v8__BackingStore__IsShared(self) }
   }
+
+  /// The maximum length (in bytes) this backing store may grow to. Equal to
+  /// `byte_length()` unless the backing store is resizable.
+  pub fn max_byte_length(&self) -> usize {
+    unsafe { v8__BackingStore__MaxByteLength(self) }
+  }
+
+  /// Indicates whether the backing store was created for a resizable
+  /// ArrayBuffer or a growable SharedArrayBuffer. The memory of such a
+  /// backing store is reserved up front for `max_byte_length()` bytes, and
+  /// only the first `byte_length()` bytes of it are committed.
+  pub fn is_resizable_by_user_javascript(&self) -> bool {
+    unsafe { v8__BackingStore__IsResizableByUserJavaScript(self) }
+  }
 }
 
 impl Deref for BackingStore {
@@ -354,6 +383,31 @@ impl ArrayBuffer {
     .unwrap()
   }
 
+  /// Create a new resizable ArrayBuffer of `byte_length` bytes, which
+  /// JavaScript can grow in place up to `max_byte_length` bytes with
+  /// `ArrayBuffer.prototype.resize()`. This is the equivalent of
+  /// `new ArrayBuffer(byte_length, { maxByteLength: max_byte_length })`.
+  ///
+  /// Resizable buffers are only available when V8 runs with the
+  /// `--harmony-rab-gsab` flag; without it, `None` is returned without
+  /// throwing an exception. Returns `None` and throws a `RangeError` if
+  /// `byte_length` exceeds `max_byte_length` or the memory can't be reserved.
+  pub fn new_resizable<'s>(
+    scope: &mut HandleScope<'s>,
+    byte_length: usize,
+    max_byte_length: usize,
+  ) -> Option<Local<'s, ArrayBuffer>> {
+    unsafe {
+      scope.cast_local(|sd| {
+        v8__ArrayBuffer__NewResizable(
+          sd.get_current_context(),
+          byte_length,
+          max_byte_length,
+        )
+      })
+    }
+  }
+
   pub fn with_backing_store<'s>(
     scope: &mut HandleScope<'s>,
     backing_store: &SharedRef<BackingStore>,
@@ -374,15 +428,36 @@ impl ArrayBuffer {
     unsafe { v8__ArrayBuffer__ByteLength(self) }
   }
 
+  /// Maximum length in bytes the buffer can be resized to. Equal to
+  /// `byte_length()` unless the buffer is resizable.
+  pub fn max_byte_length(&self) -> usize {
+    unsafe { v8__ArrayBuffer__MaxByteLength(self) }
+  }
+
+  /// Returns true if JavaScript can change the length of this buffer with
+  /// `ArrayBuffer.prototype.resize()`.
+  pub fn is_resizable_by_user_javascript(&self) -> bool {
+    unsafe { v8__ArrayBuffer__IsResizableByUserJavaScript(self) }
+  }
+
   /// Returns true if this ArrayBuffer may be detached.
   pub fn is_detachable(&self) -> bool {
     unsafe { v8__ArrayBuffer__IsDetachable(self) }
   }
 
+  /// Returns true if this ArrayBuffer has been detached.
+  pub fn was_detached(&self) -> bool {
+    unsafe { v8__ArrayBuffer__WasDetached(self) }
+  }
+
   /// Detaches this ArrayBuffer and all its views (typed arrays).
   /// Detaching sets the byte length of the buffer and all typed arrays to zero,
   /// preventing JavaScript from ever accessing underlying backing store.
   /// ArrayBuffer should have been externalized and must be detachable.
+  ///
+  /// Non-detachable buffers are left untouched; use `was_detached()` to check
+  /// whether the buffer was actually detached. V8 10.0 has no detach keys,
+  /// so detaching can't be guarded by one.
   pub fn detach(&self) {
     // V8 terminates when the ArrayBuffer is not detachable. Non-detachable
     // buffers are buffers that are in use by WebAssembly or asm.js.
@@ -1,9 +1,14 @@
+use std::cell::Cell;
 use std::convert::TryInto;
 use std::ffi::c_void;
+use std::ptr::NonNull;
+use std::slice;
 
 use crate::support::int;
+use crate::support::SharedRef;
 use crate::ArrayBuffer;
 use crate::ArrayBufferView;
+use crate::BackingStore;
 use crate::HandleScope;
 use crate::Local;
 
@@ -39,6 +44,95 @@ impl ArrayBufferView {
     unsafe { v8__ArrayBufferView__ByteOffset(self) }
   }
 
+  /// Returns the backing store of the underlying ArrayBuffer.
+  pub fn get_backing_store(
+    &self,
+    scope: &mut HandleScope,
+  ) -> Option<SharedRef<BackingStore>> {
+    self.buffer(scope).map(|buffer| buffer.get_backing_store())
+  }
+
+  /// Returns a pointer to the first byte of this view, or `None` if the view
+  /// is empty or its buffer has been detached.
+  ///
+  /// The pointer is only valid as long as the underlying ArrayBuffer is
+  /// neither garbage collected nor detached, and it must not be used to
+  /// access more than `byte_length()` bytes.
+  pub fn data(&self, scope: &mut HandleScope) -> Option<NonNull<u8>> {
+    let byte_length = self.byte_length();
+    if byte_length == 0 {
+      return None;
+    }
+    let backing_store = self.get_backing_store(scope)?;
+    let data = backing_store.data()?.cast::<u8>();
+    Some(unsafe {
+      NonNull::new_unchecked(data.as_ptr().add(self.byte_offset()))
+    })
+  }
+
+  /// Calls `f` with a mutable slice of the bytes in this view, without
+  /// copying them. If the buffer has been detached, `f` gets an empty slice.
+  ///
+  /// The backing store is kept alive while `f` runs, so the memory stays
+  /// valid even if the buffer is detached in the meantime. Since `f` has no
+  /// access to a scope, no JavaScript can observe the contents while they
+  /// are borrowed.
+  ///
+  /// For a safe alternative, see `with_cell_slice()` or `copy_contents()`.
+  ///
+  /// # Safety
+  ///
+  /// The bytes of a view are not owned by Rust: JavaScript code, other views
+  /// and other `BackingStore` handles of the same buffer can all reach them,
+  /// so handing out `&mut [u8]` is only sound if none of them does while `f`
+  /// runs. The caller must ensure that the bytes of this view are not
+  /// accessed through any other path during that time, e.g. through the
+  /// `[Cell<u8>]` slice of a `BackingStore` of the same buffer, a pointer
+  /// returned by `data()`, or another view of the same memory. In particular,
+  /// the view must not be backed by a `SharedArrayBuffer`, whose contents can
+  /// be written by other threads at any time.
+  pub unsafe fn with_slice<R>(
+    &self,
+    scope: &mut HandleScope,
+    f: impl FnOnce(&mut [u8]) -> R,
+  ) -> R {
+    let byte_length = self.byte_length();
+    let backing_store = match self.get_backing_store(scope) {
+      Some(backing_store) if byte_length > 0 => backing_store,
+      _ => return f(&mut []),
+    };
+    match backing_store.data() {
+      Some(data) => {
+        let ptr = data.cast::<u8>().as_ptr().add(self.byte_offset());
+        f(slice::from_raw_parts_mut(ptr, byte_length))
+      }
+      None => f(&mut []),
+    }
+  }
+
+  /// Calls `f` with the bytes in this view as a slice of `Cell<u8>`, without
+  /// copying them. If the buffer has been detached, `f` gets an empty slice.
+  ///
+  /// Unlike `with_slice()`, this is safe: like the slice a `BackingStore`
+  /// dereferences to, the cells tolerate other handles to the same memory.
+  /// The backing store is kept alive while `f` runs.
+  pub fn with_cell_slice<R>(
+    &self,
+    scope: &mut HandleScope,
+    f: impl FnOnce(&[Cell<u8>]) -> R,
+  ) -> R {
+    let byte_length = self.byte_length();
+    let backing_store = match self.get_backing_store(scope) {
+      Some(backing_store) => backing_store,
+      None => return f(&[]),
+    };
+    let byte_offset = self.byte_offset();
+    match backing_store.get(byte_offset..byte_offset + byte_length) {
+      Some(cells) => f(cells),
+      None => f(&[]),
+    }
+  }
+
   /// Copy the contents of the ArrayBufferView's buffer to an embedder defined
   /// memory without additional overhead that calling ArrayBufferView::Buffer
   /// might incur.
@@ -4,7 +4,12 @@
 #include <iostream>
 
 #include "support.h"
+#include "v8/include/cppgc/allocation.h"
+#include "v8/include/cppgc/garbage-collected.h"
+#include "v8/include/cppgc/platform.h"
+#include "v8/include/cppgc/visitor.h"
 #include "v8/include/libplatform/libplatform.h"
+#include "v8/include/v8-cppgc.h"
 #include "v8/include/v8-fast-api-calls.h"
 #include "v8/include/v8-inspector.h"
 #include "v8/include/v8-platform.h"
@@ -15,6 +20,11 @@
 #include "v8/src/execution/isolate-utils-inl.h"
 #include "v8/src/execution/isolate-utils.h"
 #include "v8/src/flags/flags.h"
+#include "v8/src/heap/heap-inl.h"
+#include "v8/src/objects/backing-store.h"
+#include "v8/src/objects/hash-table-inl.h"
+#include "v8/src/objects/js-array-buffer-inl.h"
+#include "v8/src/objects/js-collection-inl.h"
 #include "v8/src/objects/objects-inl.h"
 #include "v8/src/objects/objects.h"
 #include "v8/src/objects/smi.h"
@@ -39,6 +49,9 @@ static_assert(sizeof(v8::PromiseRejectMessage) == sizeof(size_t) * 3,
 
 static_assert(sizeof(v8::Locker) == sizeof(size_t) * 2, "Locker size mismatch");
 
+static_assert(v8::Isolate::kUseCounterFeatureCount == 115,
+              "UseCounterFeature count mismatch");
+
 static_assert(sizeof(v8::ScriptCompiler::Source) <= sizeof(size_t) * 8,
               "Source size mismatch");
 
@@ -142,6 +155,23 @@ void v8__V8__SetEntropySource(v8::EntropySource callback) {
 
 const char* v8__V8__GetVersion() { return v8::V8::GetVersion(); }
 
+bool v8__V8__InitializeICU(const char* icu_data_file) {
+  return v8::V8::InitializeICU(icu_data_file);
+}
+
+bool v8__V8__InitializeICUDefaultLocation(const char* exec_path,
+                                          const char* icu_data_file) {
+  return v8::V8::InitializeICUDefaultLocation(exec_path, icu_data_file);
+}
+
+void v8__V8__InitializeExternalStartupData(const char* directory_path) {
+  v8::V8::InitializeExternalStartupData(directory_path);
+}
+
+void v8__V8__InitializeExternalStartupDataFromFile(const char* snapshot_blob) {
+  v8::V8::InitializeExternalStartupDataFromFile(snapshot_blob);
+}
+
 void v8__V8__InitializePlatform(v8::Platform* platform) {
   v8::V8::InitializePlatform(platform);
 }
@@ -162,6 +192,8 @@ void v8__Isolate__Enter(v8::Isolate* isolate) { isolate->Enter(); }
 
 void v8__Isolate__Exit(v8::Isolate* isolate) { isolate->Exit(); }
 
+bool v8__Isolate__IsInUse(v8::Isolate* isolate) { return isolate->IsInUse(); }
+
 void v8__Isolate__ClearKeptObjects(v8::Isolate* isolate) {
   isolate->ClearKeptObjects();
 }
@@ -170,6 +202,22 @@ void v8__Isolate__LowMemoryNotification(v8::Isolate* isolate) {
   isolate->LowMemoryNotification();
 }
 
+void v8__Isolate__InstallConditionalFeatures(v8::Isolate* isolate,
+                                             const v8::Context& context) {
+  isolate->InstallConditionalFeatures(ptr_to_local(&context));
+}
+
+void v8__Isolate__DateTimeConfigurationChangeNotification(
+    v8::Isolate* isolate, v8::Isolate::TimeZoneDetection time_zone_detection) {
+  static_assert(
+      0 == static_cast<int>(v8::Isolate::TimeZoneDetection::kSkip),
+      "v8::Isolate::TimeZoneDetection::kSkip mismatch");
+  static_assert(
+      1 == static_cast<int>(v8::Isolate::TimeZoneDetection::kRedetect),
+      "v8::Isolate::TimeZoneDetection::kRedetect mismatch");
+  isolate->DateTimeConfigurationChangeNotification(time_zone_detection);
+}
+
 void v8__Isolate__GetHeapStatistics(v8::Isolate* isolate,
                                     v8::HeapStatistics* s) {
   isolate->GetHeapStatistics(s);
@@ -217,6 +265,19 @@ void v8__Isolate__SetMicrotasksPolicy(v8::Isolate* isolate,
   isolate->SetMicrotasksPolicy(policy);
 }
 
+v8::MicrotasksScope* v8__MicrotasksScope__NEW(v8::Isolate* isolate,
+                                              v8::MicrotasksScope::Type type) {
+  static_assert(0 == static_cast<uint32_t>(
+                         v8::MicrotasksScope::Type::kRunMicrotasks),
+                "v8::MicrotasksScope::Type::kRunMicrotasks mismatch");
+  static_assert(1 == static_cast<uint32_t>(
+                         v8::MicrotasksScope::Type::kDoNotRunMicrotasks),
+                "v8::MicrotasksScope::Type::kDoNotRunMicrotasks mismatch");
+  return new v8::MicrotasksScope(isolate, type);
+}
+
+void v8__MicrotasksScope__DELETE(v8::MicrotasksScope* self) { delete self; }
+
 void v8__Isolate__PerformMicrotaskCheckpoint(v8::Isolate* isolate) {
   isolate->PerformMicrotaskCheckpoint();
 }
@@ -226,6 +287,11 @@ void v8__Isolate__EnqueueMicrotask(v8::Isolate* isolate,
   isolate->EnqueueMicrotask(ptr_to_local(&function));
 }
 
+void v8__Isolate__EnqueueMicrotask__with_callback(
+    v8::Isolate* isolate, v8::MicrotaskCallback callback, void* data) {
+  isolate->EnqueueMicrotask(callback, data);
+}
+
 void v8__Isolate__RequestInterrupt(v8::Isolate* isolate,
                                    v8::InterruptCallback callback, void* data) {
   isolate->RequestInterrupt(callback, data);
@@ -285,6 +351,13 @@ int64_t v8__Isolate__AdjustAmountOfExternalAllocatedMemory(
   return isolate->AdjustAmountOfExternalAllocatedMemory(change_in_bytes);
 }
 
+int64_t v8__Isolate__GetExternalMemory(const v8::Isolate* isolate) {
+  auto i_isolate = reinterpret_cast<const v8::internal::Isolate*>(isolate);
+  return const_cast<v8::internal::Isolate*>(i_isolate)
+      ->heap()
+      ->external_memory();
+}
+
 void v8__Isolate__SetOOMErrorHandler(v8::Isolate* isolate,
                                      v8::OOMErrorCallback callback) {
   isolate->SetOOMErrorHandler(callback);
@@ -316,6 +389,99 @@ void v8__Isolate__SetWasmStreamingCallback(v8::Isolate* isolate,
   isolate->SetWasmStreamingCallback(callback);
 }
 
+void v8__Isolate__SetModifyCodeGenerationFromStringsCallback(
+    v8::Isolate* isolate,
+    v8::ModifyCodeGenerationFromStringsCallback2 callback) {
+  // The Rust callback returns this struct by value as a C struct.
+  static_assert(sizeof(v8::ModifyCodeGenerationFromStringsResult) ==
+                    sizeof(size_t) * 2,
+                "ModifyCodeGenerationFromStringsResult size mismatch");
+  static_assert(offsetof(v8::ModifyCodeGenerationFromStringsResult,
+                         modified_source) == sizeof(size_t),
+                "ModifyCodeGenerationFromStringsResult layout mismatch");
+  isolate->SetModifyCodeGenerationFromStringsCallback(callback);
+}
+
+void v8__Isolate__SetAllowWasmCodeGenerationCallback(
+    v8::Isolate* isolate, v8::AllowWasmCodeGenerationCallback callback) {
+  isolate->SetAllowWasmCodeGenerationCallback(callback);
+}
+
+void v8__Isolate__SetWasmModuleCallback(v8::Isolate* isolate,
+                                        v8::ExtensionCallback callback) {
+  isolate->SetWasmModuleCallback(callback);
+}
+
+void v8__Isolate__SetWasmInstanceCallback(v8::Isolate* isolate,
+                                          v8::ExtensionCallback callback) {
+  isolate->SetWasmInstanceCallback(callback);
+}
+
+void v8__Isolate__SetAbortOnUncaughtExceptionCallback(
+    v8::Isolate* isolate,
+    v8::Isolate::AbortOnUncaughtExceptionCallback callback) {
+  isolate->SetAbortOnUncaughtExceptionCallback(callback);
+}
+
+void v8__Isolate__SetCounterFunction(v8::Isolate* isolate,
+                                     v8::CounterLookupCallback callback) {
+  isolate->SetCounterFunction(callback);
+}
+
+void v8__Isolate__SetCreateHistogramFunction(
+    v8::Isolate* isolate, v8::CreateHistogramCallback callback) {
+  isolate->SetCreateHistogramFunction(callback);
+}
+
+void v8__Isolate__SetAddHistogramSampleFunction(
+    v8::Isolate* isolate, v8::AddHistogramSampleCallback callback) {
+  isolate->SetAddHistogramSampleFunction(callback);
+}
+
+void v8__Isolate__SetUseCounterCallback(
+    v8::Isolate* isolate, v8::Isolate::UseCounterCallback callback) {
+  isolate->SetUseCounterCallback(callback);
+}
+
+using RustAddCrashKeyCallback = void (*)(v8::CrashKeyId id, const char* data,
+                                         size_t length);
+
+// V8's AddCrashKeyCallback does not take a data pointer, so the Rust callback
+// is stored in a process-wide variable.
+static RustAddCrashKeyCallback rust_add_crash_key_callback = nullptr;
+
+void v8__Isolate__SetAddCrashKeyCallback(v8::Isolate* isolate,
+                                         RustAddCrashKeyCallback callback) {
+  static_assert(sizeof(v8::CrashKeyId) == sizeof(int),
+                "v8::CrashKeyId size mismatch");
+  static_assert(0 == static_cast<int>(v8::CrashKeyId::kIsolateAddress),
+                "v8::CrashKeyId::kIsolateAddress mismatch");
+  static_assert(
+      1 == static_cast<int>(v8::CrashKeyId::kReadonlySpaceFirstPageAddress),
+      "v8::CrashKeyId::kReadonlySpaceFirstPageAddress mismatch");
+  static_assert(
+      2 == static_cast<int>(v8::CrashKeyId::kMapSpaceFirstPageAddress),
+      "v8::CrashKeyId::kMapSpaceFirstPageAddress mismatch");
+  static_assert(
+      3 == static_cast<int>(v8::CrashKeyId::kCodeSpaceFirstPageAddress),
+      "v8::CrashKeyId::kCodeSpaceFirstPageAddress mismatch");
+  static_assert(4 == static_cast<int>(v8::CrashKeyId::kDumpType),
+                "v8::CrashKeyId::kDumpType mismatch");
+  rust_add_crash_key_callback = callback;
+  isolate->SetAddCrashKeyCallback(
+      [](v8::CrashKeyId id, const std::string& value) {
+        rust_add_crash_key_callback(id, value.data(), value.size());
+      });
+}
+
+void v8__Isolate__AttachCppHeap(v8::Isolate* isolate, v8::CppHeap* heap) {
+  isolate->AttachCppHeap(heap);
+}
+
+void v8__Isolate__DetachCppHeap(v8::Isolate* isolate) {
+  isolate->DetachCppHeap();
+}
+
 bool v8__Isolate__HasPendingBackgroundTasks(v8::Isolate* isolate) {
   return isolate->HasPendingBackgroundTasks();
 }
@@ -367,6 +533,18 @@ void v8__Global__Reset(const v8::Data* data) {
   global.Reset();
 }
 
+void v8__Global__SetWrapperClassId(const v8::Data* data, uint16_t class_id) {
+  // Reinterpret the pointer to the global handle's storage cell as a
+  // `v8::Persistent`, without taking ownership of it.
+  auto persistent = reinterpret_cast<v8::Persistent<v8::Value>*>(&data);
+  persistent->SetWrapperClassId(class_id);
+}
+
+uint16_t v8__Global__WrapperClassId(const v8::Data* data) {
+  auto persistent = reinterpret_cast<v8::Persistent<v8::Value>*>(&data);
+  return persistent->WrapperClassId();
+}
+
 v8::Isolate* v8__WeakCallbackInfo__GetIsolate(
     const v8::WeakCallbackInfo<void>* self) {
   return self->GetIsolate();
@@ -416,6 +594,87 @@ const v8::ScriptCompiler::CachedData* v8__ScriptCompiler__Source__GetCachedData(
   return source->GetCachedData();
 }
 
+struct RustSourceStreamVtable {
+  size_t (*get_more_data)(void* stream, const uint8_t** src);
+  void (*drop)(void* stream);
+};
+
+class RustSourceStream : public v8::ScriptCompiler::ExternalSourceStream {
+ private:
+  void* stream;
+  const RustSourceStreamVtable* vtable;
+
+ public:
+  RustSourceStream(void* stream, const RustSourceStreamVtable* vtable) {
+    this->stream = stream;
+    this->vtable = vtable;
+  }
+
+  RustSourceStream(const RustSourceStream& that) = delete;
+  RustSourceStream(RustSourceStream&& that) = delete;
+  void operator=(const RustSourceStream& that) = delete;
+  void operator=(RustSourceStream&& that) = delete;
+
+  virtual ~RustSourceStream() { vtable->drop(stream); }
+
+  size_t GetMoreData(const uint8_t** src) final {
+    // V8 takes ownership of the chunk and frees it with delete[], while the
+    // Rust side keeps its own buffer, so hand V8 a copy.
+    const uint8_t* chunk;
+    size_t length = vtable->get_more_data(stream, &chunk);
+    if (length == 0) {
+      return 0;
+    }
+    uint8_t* copy = new uint8_t[length];
+    memcpy(copy, chunk, length);
+    *src = copy;
+    return length;
+  }
+};
+
+v8::ScriptCompiler::StreamedSource* v8__ScriptCompiler__StreamedSource__NEW(
+    void* stream, const RustSourceStreamVtable* vtable,
+    v8::ScriptCompiler::StreamedSource::Encoding encoding) {
+  static_assert(v8::ScriptCompiler::StreamedSource::ONE_BYTE == 0,
+                "ONE_BYTE mismatch");
+  static_assert(v8::ScriptCompiler::StreamedSource::TWO_BYTE == 1,
+                "TWO_BYTE mismatch");
+  static_assert(v8::ScriptCompiler::StreamedSource::UTF8 == 2,
+                "UTF8 mismatch");
+  static_assert(v8::ScriptCompiler::StreamedSource::WINDOWS_1252 == 3,
+                "WINDOWS_1252 mismatch");
+  return new v8::ScriptCompiler::StreamedSource(
+      std::make_unique<RustSourceStream>(stream, vtable), encoding);
+}
+
+void v8__ScriptCompiler__StreamedSource__DELETE(
+    v8::ScriptCompiler::StreamedSource* self) {
+  delete self;
+}
+
+v8::ScriptCompiler::ScriptStreamingTask* v8__ScriptCompiler__StartStreaming(
+    v8::Isolate* isolate, v8::ScriptCompiler::StreamedSource* source) {
+  return v8::ScriptCompiler::StartStreaming(isolate, source);
+}
+
+void v8__ScriptCompiler__ScriptStreamingTask__Run(
+    v8::ScriptCompiler::ScriptStreamingTask* self) {
+  self->Run();
+}
+
+void v8__ScriptCompiler__ScriptStreamingTask__DELETE(
+    v8::ScriptCompiler::ScriptStreamingTask* self) {
+  delete self;
+}
+
+const v8::Script* v8__ScriptCompiler__Compile__with_StreamedSource(
+    const v8::Context& context, v8::ScriptCompiler::StreamedSource* source,
+    const v8::String& full_source_string, const v8::ScriptOrigin& origin) {
+  return maybe_local_to_ptr(v8::ScriptCompiler::Compile(
+      ptr_to_local(&context), source, ptr_to_local(&full_source_string),
+      origin));
+}
+
 const v8::Module* v8__ScriptCompiler__CompileModule(
     v8::Isolate* isolate, v8::ScriptCompiler::Source* source,
     v8::ScriptCompiler::CompileOptions options,
@@ -846,6 +1105,10 @@ bool v8__ArrayBuffer__IsDetachable(const v8::ArrayBuffer& self) {
   return ptr_to_local(&self)->IsDetachable();
 }
 
+bool v8__ArrayBuffer__WasDetached(const v8::ArrayBuffer& self) {
+  return v8::Utils::OpenHandle(&self)->was_detached();
+}
+
 void* v8__BackingStore__Data(const v8::BackingStore& self) {
   return self.Data();
 }
@@ -858,6 +1121,19 @@ bool v8__BackingStore__IsShared(const v8::BackingStore& self) {
   return self.IsShared();
 }
 
+size_t v8__BackingStore__MaxByteLength(const v8::BackingStore& self) {
+  auto backing_store =
+      reinterpret_cast<const v8::internal::BackingStore*>(&self);
+  return backing_store->is_resizable() ? backing_store->max_byte_length()
+                                       : backing_store->byte_length();
+}
+
+bool v8__BackingStore__IsResizableByUserJavaScript(
+    const v8::BackingStore& self) {
+  return reinterpret_cast<const v8::internal::BackingStore*>(&self)
+      ->is_resizable();
+}
+
 void v8__BackingStore__DELETE(v8::BackingStore* self) { delete self; }
 
 two_pointers_t std__shared_ptr__v8__BackingStore__COPY(
@@ -939,6 +1215,13 @@ const v8::String* v8__String__NewFromOneByte(v8::Isolate* isolate,
       v8::String::NewFromOneByte(isolate, data, new_type, length));
 }
 
+const v8::String* v8__String__Concat(v8::Isolate* isolate,
+                                    const v8::String& left,
+                                    const v8::String& right) {
+  return local_to_ptr(
+      v8::String::Concat(isolate, ptr_to_local(&left), ptr_to_local(&right)));
+}
+
 const v8::String* v8__String__NewFromTwoByte(v8::Isolate* isolate,
                                              const uint16_t* data,
                                              v8::NewStringType new_type,
@@ -1080,6 +1363,15 @@ void v8__Template__Set(const v8::Template& self, const v8::Name& key,
   ptr_to_local(&self)->Set(ptr_to_local(&key), ptr_to_local(&value), attr);
 }
 
+void v8__Template__SetLazyDataProperty(const v8::Template& self,
+                                       const v8::Name& key,
+                                       v8::AccessorNameGetterCallback getter,
+                                       const v8::Value* data_or_null,
+                                       v8::PropertyAttribute attr) {
+  ptr_to_local(&self)->SetLazyDataProperty(
+      ptr_to_local(&key), getter, ptr_to_local(data_or_null), attr);
+}
+
 const v8::ObjectTemplate* v8__ObjectTemplate__New(
     v8::Isolate* isolate, const v8::FunctionTemplate& templ) {
   return local_to_ptr(v8::ObjectTemplate::New(isolate, ptr_to_local(&templ)));
@@ -1207,6 +1499,16 @@ MaybeBool v8__Object__SetAccessorWithSetter(
       ptr_to_local(&context), ptr_to_local(&key), getter, setter));
 }
 
+MaybeBool v8__Object__SetAccessorWithConfiguration(
+    const v8::Object& self, const v8::Context& context, const v8::Name& key,
+    v8::AccessorNameGetterCallback getter,
+    v8::AccessorNameSetterCallback setter, const v8::Value* data,
+    v8::PropertyAttribute attr) {
+  return maybe_to_maybe_bool(ptr_to_local(&self)->SetAccessor(
+      ptr_to_local(&context), ptr_to_local(&key), getter, setter,
+      ptr_to_maybe_local(data), v8::AccessControl::DEFAULT, attr));
+}
+
 v8::Isolate* v8__Object__GetIsolate(const v8::Object& self) {
   return ptr_to_local(&self)->GetIsolate();
 }
@@ -1250,6 +1552,26 @@ MaybeBool v8__Object__HasOwnProperty(const v8::Object& self,
       ptr_to_local(&context), ptr_to_local(&key)));
 }
 
+void v8__Object__GetPropertyAttributes(const v8::Object& self,
+                                       const v8::Context& context,
+                                       const v8::Value& key,
+                                       v8::Maybe<v8::PropertyAttribute>* out) {
+  *out = ptr_to_local(&self)->GetPropertyAttributes(ptr_to_local(&context),
+                                                     ptr_to_local(&key));
+}
+
+const v8::Value* v8__Object__GetOwnPropertyDescriptor(
+    const v8::Object& self, const v8::Context& context, const v8::Name& key) {
+  return maybe_local_to_ptr(ptr_to_local(&self)->GetOwnPropertyDescriptor(
+      ptr_to_local(&context), ptr_to_local(&key)));
+}
+
+const v8::String* v8__Object__ObjectProtoToString(
+    const v8::Object& self, const v8::Context& context) {
+  return maybe_local_to_ptr(
+      ptr_to_local(&self)->ObjectProtoToString(ptr_to_local(&context)));
+}
+
 MaybeBool v8__Object__Delete(const v8::Object& self, const v8::Context& context,
                              const v8::Value& key) {
   return maybe_to_maybe_bool(
@@ -1288,6 +1610,16 @@ void v8__Object__SetInternalField(const v8::Object& self, int index,
   ptr_to_local(&self)->SetInternalField(index, ptr_to_local(&value));
 }
 
+void* v8__Object__GetAlignedPointerFromInternalField(const v8::Object& self,
+                                                     int index) {
+  return ptr_to_local(&self)->GetAlignedPointerFromInternalField(index);
+}
+
+void v8__Object__SetAlignedPointerInInternalField(const v8::Object& self,
+                                                  int index, void* value) {
+  ptr_to_local(&self)->SetAlignedPointerInInternalField(index, value);
+}
+
 const v8::Value* v8__Object__GetPrivate(const v8::Object& self,
                                         const v8::Context& context,
                                         const v8::Private& key) {
@@ -1317,6 +1649,46 @@ MaybeBool v8__Object__HasPrivate(const v8::Object& self,
       ptr_to_local(&context), ptr_to_local(&key)));
 }
 
+const v8::Value* v8__Object__GetRealNamedProperty(const v8::Object& self,
+                                                  const v8::Context& context,
+                                                  const v8::Name& key) {
+  return maybe_local_to_ptr(ptr_to_local(&self)->GetRealNamedProperty(
+      ptr_to_local(&context), ptr_to_local(&key)));
+}
+
+void v8__Object__GetRealNamedPropertyAttributes(
+    const v8::Object& self, const v8::Context& context, const v8::Name& key,
+    v8::Maybe<v8::PropertyAttribute>* out) {
+  *out = ptr_to_local(&self)->GetRealNamedPropertyAttributes(
+      ptr_to_local(&context), ptr_to_local(&key));
+}
+
+MaybeBool v8__Object__HasRealNamedProperty(const v8::Object& self,
+                                           const v8::Context& context,
+                                           const v8::Name& key) {
+  return maybe_to_maybe_bool(ptr_to_local(&self)->HasRealNamedProperty(
+      ptr_to_local(&context), ptr_to_local(&key)));
+}
+
+MaybeBool v8__Object__HasRealIndexedProperty(const v8::Object& self,
+                                             const v8::Context& context,
+                                             uint32_t index) {
+  return maybe_to_maybe_bool(ptr_to_local(&self)->HasRealIndexedProperty(
+      ptr_to_local(&context), index));
+}
+
+MaybeBool v8__Object__HasRealNamedCallbackProperty(const v8::Object& self,
+                                                   const v8::Context& context,
+                                                   const v8::Name& key) {
+  return maybe_to_maybe_bool(ptr_to_local(&self)->HasRealNamedCallbackProperty(
+      ptr_to_local(&context), ptr_to_local(&key)));
+}
+
+const v8::Array* v8__Object__PreviewEntries(const v8::Object& self,
+                                            bool* is_key_value) {
+  return maybe_local_to_ptr(ptr_to_local(&self)->PreviewEntries(is_key_value));
+}
+
 const v8::Array* v8__Array__New(v8::Isolate* isolate, int length) {
   return local_to_ptr(v8::Array::New(isolate, length));
 }
@@ -1391,6 +1763,110 @@ const v8::Array* v8__Map__As__Array(const v8::Map& self) {
   return local_to_ptr(self.AsArray());
 }
 
+// V8 has no public API for WeakMap and WeakSet, so these operate on the
+// internal objects directly. Keys are always JS objects.
+static bool WeakCollectionLookup(const v8::Object& self, v8::Isolate* isolate,
+                                 const v8::Object& key,
+                                 const v8::Value** value) {
+  auto i_isolate = reinterpret_cast<v8::internal::Isolate*>(isolate);
+  auto collection =
+      v8::internal::JSWeakCollection::cast(*v8::Utils::OpenHandle(&self));
+  auto table = v8::internal::EphemeronHashTable::cast(collection.table());
+  v8::internal::Object result = table.Lookup(v8::Utils::OpenHandle(&key));
+  if (result.IsTheHole(i_isolate)) {
+    return false;
+  }
+  *value = local_to_ptr(
+      v8::Utils::ToLocal(v8::internal::handle(result, i_isolate)));
+  return true;
+}
+
+static void WeakCollectionSet(const v8::Object& self, v8::Isolate* isolate,
+                              const v8::Object& key,
+                              v8::Local<v8::Value> value) {
+  auto i_isolate = reinterpret_cast<v8::internal::Isolate*>(isolate);
+  auto collection = v8::internal::Handle<v8::internal::JSWeakCollection>::cast(
+      v8::Utils::OpenHandle(&self));
+  auto key_obj = v8::Utils::OpenHandle(&key);
+  int32_t hash = key_obj->GetOrCreateHash(i_isolate).value();
+  v8::internal::JSWeakCollection::Set(collection, key_obj,
+                                      v8::Utils::OpenHandle(*value), hash);
+}
+
+static bool WeakCollectionDelete(const v8::Object& self, v8::Isolate* isolate,
+                                 const v8::Object& key) {
+  auto i_isolate = reinterpret_cast<v8::internal::Isolate*>(isolate);
+  auto collection = v8::internal::Handle<v8::internal::JSWeakCollection>::cast(
+      v8::Utils::OpenHandle(&self));
+  auto key_obj = v8::Utils::OpenHandle(&key);
+  // An object whose hash was never computed can't be in any collection.
+  v8::internal::Object hash = key_obj->GetHash();
+  if (hash.IsUndefined(i_isolate)) {
+    return false;
+  }
+  return v8::internal::JSWeakCollection::Delete(
+      collection, key_obj, v8::internal::Smi::ToInt(hash));
+}
+
+const v8::Object* v8__WeakMap__New(v8::Isolate* isolate) {
+  auto i_isolate = reinterpret_cast<v8::internal::Isolate*>(isolate);
+  v8::internal::Handle<v8::internal::JSObject> map =
+      i_isolate->factory()->NewJSObject(i_isolate->js_weak_map_fun());
+  v8::internal::JSWeakCollection::Initialize(
+      v8::internal::Handle<v8::internal::JSWeakMap>::cast(map), i_isolate);
+  return local_to_ptr(v8::Utils::ToLocal(map));
+}
+
+const v8::Value* v8__WeakMap__Get(const v8::Object& self, v8::Isolate* isolate,
+                                  const v8::Object& key) {
+  const v8::Value* value;
+  if (!WeakCollectionLookup(self, isolate, key, &value)) {
+    return local_to_ptr(v8::Undefined(isolate).As<v8::Value>());
+  }
+  return value;
+}
+
+void v8__WeakMap__Set(const v8::Object& self, v8::Isolate* isolate,
+                      const v8::Object& key, const v8::Value& value) {
+  WeakCollectionSet(self, isolate, key, ptr_to_local(&value));
+}
+
+bool v8__WeakMap__Has(const v8::Object& self, v8::Isolate* isolate,
+                      const v8::Object& key) {
+  const v8::Value* value;
+  return WeakCollectionLookup(self, isolate, key, &value);
+}
+
+bool v8__WeakMap__Delete(const v8::Object& self, v8::Isolate* isolate,
+                         const v8::Object& key) {
+  return WeakCollectionDelete(self, isolate, key);
+}
+
+const v8::Object* v8__WeakSet__New(v8::Isolate* isolate) {
+  auto i_isolate = reinterpret_cast<v8::internal::Isolate*>(isolate);
+  v8::internal::Handle<v8::internal::JSObject> set =
+      i_isolate->factory()->NewJSObject(i_isolate->js_weak_set_fun());
+  v8::internal::JSWeakCollection::Initialize(
+      v8::internal::Handle<v8::internal::JSWeakSet>::cast(set), i_isolate);
+  return local_to_ptr(v8::Utils::ToLocal(set));
+}
+
+void v8__WeakSet__Add(const v8::Object& self, v8::Isolate* isolate,
+                      const v8::Object& key) {
+  WeakCollectionSet(self, isolate, key, v8::True(isolate));
+}
+
+bool v8__WeakSet__Has(const v8::Object& self, v8::Isolate* isolate,
+                      const v8::Object& key) {
+  const v8::Value* value;
+  return WeakCollectionLookup(self, isolate, key, &value);
+}
+
+bool v8__WeakSet__Delete(const v8::Object& self, v8::Isolate* isolate,
+                         const v8::Object& key) {
+  return WeakCollectionDelete(self, isolate, key);
+}
+
 const v8::Number* v8__Number__New(v8::Isolate* isolate, double value) {
   return *v8::Number::New(isolate, value);
 }
@@ -1531,6 +2007,48 @@ size_t v8__ArrayBuffer__ByteLength(const v8::ArrayBuffer& self) {
   return self.ByteLength();
 }
 
+size_t v8__ArrayBuffer__MaxByteLength(const v8::ArrayBuffer& self) {
+  auto buffer = v8::Utils::OpenHandle(&self);
+  return buffer->is_resizable() ? buffer->max_byte_length()
+                                : buffer->byte_length();
+}
+
+bool v8__ArrayBuffer__IsResizableByUserJavaScript(
+    const v8::ArrayBuffer& self) {
+  return v8::Utils::OpenHandle(&self)->is_resizable();
+}
+
+const v8::ArrayBuffer* v8__ArrayBuffer__NewResizable(
+    const v8::Context& context, size_t byte_length, size_t max_byte_length) {
+  // There is no embedder API to allocate a resizable buffer, so call the
+  // intrinsic %ArrayBuffer% constructor with a `maxByteLength` option.
+  auto ctx = ptr_to_local(&context);
+  v8::Isolate* isolate = ctx->GetIsolate();
+  auto i_isolate = reinterpret_cast<v8::internal::Isolate*>(isolate);
+  v8::Local<v8::Function> constructor =
+      v8::Utils::ToLocal(i_isolate->array_buffer_fun());
+  v8::Local<v8::Object> options = v8::Object::New(isolate);
+  if (options
+          ->Set(ctx, v8::String::NewFromUtf8Literal(isolate, "maxByteLength"),
+                v8::Number::New(isolate, static_cast<double>(max_byte_length)))
+          .IsNothing()) {
+    return nullptr;
+  }
+  v8::Local<v8::Value> argv[] = {
+      v8::Number::New(isolate, static_cast<double>(byte_length)), options};
+  v8::Local<v8::Object> buffer;
+  if (!constructor->NewInstance(ctx, 2, argv).ToLocal(&buffer)) {
+    return nullptr;
+  }
+  // Without --harmony-rab-gsab, the options are ignored and a fixed-length
+  // buffer is created.
+  auto array_buffer = buffer.As<v8::ArrayBuffer>();
+  if (!v8::Utils::OpenHandle(*array_buffer)->is_resizable()) {
+    return nullptr;
+  }
+  return local_to_ptr(array_buffer);
+}
+
 struct InternalFieldData {
   uint32_t data;
 };
@@ -1578,6 +2096,15 @@ const v8::Object* v8__Context__Global(const v8::Context& self) {
   return local_to_ptr(ptr_to_local(&self)->Global());
 }
 
+void v8__Context__DetachGlobal(const v8::Context& self) {
+  ptr_to_local(&self)->DetachGlobal();
+}
+
+const v8::Object* v8__Context__GetExtrasBindingObject(
+    const v8::Context& self) {
+  return local_to_ptr(ptr_to_local(&self)->GetExtrasBindingObject());
+}
+
 const v8::Data* v8__Context__GetDataFromSnapshotOnce(v8::Context& self,
                                                      size_t index) {
   return maybe_local_to_ptr(
@@ -1596,6 +2123,41 @@ void v8__Context__SetPromiseHooks(v8::Context& self,
     ptr_to_local(&resolve_hook));
 }
 
+void v8__Context__SetSecurityToken(const v8::Context& self,
+                                   const v8::Value& token) {
+  ptr_to_local(&self)->SetSecurityToken(ptr_to_local(&token));
+}
+
+void v8__Context__UseDefaultSecurityToken(const v8::Context& self) {
+  ptr_to_local(&self)->UseDefaultSecurityToken();
+}
+
+const v8::Value* v8__Context__GetSecurityToken(const v8::Context& self) {
+  return local_to_ptr(ptr_to_local(&self)->GetSecurityToken());
+}
+
+void v8__Context__SetContinuationPreservedEmbedderData(
+    const v8::Context& self, const v8::Value& data) {
+  ptr_to_local(&self)->SetContinuationPreservedEmbedderData(
+      ptr_to_local(&data));
+}
+
+const v8::Value* v8__Context__GetContinuationPreservedEmbedderData(
+    const v8::Context& self) {
+  return local_to_ptr(
+      ptr_to_local(&self)->GetContinuationPreservedEmbedderData());
+}
+
+void v8__Context__AllowCodeGenerationFromStrings(const v8::Context& self,
+                                                 bool allow) {
+  ptr_to_local(&self)->AllowCodeGenerationFromStrings(allow);
+}
+
+bool v8__Context__IsCodeGenerationFromStringsAllowed(
+    const v8::Context& self) {
+  return ptr_to_local(&self)->IsCodeGenerationFromStringsAllowed();
+}
+
 const v8::String* v8__Message__Get(const v8::Message& self) {
   return local_to_ptr(self.Get());
 }
@@ -1653,6 +2215,10 @@ bool v8__Message__IsSharedCrossOrigin(const v8::Message& self) {
 
 bool v8__Message__IsOpaque(const v8::Message& self) { return self.IsOpaque(); }
 
+int v8__Message__GetScriptId(const v8::Message& self) {
+  return self.GetScriptOrigin().ScriptId();
+}
+
 v8::Isolate* v8__Message__GetIsolate(const v8::Message& self) {
   return self.GetIsolate();
 }
@@ -1714,6 +2280,14 @@ const v8::Object* v8__Function__NewInstance(const v8::Function& self,
       ptr_to_local(&context), argc, const_ptr_array_to_local_array(argv)));
 }
 
+const v8::Object* v8__Function__NewInstanceWithSideEffectType(
+    const v8::Function& self, const v8::Context& context, int argc,
+    const v8::Value* const argv[], v8::SideEffectType side_effect_type) {
+  return maybe_local_to_ptr(ptr_to_local(&self)->NewInstanceWithSideEffectType(
+      ptr_to_local(&context), argc, const_ptr_array_to_local_array(argv),
+      side_effect_type));
+}
+
 const v8::Value* v8__Function__GetName(const v8::Function& self) {
   return local_to_ptr(self.GetName());
 }
@@ -1722,6 +2296,14 @@ void v8__Function__SetName(const v8::Function& self, const v8::String& name) {
   return ptr_to_local(&self)->SetName(ptr_to_local(&name));
 }
 
+const v8::Value* v8__Function__GetInferredName(const v8::Function& self) {
+  return local_to_ptr(self.GetInferredName());
+}
+
+const v8::Value* v8__Function__GetDebugName(const v8::Function& self) {
+  return local_to_ptr(self.GetDebugName());
+}
+
 int v8__Function__GetScriptColumnNumber(const v8::Function& self) {
   return ptr_to_local(&self)->GetScriptColumnNumber();
 }
@@ -1771,6 +2353,11 @@ void v8__FunctionTemplate__RemovePrototype(const v8::FunctionTemplate& self) {
   ptr_to_local(&self)->RemovePrototype();
 }
 
+void v8__FunctionTemplate__SetAcceptAnyReceiver(
+    const v8::FunctionTemplate& self, bool value) {
+  ptr_to_local(&self)->SetAcceptAnyReceiver(value);
+}
+
 const v8::ObjectTemplate* v8__FunctionTemplate__PrototypeTemplate(
     const v8::FunctionTemplate& self) {
   return local_to_ptr(ptr_to_local(&self)->PrototypeTemplate());
@@ -1811,6 +2398,11 @@ const v8::Value* v8__FunctionCallbackInfo__NewTarget(
   return local_to_ptr(self.NewTarget());
 }
 
+bool v8__FunctionCallbackInfo__IsConstructCall(
+    const v8::FunctionCallbackInfo<v8::Value>& self) {
+  return self.IsConstructCall();
+}
+
 void v8__ReturnValue__Set(v8::ReturnValue<v8::Value>* self,
                           const v8::Value& value) {
   self->Set(ptr_to_local(&value));
@@ -1945,6 +2537,7 @@ V(Float32Array)
 V(Float64Array)
 V(BigUint64Array)
 V(BigInt64Array)
+V(DataView)
 #undef V
 
 const v8::Script* v8__Script__Compile(const v8::Context& context,
@@ -1965,6 +2558,15 @@ const v8::Script* v8__UnboundScript__BindToCurrentContext(
   return local_to_ptr(ptr_to_local(&unbound_script)->BindToCurrentContext());
 }
 
+int v8__UnboundScript__GetId(const v8::UnboundScript& unbound_script) {
+  return ptr_to_local(&unbound_script)->GetId();
+}
+
+const v8::Value* v8__UnboundScript__GetScriptName(
+    const v8::UnboundScript& unbound_script) {
+  return local_to_ptr(ptr_to_local(&unbound_script)->GetScriptName());
+}
+
 v8::ScriptCompiler::CachedData* v8__UnboundScript__CreateCodeCache(
     const v8::UnboundScript& unbound_script) {
   return v8::ScriptCompiler::CreateCodeCache(ptr_to_local(&unbound_script));
@@ -1991,11 +2593,12 @@ void v8__ScriptOrigin__CONSTRUCT(
     const v8::Value& resource_name, int resource_line_offset,
     int resource_column_offset, bool resource_is_shared_cross_origin,
     int script_id, const v8::Value& source_map_url, bool resource_is_opaque,
-    bool is_wasm, bool is_module) {
+    bool is_wasm, bool is_module, const v8::Data* host_defined_options) {
   construct_in_place<v8::ScriptOrigin>(
       buf, isolate, ptr_to_local(&resource_name), resource_line_offset,
       resource_column_offset, resource_is_shared_cross_origin, script_id,
-      ptr_to_local(&source_map_url), resource_is_opaque, is_wasm, is_module);
+      ptr_to_local(&source_map_url), resource_is_opaque, is_wasm, is_module,
+      ptr_to_local(host_defined_options));
 }
 
 const v8::Value* v8__ScriptOrModule__GetResourceName(
@@ -2087,6 +2690,10 @@ bool v8__Promise__HasHandler(const v8::Promise& self) {
   return ptr_to_local(&self)->HasHandler();
 }
 
+void v8__Promise__MarkAsHandled(const v8::Promise& self) {
+  ptr_to_local(&self)->MarkAsHandled();
+}
+
 const v8::Value* v8__Promise__Result(const v8::Promise& self) {
   return local_to_ptr(ptr_to_local(&self)->Result());
 }
@@ -2144,6 +2751,16 @@ const v8::Object* v8__PropertyCallbackInfo__This(
   return local_to_ptr(self.This());
 }
 
+const v8::Value* v8__PropertyCallbackInfo__Data(
+    const v8::PropertyCallbackInfo<v8::Value>& self) {
+  return local_to_ptr(self.Data());
+}
+
+bool v8__PropertyCallbackInfo__ShouldThrowOnError(
+    const v8::PropertyCallbackInfo<v8::Value>& self) {
+  return self.ShouldThrowOnError();
+}
+
 const v8::Proxy* v8__Proxy__New(const v8::Context& context,
                                 const v8::Object& target,
                                 const v8::Object& handler) {
@@ -2166,8 +2783,10 @@ bool v8__Proxy__IsRevoked(const v8::Proxy& self) {
 void v8__Proxy__Revoke(const v8::Proxy& self) { ptr_to_local(&self)->Revoke(); }
 
 void v8__SnapshotCreator__CONSTRUCT(uninit_t<v8::SnapshotCreator>* buf,
-                                    const intptr_t* external_references) {
-  construct_in_place<v8::SnapshotCreator>(buf, external_references);
+                                    const intptr_t* external_references,
+                                    const v8::StartupData* existing_blob) {
+  construct_in_place<v8::SnapshotCreator>(
+      buf, external_references, const_cast<v8::StartupData*>(existing_blob));
 }
 
 void v8__SnapshotCreator__DESTRUCT(v8::SnapshotCreator* self) {
@@ -2609,6 +3228,39 @@ void v8__WasmStreaming__SetUrl(WasmStreamingSharedPtr* self, const char* url,
   self->inner->SetUrl(url, len);
 }
 
+v8::SnapshotObjectId v8__HeapProfiler__GetObjectId(v8::Isolate* isolate,
+                                                   const v8::Value& value) {
+  return isolate->GetHeapProfiler()->GetObjectId(ptr_to_local(&value));
+}
+
+const v8::Value* v8__HeapProfiler__FindObjectById(v8::Isolate* isolate,
+                                                  v8::SnapshotObjectId id) {
+  return local_to_ptr(isolate->GetHeapProfiler()->FindObjectById(id));
+}
+
+using PersistentHandleVisitorCallback = void (*)(void*, const v8::Value*,
+                                                uint16_t);
+
+void v8__Isolate__VisitHandlesWithClassIds(
+    v8::Isolate* isolate, PersistentHandleVisitorCallback callback,
+    void* arg) {
+  struct Visitor : public v8::PersistentHandleVisitor {
+    Visitor(PersistentHandleVisitorCallback callback, void* arg)
+        : callback_(callback), arg_(arg) {}
+    void VisitPersistentHandle(v8::Persistent<v8::Value>* value,
+                               uint16_t class_id) override {
+      // A persistent handle is a pointer to its storage cell, just like a
+      // local handle, so it can be passed to Rust as a `Local`.
+      callback_(arg_, *reinterpret_cast<const v8::Value**>(value), class_id);
+    }
+    PersistentHandleVisitorCallback const callback_;
+    void* const arg_;
+  };
+
+  Visitor visitor(callback, arg);
+  isolate->VisitHandlesWithClassIds(&visitor);
+}
+
 using HeapSnapshotCallback = bool (*)(void*, const char*, size_t);
 
 void v8__HeapProfiler__TakeHeapSnapshot(v8::Isolate* isolate,
@@ -2822,6 +3474,11 @@ void v8__ValueSerializer__TransferArrayBuffer(
   self->TransferArrayBuffer(transfer_id, array_buffer);
 }
 
+void v8__ValueSerializer__SetTreatArrayBufferViewsAsHostObjects(
+    v8::ValueSerializer* self, bool mode) {
+  self->SetTreatArrayBufferViewsAsHostObjects(mode);
+}
+
 void v8__ValueSerializer__WriteUint32(v8::ValueSerializer* self,
                                       uint32_t value) {
   self->WriteUint32(value);
@@ -2987,3 +3644,65 @@ void v8__CompiledWasmModule__DELETE(v8::CompiledWasmModule* self) {
   delete self;
 }
 }  // extern "C"
+
+// cppgc
+
+extern "C" {
+using RustTraceFn = void (*)(void* obj, cppgc::Visitor*);
+using RustDestroyFn = void (*)(void* obj);
+
+class RustObj final : public cppgc::GarbageCollected<RustObj> {
+ public:
+  explicit RustObj(void* obj, RustTraceFn trace, RustDestroyFn destroy)
+      : obj_(obj), trace_(trace), destroy_(destroy) {}
+
+  ~RustObj() { destroy_(obj_); }
+
+  void Trace(cppgc::Visitor* visitor) const { trace_(obj_, visitor); }
+
+  void* obj() const { return obj_; }
+
+ private:
+  void* obj_;
+  RustTraceFn trace_;
+  RustDestroyFn destroy_;
+};
+
+void cppgc__initialize_process(v8::Platform* platform) {
+  cppgc::InitializeProcess(platform->GetPageAllocator());
+}
+
+void cppgc__shutdown_process() { cppgc::ShutdownProcess(); }
+
+v8::CppHeap* cppgc__heap__create(v8::Platform* platform,
+                                 int wrappable_type_index,
+                                 int wrappable_instance_index,
+                                 uint16_t embedder_id) {
+  v8::CppHeapCreateParams params{
+      {},
+      v8::WrapperDescriptor(wrappable_type_index, wrappable_instance_index,
+                            embedder_id),
+  };
+  return v8::CppHeap::Create(platform, params).release();
+}
+
+void cppgc__heap__DELETE(v8::CppHeap* self) { delete self; }
+
+void cppgc__heap__collect_garbage_for_testing(
+    v8::CppHeap* self, cppgc::EmbedderStackState stack_state) {
+  self->CollectGarbageForTesting(stack_state);
+}
+
+RustObj* cppgc__make_garbage_collectable(v8::CppHeap* heap, void* obj,
+                                         RustTraceFn trace,
+                                         RustDestroyFn destroy) {
+  return cppgc::MakeGarbageCollected<RustObj>(heap->GetAllocationHandle(),
+                                              obj, trace, destroy);
+}
+
+void* cppgc__RustObj__get(const RustObj* self) { return self->obj(); }
+
+void cppgc__visitor__trace(cppgc::Visitor* visitor, RustObj* member) {
+  visitor->Trace(cppgc::Member<RustObj>(member));
+}
+}  // extern "C"
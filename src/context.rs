@@ -7,6 +7,8 @@ use crate::Local;
 use crate::Object;
 use crate::ObjectTemplate;
 use crate::Value;
+use std::ops::Deref;
+use std::ops::DerefMut;
 use std::ptr::null;
 
 extern "C" {
@@ -16,6 +18,9 @@ extern "C" {
     global_object: *const Value,
   ) -> *const Context;
   fn v8__Context__Global(this: *const Context) -> *const Object;
+  fn v8__Context__DetachGlobal(this: *const Context);
+  fn v8__Context__GetExtrasBindingObject(this: *const Context)
+    -> *const Object;
   fn v8__Context__SetPromiseHooks(
     this: *const Context,
     init_hook: *const Function,
@@ -23,6 +28,41 @@ extern "C" {
     after_hook: *const Function,
     resolve_hook: *const Function,
   );
+  fn v8__Context__SetSecurityToken(this: *const Context, token: *const Value);
+  fn v8__Context__UseDefaultSecurityToken(this: *const Context);
+  fn v8__Context__GetSecurityToken(this: *const Context) -> *const Value;
+  fn v8__Context__SetContinuationPreservedEmbedderData(
+    this: *const Context,
+    data: *const Value,
+  );
+  fn v8__Context__GetContinuationPreservedEmbedderData(
+    this: *const Context,
+  ) -> *const Value;
+  fn v8__Context__AllowCodeGenerationFromStrings(
+    this: *const Context,
+    allow: bool,
+  );
+  fn v8__Context__IsCodeGenerationFromStringsAllowed(
+    this: *const Context,
+  ) -> bool;
+}
+
+/// Optional arguments for `Context::new_with_options()`.
+///
+/// Unlike `v8::Context::New()`, there is no option to pass a per-context
+/// microtask queue: the crate doesn't wrap `v8::MicrotaskQueue`, so every
+/// context uses the isolate's default queue.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ContextOptions<'s> {
+  /// An optional object template from which the global object for the newly
+  /// created context will be created.
+  pub global_template: Option<Local<'s, ObjectTemplate>>,
+  /// An optional global object to be reused for the newly created context.
+  /// This global object must have been created by a previous call to
+  /// `Context::new_with_options()` with the same global template. The state
+  /// of the global object will be completely reset and only object identity
+  /// will remain.
+  pub global_object: Option<Local<'s, Value>>,
 }
 
 impl Context {
@@ -50,6 +90,24 @@ impl Context {
     .unwrap()
   }
 
+  /// Creates a new context with the global template and/or global object
+  /// given in `options`.
+  pub fn new_with_options<'s>(
+    scope: &mut HandleScope<'s, ()>,
+    options: ContextOptions,
+  ) -> Local<'s, Context> {
+    unsafe {
+      scope.cast_local(|sd| {
+        v8__Context__New(
+          sd.get_isolate_ptr(),
+          options.global_template.map_or_else(null, |t| &*t),
+          options.global_object.map_or_else(null, |o| &*o),
+        )
+      })
+    }
+    .unwrap()
+  }
+
   /// Returns the global proxy object.
   ///
   /// Global proxy object is a thin wrapper whose prototype points to actual
@@ -67,6 +125,36 @@ impl Context {
     unsafe { scope.cast_local(|_| v8__Context__Global(self)) }.unwrap()
   }
 
+  /// Detaches the global proxy object (see `global()`) from this context, so
+  /// it can be reused to create a new context by passing it as
+  /// `ContextOptions::global_object` to `Context::new_with_options()`.
+  ///
+  /// Only the identity of the global proxy survives: the new context gets a
+  /// fresh global object with fresh built-ins, and properties that scripts
+  /// defined on the old global are not carried over. Nothing else of this
+  /// context is reused either, so embedder data, the security token and
+  /// anything installed through templates or by the embedder has to be set up
+  /// again on the new context. Host objects that hold on to the old global
+  /// proxy keep working with the new context.
+  ///
+  /// After this call, code running in this context can still access its own
+  /// global object, but `globalThis` and the global proxy no longer lead to
+  /// it.
+  pub fn detach_global(&self) {
+    unsafe { v8__Context__DetachGlobal(self) }
+  }
+
+  /// Returns an object that is only reachable from native code and from
+  /// V8's extras scripts. Embedders can use it to pass privileged helpers
+  /// that must not be exposed on the global object.
+  pub fn get_extras_binding_object<'s>(
+    &self,
+    scope: &mut HandleScope<'s, ()>,
+  ) -> Local<'s, Object> {
+    unsafe { scope.cast_local(|_| v8__Context__GetExtrasBindingObject(self)) }
+      .unwrap()
+  }
+
   pub fn set_promise_hooks(
     &self,
     init_hook: Local<Function>,
@@ -84,4 +172,115 @@ impl Context {
       )
     }
   }
+
+  /// Sets the security token for the context. To access an object in
+  /// another context, the security tokens must match.
+  pub fn set_security_token(&self, token: Local<Value>) {
+    unsafe { v8__Context__SetSecurityToken(self, &*token) }
+  }
+
+  /// Restores the security token to the default value.
+  pub fn use_default_security_token(&self) {
+    unsafe { v8__Context__UseDefaultSecurityToken(self) }
+  }
+
+  /// Returns the security token of this context.
+  pub fn get_security_token<'s>(
+    &self,
+    scope: &mut HandleScope<'s, ()>,
+  ) -> Local<'s, Value> {
+    unsafe { scope.cast_local(|_| v8__Context__GetSecurityToken(self)) }
+      .unwrap()
+  }
+
+  /// Sets the continuation-preserved embedder data. The data is captured
+  /// when a promise reaction is scheduled (e.g. by `then()` or `await`) and
+  /// restored while the reaction runs, so it flows across await points even
+  /// if it is changed in the meantime. This is the building block for
+  /// tracking an async context, like Node's `AsyncLocalStorage`.
+  pub fn set_continuation_preserved_embedder_data(&self, data: Local<Value>) {
+    unsafe { v8__Context__SetContinuationPreservedEmbedderData(self, &*data) }
+  }
+
+  /// Returns the continuation-preserved embedder data, or `undefined` if
+  /// none has been set.
+  pub fn get_continuation_preserved_embedder_data<'s>(
+    &self,
+    scope: &mut HandleScope<'s, ()>,
+  ) -> Local<'s, Value> {
+    unsafe {
+      scope
+        .cast_local(|_| v8__Context__GetContinuationPreservedEmbedderData(self))
+    }
+    .unwrap()
+  }
+
+  /// Control whether code generation from strings is allowed. Calling
+  /// this method with false will disable 'eval' and the 'Function'
+  /// constructor for code running in this context. If 'eval' or the
+  /// 'Function' constructor are used an exception will be thrown.
+  pub fn allow_code_generation_from_strings(&self, allow: bool) {
+    unsafe { v8__Context__AllowCodeGenerationFromStrings(self, allow) }
+  }
+
+  /// Returns true if code generation from strings is allowed for the context.
+  /// For more details see `allow_code_generation_from_strings()`.
+  pub fn is_code_generation_from_strings_allowed(&self) -> bool {
+    unsafe { v8__Context__IsCodeGenerationFromStringsAllowed(self) }
+  }
+}
+
+/// Sets the continuation-preserved embedder data of a context for as long as
+/// the scope is alive, and restores the previous value when it is dropped.
+/// This gives `run(store, callback)` semantics like those of Node's
+/// `AsyncLocalStorage`: promise reactions scheduled while the scope is alive
+/// keep seeing `data`, while code that runs after the scope has been dropped
+/// sees the previous value again.
+///
+/// The scope wraps a `HandleScope` and dereferences to it, so it can be used
+/// everywhere the wrapped scope can be used.
+#[derive(Debug)]
+pub struct ContinuationPreservedEmbedderDataScope<'s, 'p> {
+  scope: &'s mut HandleScope<'p>,
+  context: Local<'p, Context>,
+  previous: Local<'p, Value>,
+}
+
+impl<'s, 'p> ContinuationPreservedEmbedderDataScope<'s, 'p> {
+  /// Sets the continuation-preserved embedder data of `context` to `data`.
+  /// The previous value is restored when the returned scope is dropped.
+  pub fn new(
+    scope: &'s mut HandleScope<'p>,
+    context: Local<'p, Context>,
+    data: Local<Value>,
+  ) -> Self {
+    let previous = context.get_continuation_preserved_embedder_data(scope);
+    context.set_continuation_preserved_embedder_data(data);
+    Self {
+      scope,
+      context,
+      previous,
+    }
+  }
+}
+
+impl<'s, 'p> Deref for ContinuationPreservedEmbedderDataScope<'s, 'p> {
+  type Target = HandleScope<'p>;
+  fn deref(&self) -> &Self::Target {
+    self.scope
+  }
+}
+
+impl<'s, 'p> DerefMut for ContinuationPreservedEmbedderDataScope<'s, 'p> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.scope
+  }
+}
+
+impl<'s, 'p> Drop for ContinuationPreservedEmbedderDataScope<'s, 'p> {
+  fn drop(&mut self) {
+    self
+      .context
+      .set_continuation_preserved_embedder_data(self.previous);
+  }
 }
@@ -0,0 +1,274 @@
+// Copyright 2019-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Bindings to `cppgc`, the garbage collector for C++ objects that is
+//! integrated with the V8 heap. Rust objects allocated on a managed [`Heap`]
+//! are traced together with the JavaScript objects that wrap them, so their
+//! lifetime follows the lifetime of their JS wrappers.
+
+use crate::platform::Platform;
+use crate::support::int;
+use crate::support::Opaque;
+use crate::support::SharedRef;
+use crate::support::UniqueRef;
+
+use std::ffi::c_void;
+use std::marker::PhantomData;
+
+extern "C" {
+  fn cppgc__initialize_process(platform: *mut Platform);
+  fn cppgc__shutdown_process();
+
+  fn cppgc__heap__create(
+    platform: *mut Platform,
+    wrappable_type_index: int,
+    wrappable_instance_index: int,
+    embedder_id: u16,
+  ) -> *mut Heap;
+  fn cppgc__heap__DELETE(this: *mut Heap);
+  fn cppgc__heap__collect_garbage_for_testing(
+    this: *mut Heap,
+    stack_state: EmbedderStackState,
+  );
+
+  fn cppgc__make_garbage_collectable(
+    heap: *mut Heap,
+    obj: *mut c_void,
+    trace: TraceFn,
+    destroy: DestroyFn,
+  ) -> *mut RustObj;
+  fn cppgc__RustObj__get(this: *const RustObj) -> *mut c_void;
+
+  fn cppgc__visitor__trace(visitor: *const Visitor, member: *const RustObj);
+}
+
+type TraceFn = unsafe extern "C" fn(obj: *mut c_void, visitor: *const Visitor);
+type DestroyFn = unsafe extern "C" fn(obj: *mut c_void);
+
+/// Initializes the garbage collector process. Must be called once, after
+/// the V8 platform has been initialized and before any [`Heap`] is created.
+pub fn initialize_process(platform: &SharedRef<Platform>) {
+  unsafe {
+    cppgc__initialize_process(&**platform as *const Platform as *mut _);
+  }
+}
+
+/// # Safety
+///
+/// Must be called after destroying the last used heap. Some process-global
+/// metadata may not be returned and reused upon a subsequent
+/// `initialize_process()` call.
+pub unsafe fn shutdown_process() {
+  cppgc__shutdown_process();
+}
+
+/// The embedder id V8 uses to recognize wrapper objects whose C++ part is
+/// managed by a [`Heap`]. It is written to the first two bytes of the memory
+/// pointed to by the type internal field of a wrapper.
+pub const DEFAULT_CPP_GC_EMBEDDER_ID: u16 = 0x90de;
+
+/// Whether the native stack may contain pointers into the managed heap
+/// during a garbage collection.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedderStackState {
+  MayContainHeapPointers,
+  NoHeapPointers,
+}
+
+/// Describes how V8 finds the managed object behind a JS wrapper: the
+/// wrapper's internal field at `wrappable_type_index` must hold an aligned
+/// pointer to `embedder_id_for_garbage_collected`, and the field at
+/// `wrappable_instance_index` must hold [`Member::handle`].
+#[derive(Debug, Clone, Copy)]
+pub struct WrapperDescriptor {
+  pub wrappable_type_index: int,
+  pub wrappable_instance_index: int,
+  pub embedder_id_for_garbage_collected: u16,
+}
+
+impl WrapperDescriptor {
+  pub fn new(
+    wrappable_type_index: int,
+    wrappable_instance_index: int,
+    embedder_id_for_garbage_collected: u16,
+  ) -> Self {
+    Self {
+      wrappable_type_index,
+      wrappable_instance_index,
+      embedder_id_for_garbage_collected,
+    }
+  }
+}
+
+impl Default for WrapperDescriptor {
+  fn default() -> Self {
+    Self::new(0, 1, DEFAULT_CPP_GC_EMBEDDER_ID)
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct HeapCreateParams {
+  wrapper_descriptor: WrapperDescriptor,
+}
+
+impl HeapCreateParams {
+  pub fn new(wrapper_descriptor: WrapperDescriptor) -> Self {
+    Self { wrapper_descriptor }
+  }
+}
+
+/// A managed C++ heap (`v8::CppHeap`). It is traced together with the
+/// JavaScript heap of the isolate it is attached to with
+/// `Isolate::attach_cpp_heap()`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Heap(Opaque);
+
+impl Heap {
+  pub fn create(
+    platform: &SharedRef<Platform>,
+    params: HeapCreateParams,
+  ) -> UniqueRef<Heap> {
+    let descriptor = params.wrapper_descriptor;
+    unsafe {
+      UniqueRef::from_raw(cppgc__heap__create(
+        &**platform as *const Platform as *mut _,
+        descriptor.wrappable_type_index,
+        descriptor.wrappable_instance_index,
+        descriptor.embedder_id_for_garbage_collected,
+      ))
+    }
+  }
+
+  /// Performs a stop-the-world garbage collection for testing purposes.
+  pub fn collect_garbage_for_testing(
+    &mut self,
+    stack_state: EmbedderStackState,
+  ) {
+    unsafe { cppgc__heap__collect_garbage_for_testing(self, stack_state) }
+  }
+}
+
+impl Drop for Heap {
+  fn drop(&mut self) {
+    unsafe { cppgc__heap__DELETE(self) }
+  }
+}
+
+/// A Rust type whose lifetime is managed by a [`Heap`]. `trace()` must
+/// report every [`Member`] the object holds, so that the objects they point
+/// to are kept alive as long as this one is.
+pub trait GarbageCollected {
+  fn trace(&self, _visitor: &Visitor) {}
+}
+
+#[repr(C)]
+#[derive(Debug)]
+struct RustObj(Opaque);
+
+/// Passed to `GarbageCollected::trace()` to report outgoing references.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Visitor(Opaque);
+
+impl Visitor {
+  /// Marks the object `member` points to as reachable.
+  ///
+  /// # Safety
+  ///
+  /// The object must not have been collected yet. This holds for a member
+  /// that the object being traced has reported on every trace since it was
+  /// stored there, but not for a stale copy of a member whose object has
+  /// since become unreachable.
+  pub unsafe fn trace<T: GarbageCollected>(&self, member: &Member<T>) {
+    cppgc__visitor__trace(self, member.handle)
+  }
+}
+
+/// A reference to an object allocated on a [`Heap`]. A member does not keep
+/// its object alive by itself, and there is no write barrier: it is a plain
+/// pointer that must be kept reachable either through a JS wrapper or
+/// through the `trace()` method of another live object. Once the object is
+/// collected, the member dangles.
+#[derive(Debug)]
+pub struct Member<T: GarbageCollected> {
+  handle: *mut RustObj,
+  _phantom: PhantomData<T>,
+}
+
+impl<T: GarbageCollected> Member<T> {
+  /// Returns the pointer that identifies this object to the garbage
+  /// collector. Store it in the `wrappable_instance_index` internal field of
+  /// a JS object to make that object a wrapper.
+  pub fn handle(&self) -> *mut c_void {
+    self.handle as _
+  }
+
+  /// Recreates a member from a pointer previously returned by `handle()`,
+  /// e.g. one read back from the internal field of a wrapper.
+  ///
+  /// # Safety
+  ///
+  /// `handle` must have been returned by `handle()` on a `Member<T>` of the
+  /// same type `T`.
+  pub unsafe fn from_handle(handle: *mut c_void) -> Self {
+    Self {
+      handle: handle as _,
+      _phantom: PhantomData,
+    }
+  }
+
+  /// # Safety
+  ///
+  /// The object must not have been collected yet.
+  pub unsafe fn borrow(&self) -> &T {
+    &*(cppgc__RustObj__get(self.handle) as *const T)
+  }
+}
+
+impl<T: GarbageCollected> Clone for Member<T> {
+  fn clone(&self) -> Self {
+    Self {
+      handle: self.handle,
+      _phantom: PhantomData,
+    }
+  }
+}
+
+unsafe extern "C" fn trace<T: GarbageCollected>(
+  obj: *mut c_void,
+  visitor: *const Visitor,
+) {
+  let obj = &*(obj as *const T);
+  obj.trace(&*visitor);
+}
+
+unsafe extern "C" fn destroy<T>(obj: *mut c_void) {
+  drop(Box::from_raw(obj as *mut T));
+}
+
+/// Moves `obj` onto the managed heap. It is dropped when the garbage
+/// collector finds it unreachable.
+///
+/// # Safety
+///
+/// The returned [`Member`] is not a root. Before the next garbage collection
+/// that runs with [`EmbedderStackState::NoHeapPointers`], the object must be
+/// made reachable, either by storing [`Member::handle`] in the internal field
+/// of a JS wrapper or by storing the member in an object whose `trace()`
+/// reports it. Otherwise the object is collected and the member dangles.
+pub unsafe fn make_garbage_collected<T: GarbageCollected + 'static>(
+  heap: &mut Heap,
+  obj: Box<T>,
+) -> Member<T> {
+  let handle = cppgc__make_garbage_collectable(
+    heap,
+    Box::into_raw(obj) as _,
+    trace::<T>,
+    destroy::<T>,
+  );
+  Member {
+    handle,
+    _phantom: PhantomData,
+  }
+}
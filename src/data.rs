@@ -147,7 +147,7 @@ macro_rules! impl_try_from {
         }
         match l {
           $value if $check => Ok(unsafe { transmute(l) }),
-          _ => Err(DataError::bad_type::<$target, $source>())
+          _ => Err(DataError::bad_type_of::<$target, $source>(&l))
         }
       }
     }
@@ -225,6 +225,39 @@ impl DataError {
     }
   }
 
+  /// Like `bad_type()`, but if `data` is a `Value`, reports its actual
+  /// runtime type rather than the static type `A`, e.g. `Number` instead of
+  /// `Value` when a number is converted to a `Local<Object>`.
+  pub(crate) fn bad_type_of<E: 'static, A: 'static>(data: &Data) -> Self {
+    if !data.is_value() {
+      return Self::bad_type::<E, A>();
+    }
+    let value = unsafe { &*(data as *const Data as *const Value) };
+    let actual = if value.is_string() {
+      type_name::<String>()
+    } else if value.is_number() {
+      type_name::<Number>()
+    } else if value.is_boolean() {
+      type_name::<Boolean>()
+    } else if value.is_big_int() {
+      type_name::<BigInt>()
+    } else if value.is_symbol() {
+      type_name::<Symbol>()
+    } else if value.is_function() {
+      type_name::<Function>()
+    } else if value.is_array() {
+      type_name::<Array>()
+    } else if value.is_object() {
+      type_name::<Object>()
+    } else {
+      type_name::<Primitive>()
+    };
+    Self::BadType {
+      expected: type_name::<E>(),
+      actual,
+    }
+  }
+
   pub(crate) fn no_data<E: 'static>() -> Self {
     Self::NoData {
       expected: type_name::<E>(),
@@ -299,6 +332,8 @@ impl_from! { StringObject for Data }
 impl_from! { SymbolObject for Data }
 impl_from! { WasmMemoryObject for Data }
 impl_from! { WasmModuleObject for Data }
+impl_from! { WeakMap for Data }
+impl_from! { WeakSet for Data }
 impl_from! { Primitive for Data }
 impl_from! { BigInt for Data }
 impl_from! { Boolean for Data }
@@ -355,6 +390,8 @@ impl_partial_eq! { StringObject for Data use identity }
 impl_partial_eq! { SymbolObject for Data use identity }
 impl_partial_eq! { WasmMemoryObject for Data use identity }
 impl_partial_eq! { WasmModuleObject for Data use identity }
+impl_partial_eq! { WeakMap for Data use identity }
+impl_partial_eq! { WeakSet for Data use identity }
 impl_partial_eq! { Boolean for Data use identity }
 impl_partial_eq! { Symbol for Data use identity }
 
@@ -432,6 +469,7 @@ impl_partial_eq! { ModuleRequest for ModuleRequest use identity }
 pub struct PrimitiveArray(Opaque);
 
 impl_deref! { Data for PrimitiveArray }
+impl_try_from! { Data for PrimitiveArray if v => v.is_fixed_array() }
 impl_eq! { for PrimitiveArray }
 impl_partial_eq! { Data for PrimitiveArray use identity }
 impl_partial_eq! { PrimitiveArray for PrimitiveArray use identity }
@@ -709,6 +747,8 @@ impl_from! { StringObject for Value }
 impl_from! { SymbolObject for Value }
 impl_from! { WasmMemoryObject for Value }
 impl_from! { WasmModuleObject for Value }
+impl_from! { WeakMap for Value }
+impl_from! { WeakSet for Value }
 impl_from! { Primitive for Value }
 impl_from! { BigInt for Value }
 impl_from! { Boolean for Value }
@@ -756,6 +796,8 @@ impl_partial_eq! { StringObject for Value use identity }
 impl_partial_eq! { SymbolObject for Value use identity }
 impl_partial_eq! { WasmMemoryObject for Value use identity }
 impl_partial_eq! { WasmModuleObject for Value use identity }
+impl_partial_eq! { WeakMap for Value use identity }
+impl_partial_eq! { WeakSet for Value use identity }
 impl_partial_eq! { Primitive for Value use same_value_zero }
 impl_partial_eq! { BigInt for Value use same_value_zero }
 impl_partial_eq! { Boolean for Value use identity }
@@ -822,6 +864,8 @@ impl_from! { StringObject for Object }
 impl_from! { SymbolObject for Object }
 impl_from! { WasmMemoryObject for Object }
 impl_from! { WasmModuleObject for Object }
+impl_from! { WeakMap for Object }
+impl_from! { WeakSet for Object }
 impl_eq! { for Object }
 impl_hash! { for Object use get_identity_hash }
 impl_partial_eq! { Data for Object use identity }
@@ -859,6 +903,8 @@ impl_partial_eq! { StringObject for Object use identity }
 impl_partial_eq! { SymbolObject for Object use identity }
 impl_partial_eq! { WasmMemoryObject for Object use identity }
 impl_partial_eq! { WasmModuleObject for Object use identity }
+impl_partial_eq! { WeakMap for Object use identity }
+impl_partial_eq! { WeakSet for Object use identity }
 
 /// An instance of the built-in array constructor (ECMA-262, 15.4.2).
 #[repr(C)]
@@ -1465,6 +1511,41 @@ impl_partial_eq! { Value for WasmModuleObject use identity }
 impl_partial_eq! { Object for WasmModuleObject use identity }
 impl_partial_eq! { WasmModuleObject for WasmModuleObject use identity }
 
+/// An instance of the built-in WeakMap constructor (ECMA-262, 6th Edition,
+/// 23.3.1). Keys are held weakly: an entry is removed once its key is
+/// garbage collected. Unlike with `Map`, keys must be objects.
+#[repr(C)]
+#[derive(Debug)]
+pub struct WeakMap(Opaque);
+
+impl_deref! { Object for WeakMap }
+impl_try_from! { Data for WeakMap if v => v.is_value() && cast::<Value>(v).is_weak_map() }
+impl_try_from! { Value for WeakMap if v => v.is_weak_map() }
+impl_try_from! { Object for WeakMap if v => v.is_weak_map() }
+impl_eq! { for WeakMap }
+impl_hash! { for WeakMap use get_identity_hash }
+impl_partial_eq! { Data for WeakMap use identity }
+impl_partial_eq! { Value for WeakMap use identity }
+impl_partial_eq! { Object for WeakMap use identity }
+impl_partial_eq! { WeakMap for WeakMap use identity }
+
+/// An instance of the built-in WeakSet constructor (ECMA-262, 6th Edition,
+/// 23.4.1). Values are held weakly, and must be objects.
+#[repr(C)]
+#[derive(Debug)]
+pub struct WeakSet(Opaque);
+
+impl_deref! { Object for WeakSet }
+impl_try_from! { Data for WeakSet if v => v.is_value() && cast::<Value>(v).is_weak_set() }
+impl_try_from! { Value for WeakSet if v => v.is_weak_set() }
+impl_try_from! { Object for WeakSet if v => v.is_weak_set() }
+impl_eq! { for WeakSet }
+impl_hash! { for WeakSet use get_identity_hash }
+impl_partial_eq! { Data for WeakSet use identity }
+impl_partial_eq! { Value for WeakSet use identity }
+impl_partial_eq! { Object for WeakSet use identity }
+impl_partial_eq! { WeakSet for WeakSet use identity }
+
 /// The superclass of primitive values. See ECMA-262 4.3.2.
 #[repr(C)]
 #[derive(Debug)]
@@ -0,0 +1,29 @@
+// Copyright 2019-2021 the Deno authors. All rights reserved. MIT license.
+use crate::ArrayBuffer;
+use crate::DataView;
+use crate::HandleScope;
+use crate::Local;
+
+extern "C" {
+  fn v8__DataView__New(
+    buf_ptr: *const ArrayBuffer,
+    byte_offset: usize,
+    length: usize,
+  ) -> *const DataView;
+}
+
+impl DataView {
+  /// Creates a DataView over `length` bytes of `buf`, starting at
+  /// `byte_offset`. The underlying buffer, byte offset and byte length are
+  /// available through the `ArrayBufferView` methods.
+  pub fn new<'s>(
+    scope: &mut HandleScope<'s>,
+    buf: Local<ArrayBuffer>,
+    byte_offset: usize,
+    length: usize,
+  ) -> Option<Local<'s, DataView>> {
+    unsafe {
+      scope.cast_local(|_| v8__DataView__New(&*buf, byte_offset, length))
+    }
+  }
+}
@@ -32,6 +32,7 @@ extern "C" {
   fn v8__Message__GetEndColumn(this: *const Message) -> int;
   fn v8__Message__IsSharedCrossOrigin(this: *const Message) -> bool;
   fn v8__Message__IsOpaque(this: *const Message) -> bool;
+  fn v8__Message__GetScriptId(this: *const Message) -> int;
   fn v8__Message__GetStackTrace(this: *const Message) -> *const StackTrace;
 
   fn v8__StackTrace__CurrentStackTrace(
@@ -272,9 +273,17 @@ impl Message {
     unsafe { v8__Message__IsSharedCrossOrigin(self) }
   }
 
+  /// Passes on the value set by the embedder when it fed the script from which
+  /// this Message was generated to V8.
   pub fn is_opaque(&self) -> bool {
     unsafe { v8__Message__IsOpaque(self) }
   }
+
+  /// Returns the id of the script from which this Message was generated, as
+  /// returned by `UnboundScript::get_id()`.
+  pub fn get_script_id(&self) -> int {
+    unsafe { v8__Message__GetScriptId(self) }
+  }
 }
 
 /// Create new error objects by calling the corresponding error object
@@ -12,6 +12,7 @@ use crate::support::{int, Opaque};
 use crate::Context;
 use crate::Function;
 use crate::HandleScope;
+use crate::Isolate;
 use crate::Local;
 use crate::Name;
 use crate::Object;
@@ -42,8 +43,17 @@ extern "C" {
     argc: int,
     argv: *const *const Value,
   ) -> *const Object;
+  fn v8__Function__NewInstanceWithSideEffectType(
+    this: *const Function,
+    context: *const Context,
+    argc: int,
+    argv: *const *const Value,
+    side_effect_type: SideEffectType,
+  ) -> *const Object;
   fn v8__Function__GetName(this: *const Function) -> *const String;
   fn v8__Function__SetName(this: *const Function, name: *const String);
+  fn v8__Function__GetInferredName(this: *const Function) -> *const String;
+  fn v8__Function__GetDebugName(this: *const Function) -> *const String;
   fn v8__Function__GetScriptColumnNumber(this: *const Function) -> int;
   fn v8__Function__GetScriptLineNumber(this: *const Function) -> int;
 
@@ -69,6 +79,12 @@ extern "C" {
   fn v8__FunctionCallbackInfo__NewTarget(
     this: *const FunctionCallbackInfo,
   ) -> *const Value;
+  fn v8__FunctionCallbackInfo__IsConstructCall(
+    this: *const FunctionCallbackInfo,
+  ) -> bool;
+  fn v8__FunctionCallbackInfo__GetIsolate(
+    this: *const FunctionCallbackInfo,
+  ) -> *mut Isolate;
 
   fn v8__PropertyCallbackInfo__GetReturnValue(
     this: *const PropertyCallbackInfo,
@@ -76,6 +92,12 @@ extern "C" {
   fn v8__PropertyCallbackInfo__This(
     this: *const PropertyCallbackInfo,
   ) -> *const Object;
+  fn v8__PropertyCallbackInfo__Data(
+    this: *const PropertyCallbackInfo,
+  ) -> *const Value;
+  fn v8__PropertyCallbackInfo__ShouldThrowOnError(
+    this: *const PropertyCallbackInfo,
+  ) -> bool;
 
   fn v8__ReturnValue__Set(this: *mut ReturnValue, value: *const Value);
   fn v8__ReturnValue__Get(this: *const ReturnValue) -> *const Value;
@@ -225,6 +247,23 @@ impl<'s> FunctionCallbackArguments<'s> {
       Local::from_raw(v8__FunctionCallbackInfo__NewTarget(self.info)).unwrap()
     }
   }
+
+  /// Indicates whether this is a regular call or a construct call, i.e.
+  /// whether the function was invoked with `new`.
+  pub fn is_construct_call(&self) -> bool {
+    unsafe { v8__FunctionCallbackInfo__IsConstructCall(self.info) }
+  }
+
+  /// Returns the isolate the callback is running in.
+  ///
+  /// # Safety
+  ///
+  /// The returned reference aliases the isolate that the callback's
+  /// `HandleScope` also refers to. It must not be used to create scopes or
+  /// handles while that scope is in use; prefer using the scope directly.
+  pub unsafe fn get_isolate(&mut self) -> &mut Isolate {
+    &mut *v8__FunctionCallbackInfo__GetIsolate(self.info)
+  }
 }
 
 #[derive(Debug)]
@@ -287,6 +326,23 @@ impl<'s> PropertyCallbackArguments<'s> {
       Local::from_raw(v8__PropertyCallbackInfo__This(self.info)).unwrap()
     }
   }
+
+  /// Returns the data set in the configuration, i.e., in
+  /// `AccessorConfiguration::data()`.
+  pub fn data(&self) -> Local<'s, Value> {
+    unsafe {
+      Local::from_raw(v8__PropertyCallbackInfo__Data(self.info)).unwrap()
+    }
+  }
+
+  /// Returns true if the intercepted function should throw if an error
+  /// occurs. Usually, true corresponds to 'use strict'.
+  ///
+  /// Always false when intercepting `Reflect.set()` independent of the
+  /// language mode.
+  pub fn should_throw_on_error(&self) -> bool {
+    unsafe { v8__PropertyCallbackInfo__ShouldThrowOnError(self.info) }
+  }
 }
 
 pub type FunctionCallback = extern "C" fn(*const FunctionCallbackInfo);
@@ -307,6 +363,29 @@ where
   }
 }
 
+/// ExtensionCallback is used to override the behavior of some built-in
+/// functions, e.g. the `WebAssembly.Module` and `WebAssembly.Instance`
+/// constructors. Returning true means the callback handled the call and its
+/// return value (or exception) is used; returning false makes V8 fall back to
+/// the default behavior.
+pub type ExtensionCallback = extern "C" fn(*const FunctionCallbackInfo) -> bool;
+
+impl<F> MapFnFrom<F> for ExtensionCallback
+where
+  F: UnitType
+    + Fn(&mut HandleScope, FunctionCallbackArguments, ReturnValue) -> bool,
+{
+  fn mapping() -> Self {
+    let f = |info: *const FunctionCallbackInfo| {
+      let scope = &mut unsafe { CallbackScope::new(&*info) };
+      let args = FunctionCallbackArguments::from_function_callback_info(info);
+      let rv = ReturnValue::from_function_callback_info(info);
+      (F::get())(scope, args, rv)
+    };
+    f.to_c_fn()
+  }
+}
+
 /// AccessorNameGetterCallback is used as callback functions when getting a
 /// particular property. See Object and ObjectTemplate's method SetAccessor.
 pub type AccessorNameGetterCallback<'s> =
@@ -488,6 +567,31 @@ impl Function {
     }
   }
 
+  /// Like `new_instance()`, but lets the caller declare whether the
+  /// constructor call may have side effects. This is taken into account
+  /// when the debugger evaluates code with throwOnSideEffect.
+  pub fn new_instance_with_side_effect_type<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+    args: &[Local<Value>],
+    side_effect_type: SideEffectType,
+  ) -> Option<Local<'s, Object>> {
+    let args = Local::slice_into_raw(args);
+    let argc = int::try_from(args.len()).unwrap();
+    let argv = args.as_ptr();
+    unsafe {
+      scope.cast_local(|sd| {
+        v8__Function__NewInstanceWithSideEffectType(
+          self,
+          sd.get_current_context(),
+          argc,
+          argv,
+          side_effect_type,
+        )
+      })
+    }
+  }
+
   pub fn get_name<'s>(&self, scope: &mut HandleScope<'s>) -> Local<'s, String> {
     unsafe { scope.cast_local(|_| v8__Function__GetName(self)).unwrap() }
   }
@@ -496,6 +600,36 @@ impl Function {
     unsafe { v8__Function__SetName(self, &*name) }
   }
 
+  /// Returns the name V8 inferred from the context the function was defined
+  /// in, e.g. `obj.method` for `obj.method = function() {}`. Returns an empty
+  /// string if no name could be inferred.
+  pub fn get_inferred_name<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Local<'s, String> {
+    unsafe {
+      scope
+        .cast_local(|_| v8__Function__GetInferredName(self))
+        .unwrap()
+    }
+  }
+
+  /// Returns the name that is shown for this function in stack traces and
+  /// CPU profiles: the function's name if it has one, otherwise its inferred
+  /// name. Native functions are named with `set_name()`, or with
+  /// `FunctionTemplate::set_class_name()` for all functions created from a
+  /// template.
+  pub fn get_debug_name<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Local<'s, String> {
+    unsafe {
+      scope
+        .cast_local(|_| v8__Function__GetDebugName(self))
+        .unwrap()
+    }
+  }
+
   /// Get the (zero-indexed) column number of the function's definition, if available.
   pub fn get_script_column_number(&self) -> Option<u32> {
     let ret = unsafe { v8__Function__GetScriptColumnNumber(self) };
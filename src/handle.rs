@@ -1,5 +1,10 @@
 use std::borrow::Borrow;
 use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::marker::PhantomData;
@@ -7,6 +12,8 @@ use std::mem::forget;
 use std::mem::transmute;
 use std::ops::Deref;
 use std::ptr::NonNull;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 
 use libc::c_void;
 
@@ -26,6 +33,8 @@ extern "C" {
     callback: extern "C" fn(*const WeakCallbackInfo),
   ) -> *const Data;
   fn v8__Global__Reset(data: *const Data);
+  fn v8__Global__SetWrapperClassId(data: *const Data, class_id: u16);
+  fn v8__Global__WrapperClassId(data: *const Data) -> u16;
   fn v8__WeakCallbackInfo__GetIsolate(
     this: *const WeakCallbackInfo,
   ) -> *mut Isolate;
@@ -91,6 +100,25 @@ impl<'s, T> Local<'s, T> {
     .unwrap()
   }
 
+  /// Construct a new Local from an existing Handle. Unlike `Local::new()`,
+  /// this does not panic when the handle is not hosted by the isolate that
+  /// `scope` belongs to, or when its host isolate has been disposed, but
+  /// returns an error instead.
+  pub fn try_new(
+    scope: &mut HandleScope<'s, ()>,
+    handle: impl Handle<Data = T>,
+  ) -> Result<Self, HandleError> {
+    let HandleInfo { data, host } = handle.get_handle_info();
+    host.check_isolate(scope)?;
+    let local = unsafe {
+      scope.cast_local(|sd| {
+        v8__Local__New(sd.get_isolate_ptr(), data.cast().as_ptr()) as *const T
+      })
+    }
+    .unwrap();
+    Ok(local)
+  }
+
   /// Create a local handle by downcasting from one of its super types.
   /// This function is unsafe because the cast is unchecked.
   pub unsafe fn cast<A>(other: Local<'s, A>) -> Self
@@ -100,6 +128,34 @@ impl<'s, T> Local<'s, T> {
     transmute(other)
   }
 
+  /// Returns the raw handle pointer, which has the same ABI as a C++
+  /// `v8::Local<T>`. This can be used to hand the handle to foreign code that
+  /// links against the same V8 library.
+  ///
+  /// The pointer is only valid as long as the `HandleScope` that the local
+  /// handle was created in is alive, and may only be used on the isolate
+  /// that owns that scope.
+  pub fn as_ptr(self) -> *const T {
+    self.0.as_ptr()
+  }
+
+  /// Creates a local handle from a raw handle pointer, e.g. a C++
+  /// `v8::Local<T>` that was passed in by foreign code or obtained from
+  /// `Local::as_ptr()`. Returns `None` if `ptr` is null (an empty handle).
+  ///
+  /// # Safety
+  ///
+  /// `ptr` must be a valid handle to a value of type `T` that belongs to the
+  /// isolate `scope` was created for, and it must have been allocated in
+  /// `scope` or in one of its parent handle scopes, so that it outlives the
+  /// returned `Local`.
+  pub unsafe fn from_raw_ptr(
+    _scope: &mut HandleScope<'s, ()>,
+    ptr: *const T,
+  ) -> Option<Self> {
+    Self::from_raw(ptr)
+  }
+
   pub(crate) unsafe fn from_raw(ptr: *const T) -> Option<Self> {
     NonNull::new(ptr as *mut _).map(|nn| Self::from_non_null(nn))
   }
@@ -192,6 +248,64 @@ impl<T> Global<T> {
   pub fn open<'a>(&'a self, scope: &mut Isolate) -> &'a T {
     Handle::open(self, scope)
   }
+
+  /// Creates a new `Global` that refers to the same object as this one.
+  /// Unlike `clone()`, this does not panic when the `Isolate` that hosts this
+  /// handle has been disposed, but returns `None` instead.
+  pub fn try_clone(&self) -> Option<Self> {
+    let HandleInfo { data, host } = self.get_handle_info();
+    match host {
+      HandleHost::Isolate(mut isolate) => {
+        Some(unsafe { Self::new_raw(isolate.as_mut(), data) })
+      }
+      _ => None,
+    }
+  }
+
+  /// Creates a [`Weak`] handle without a finalizer that refers to the same
+  /// object as this `Global`. The `Global` keeps the object alive until it is
+  /// dropped; after that, only the weak handle remains and the object may be
+  /// garbage collected.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the `Isolate` that hosts this handle has been
+  /// disposed.
+  pub fn downgrade(&self) -> Weak<T> {
+    let HandleInfo { data, host } = self.get_handle_info();
+    Weak::new_raw(host.get_isolate().as_ptr(), data, None)
+  }
+
+  /// Assigns a wrapper class id to this global handle. Embedders can use
+  /// class ids to identify their wrapper objects, e.g. when enumerating
+  /// global handles during embedder-side tracing. Class id 0 means that no
+  /// class id is set.
+  ///
+  /// Class ids are a property of the global handle, not of the object it
+  /// refers to: other handles to the same object are not affected.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the `Isolate` that hosts this handle has been
+  /// disposed.
+  pub fn set_wrapper_class_id(&self, class_id: u16) {
+    let HandleInfo { data, host } = self.get_handle_info();
+    host.get_isolate();
+    unsafe { v8__Global__SetWrapperClassId(data.cast().as_ptr(), class_id) }
+  }
+
+  /// Returns the class id previously assigned to this global handle with
+  /// `set_wrapper_class_id()`, or 0 if no class id was assigned.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the `Isolate` that hosts this handle has been
+  /// disposed.
+  pub fn get_wrapper_class_id(&self) -> u16 {
+    let HandleInfo { data, host } = self.get_handle_info();
+    host.get_isolate();
+    unsafe { v8__Global__WrapperClassId(data.cast().as_ptr()) }
+  }
 }
 
 impl<T> Clone for Global<T> {
@@ -435,11 +549,19 @@ impl HandleHost {
     )
   }
 
-  #[allow(dead_code)]
   fn match_isolate(self, isolate: &mut Isolate) -> bool {
     self.match_host(isolate.into(), Some(isolate))
   }
 
+  /// Non-panicking version of `assert_match_isolate()`.
+  fn check_isolate(self, isolate: &mut Isolate) -> Result<(), HandleError> {
+    match self {
+      Self::DisposedIsolate => Err(HandleError::DisposedIsolate),
+      _ if self.match_isolate(isolate) => Ok(()),
+      _ => Err(HandleError::WrongIsolate),
+    }
+  }
+
   fn assert_match_isolate(self, isolate: &mut Isolate) {
     self.assert_match_host(isolate.into(), Some(isolate))
   }
@@ -458,6 +580,34 @@ impl HandleHost {
   }
 }
 
+/// The error returned by `Local::try_new()` when a handle can't be used in
+/// the given scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandleError {
+  /// The handle is hosted by a different isolate than the scope.
+  WrongIsolate,
+  /// The isolate that hosted the handle has been disposed.
+  DisposedIsolate,
+}
+
+impl Display for HandleError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::WrongIsolate => {
+        write!(
+          f,
+          "attempt to use Handle in an Isolate that is not its host"
+        )
+      }
+      Self::DisposedIsolate => {
+        write!(f, "attempt to access Handle hosted by disposed Isolate")
+      }
+    }
+  }
+}
+
+impl Error for HandleError {}
+
 /// An object reference that does not prevent garbage collection for the object,
 /// and which allows installing finalization callbacks which will be called
 /// after the object has been GC'd.
@@ -666,6 +816,12 @@ impl<T> Weak<T> {
     }
   }
 
+  /// Creates a strong [`Global`] handle to the object, or returns `None` if
+  /// it has already been garbage collected. Same as [`Self::to_global`].
+  pub fn upgrade(&self, isolate: &mut Isolate) -> Option<Global<T>> {
+    self.to_global(isolate)
+  }
+
   pub fn to_local<'s>(
     &self,
     scope: &mut HandleScope<'s, ()>,
@@ -826,12 +982,111 @@ where
   }
 }
 
+/// A [`Weak`] handle that can be used as a `HashMap` or `HashSet` key.
+///
+/// The hash and equality of a `Weak` are based on the object it points to,
+/// so they change once the object is garbage collected. A `WeakKey` instead
+/// hashes the object's identity hash, captured when the key is created, and
+/// carries a generation tag that is shared by all of its clones:
+///
+/// - Two keys are equal if they have the same generation, or if both still
+///   point to the same object.
+/// - A key whose object has been collected can still be found with one of its
+///   clones, so entries can be removed deterministically, e.g. from a
+///   finalizer, or purged with `retain(|key, _| !key.is_empty())`.
+#[derive(Debug)]
+pub struct WeakKey<T> {
+  weak: Weak<T>,
+  hash: u64,
+  generation: u64,
+}
+
+impl<T: Hash> WeakKey<T> {
+  pub fn new(isolate: &mut Isolate, handle: impl Handle<Data = T>) -> Self {
+    let hash = Self::hash_handle(isolate, &handle);
+    Self::from_weak_raw(Weak::new(isolate, handle), hash)
+  }
+
+  /// Create a weak key with a finalization callback installed. See
+  /// [`Weak::with_finalizer`].
+  pub fn with_finalizer(
+    isolate: &mut Isolate,
+    handle: impl Handle<Data = T>,
+    finalizer: Box<dyn FnOnce(&mut Isolate)>,
+  ) -> Self {
+    let hash = Self::hash_handle(isolate, &handle);
+    Self::from_weak_raw(Weak::with_finalizer(isolate, handle, finalizer), hash)
+  }
+
+  fn hash_handle(isolate: &mut Isolate, handle: &impl Handle<Data = T>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    handle.open(isolate).hash(&mut hasher);
+    hasher.finish()
+  }
+
+  fn from_weak_raw(weak: Weak<T>, hash: u64) -> Self {
+    static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+    Self {
+      weak,
+      hash,
+      generation: NEXT_GENERATION.fetch_add(1, Ordering::Relaxed),
+    }
+  }
+}
+
+impl<T> WeakKey<T> {
+  /// Consumes this key and returns the underlying weak handle.
+  pub fn into_weak(self) -> Weak<T> {
+    self.weak
+  }
+}
+
+impl<T> Deref for WeakKey<T> {
+  type Target = Weak<T>;
+  fn deref(&self) -> &Weak<T> {
+    &self.weak
+  }
+}
+
+impl<T> Clone for WeakKey<T> {
+  fn clone(&self) -> Self {
+    Self {
+      weak: self.weak.clone(),
+      hash: self.hash,
+      generation: self.generation,
+    }
+  }
+}
+
+impl<T> Hash for WeakKey<T> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.hash.hash(state)
+  }
+}
+
+impl<T: PartialEq> PartialEq for WeakKey<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.generation == other.generation
+      || (self.hash == other.hash
+        && !self.weak.is_empty()
+        && !other.weak.is_empty()
+        && self.weak == other.weak)
+  }
+}
+
+impl<T: Eq> Eq for WeakKey<T> {}
+
 /// The inner mechanism behind [`Weak`] and finalizations.
 ///
 /// This struct is heap-allocated and will not move until it's dropped, so it
 /// can be accessed by the finalization callbacks by creating a shared reference
 /// from a pointer. The fields are wrapped in [`Cell`] so they are modifiable by
 /// both the [`Weak`] and the finalization callbacks.
+///
+/// The box is normally owned by the `Weak`. If the `Weak` is dropped after
+/// the first pass callback has run but before the second pass callback has,
+/// ownership is handed over to the second pass callback instead: `Drop` sets
+/// `weak_dropped` and leaks the box, and the callback frees it.
 pub struct WeakData<T> {
   pointer: Cell<Option<NonNull<T>>>,
   finalizer_id: Option<FinalizerId>,
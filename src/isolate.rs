@@ -1,7 +1,12 @@
 // Copyright 2019-2021 the Deno authors. All rights reserved. MIT license.
+use crate::cppgc::Heap;
+use crate::function::ExtensionCallback;
 use crate::function::FunctionCallbackInfo;
 use crate::handle::FinalizerMap;
 use crate::isolate_create_params::raw;
+use crate::isolate_create_params::AddHistogramSampleCallback;
+use crate::isolate_create_params::CounterLookupCallback;
+use crate::isolate_create_params::CreateHistogramCallback;
 use crate::isolate_create_params::CreateParams;
 use crate::promise::PromiseRejectMessage;
 use crate::scope::data::ScopeData;
@@ -48,17 +53,20 @@ use std::ptr::null_mut;
 use std::ptr::NonNull;
 use std::sync::Arc;
 use std::sync::Mutex;
+#[cfg(debug_assertions)]
+use std::thread::ThreadId;
 
 /// Policy for running microtasks:
 ///   - explicit: microtasks are invoked with the
 ///               Isolate::PerformMicrotaskCheckpoint() method;
+///   - scoped: microtasks invocation is controlled by MicrotasksScope objects;
 ///   - auto: microtasks are invoked when the script call depth decrements
 ///           to zero.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
 pub enum MicrotasksPolicy {
   Explicit = 0,
-  // Scoped = 1 (RAII) is omitted for now, doesn't quite map to idiomatic Rust.
+  Scoped = 1,
   Auto = 2,
 }
 
@@ -85,8 +93,136 @@ pub enum PromiseHookType {
   After,
 }
 
+/// Identifies a heap object across heap snapshots.
+pub type SnapshotObjectId = u32;
+
 pub type MessageCallback = extern "C" fn(Local<Message>, Local<Value>);
 
+/// Features reported to the callback installed with
+/// `Isolate::set_use_counter_callback()`. This list must be kept in sync with
+/// `v8::Isolate::UseCounterFeature`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub enum UseCounterFeature {
+  UseAsm = 0,
+  BreakIterator = 1,
+  LegacyConst = 2,
+  MarkDequeOverflow = 3,
+  StoreBufferOverflow = 4,
+  SlotsBufferOverflow = 5,
+  ObjectObserve = 6,
+  ForcedGC = 7,
+  SloppyMode = 8,
+  StrictMode = 9,
+  StrongMode = 10,
+  RegExpPrototypeStickyGetter = 11,
+  RegExpPrototypeToString = 12,
+  RegExpPrototypeUnicodeGetter = 13,
+  IntlV8Parse = 14,
+  IntlPattern = 15,
+  IntlResolved = 16,
+  PromiseChain = 17,
+  PromiseAccept = 18,
+  PromiseDefer = 19,
+  HtmlCommentInExternalScript = 20,
+  HtmlComment = 21,
+  SloppyModeBlockScopedFunctionRedefinition = 22,
+  ForInInitializer = 23,
+  ArrayProtectorDirtied = 24,
+  ArraySpeciesModified = 25,
+  ArrayPrototypeConstructorModified = 26,
+  ArrayInstanceProtoModified = 27,
+  ArrayInstanceConstructorModified = 28,
+  LegacyFunctionDeclaration = 29,
+  RegExpPrototypeSourceGetter = 30,
+  RegExpPrototypeOldFlagGetter = 31,
+  DecimalWithLeadingZeroInStrictMode = 32,
+  LegacyDateParser = 33,
+  DefineGetterOrSetterWouldThrow = 34,
+  FunctionConstructorReturnedUndefined = 35,
+  AssigmentExpressionLHSIsCallInSloppy = 36,
+  AssigmentExpressionLHSIsCallInStrict = 37,
+  PromiseConstructorReturnedUndefined = 38,
+  ConstructorNonUndefinedPrimitiveReturn = 39,
+  LabeledExpressionStatement = 40,
+  LineOrParagraphSeparatorAsLineTerminator = 41,
+  IndexAccessor = 42,
+  ErrorCaptureStackTrace = 43,
+  ErrorPrepareStackTrace = 44,
+  ErrorStackTraceLimit = 45,
+  WebAssemblyInstantiation = 46,
+  DeoptimizerDisableSpeculation = 47,
+  ArrayPrototypeSortJSArrayModifiedPrototype = 48,
+  FunctionTokenOffsetTooLongForToString = 49,
+  WasmSharedMemory = 50,
+  WasmThreadOpcodes = 51,
+  AtomicsNotify = 52,
+  AtomicsWake = 53,
+  Collator = 54,
+  NumberFormat = 55,
+  DateTimeFormat = 56,
+  PluralRules = 57,
+  RelativeTimeFormat = 58,
+  Locale = 59,
+  ListFormat = 60,
+  Segmenter = 61,
+  StringLocaleCompare = 62,
+  StringToLocaleUpperCase = 63,
+  StringToLocaleLowerCase = 64,
+  NumberToLocaleString = 65,
+  DateToLocaleString = 66,
+  DateToLocaleDateString = 67,
+  DateToLocaleTimeString = 68,
+  AttemptOverrideReadOnlyOnPrototypeSloppy = 69,
+  AttemptOverrideReadOnlyOnPrototypeStrict = 70,
+  OptimizedFunctionWithOneShotBytecode = 71,
+  RegExpMatchIsTrueishOnNonJSRegExp = 72,
+  RegExpMatchIsFalseishOnJSRegExp = 73,
+  DateGetTimezoneOffset = 74,
+  StringNormalize = 75,
+  CallSiteAPIGetFunctionSloppyCall = 76,
+  CallSiteAPIGetThisSloppyCall = 77,
+  RegExpMatchAllWithNonGlobalRegExp = 78,
+  RegExpExecCalledOnSlowRegExp = 79,
+  RegExpReplaceCalledOnSlowRegExp = 80,
+  DisplayNames = 81,
+  SharedArrayBufferConstructed = 82,
+  ArrayPrototypeHasElements = 83,
+  ObjectPrototypeHasElements = 84,
+  NumberFormatStyleUnit = 85,
+  DateTimeFormatRange = 86,
+  DateTimeFormatDateTimeStyle = 87,
+  BreakIteratorTypeWord = 88,
+  BreakIteratorTypeLine = 89,
+  InvalidatedArrayBufferDetachingProtector = 90,
+  InvalidatedArrayConstructorProtector = 91,
+  InvalidatedArrayIteratorLookupChainProtector = 92,
+  InvalidatedArraySpeciesLookupChainProtector = 93,
+  InvalidatedIsConcatSpreadableLookupChainProtector = 94,
+  InvalidatedMapIteratorLookupChainProtector = 95,
+  InvalidatedNoElementsProtector = 96,
+  InvalidatedPromiseHookProtector = 97,
+  InvalidatedPromiseResolveLookupChainProtector = 98,
+  InvalidatedPromiseSpeciesLookupChainProtector = 99,
+  InvalidatedPromiseThenLookupChainProtector = 100,
+  InvalidatedRegExpSpeciesLookupChainProtector = 101,
+  InvalidatedSetIteratorLookupChainProtector = 102,
+  InvalidatedStringIteratorLookupChainProtector = 103,
+  InvalidatedStringLengthOverflowLookupChainProtector = 104,
+  InvalidatedTypedArraySpeciesLookupChainProtector = 105,
+  WasmSimdOpcodes = 106,
+  VarRedeclaredCatchBinding = 107,
+  WasmRefTypes = 108,
+  WasmBulkMemory = 109,
+  WasmMultiValue = 110,
+  WasmExceptionHandling = 111,
+  InvalidatedMegaDOMProtector = 112,
+  FunctionPrototypeArguments = 113,
+  FunctionPrototypeCaller = 114,
+}
+
+pub type UseCounterCallback = extern "C" fn(&mut Isolate, UseCounterFeature);
+
 pub type PromiseHook =
   extern "C" fn(PromiseHookType, Local<Promise>, Local<Value>);
 
@@ -138,9 +274,24 @@ pub type NearHeapLimitCallback = extern "C" fn(
   initial_heap_limit: usize,
 ) -> usize;
 
+/// Custom callback used by embedders to help V8 determine if it should abort
+/// when it throws and no internal handler is predicted to catch the
+/// exception. If --abort-on-uncaught-exception is used on the command line,
+/// then V8 will abort if either:
+/// - no custom callback is set.
+/// - the custom callback set returns true.
+/// Otherwise, the custom callback will not be called and V8 will not abort.
+pub type AbortOnUncaughtExceptionCallback = extern "C" fn(&mut Isolate) -> bool;
+
 pub type OomErrorCallback =
   extern "C" fn(location: *const c_char, is_heap_oom: bool);
 
+/// Callback to check if WebAssembly code generation is allowed in the given
+/// context. `source` is the WebAssembly source being compiled, or the empty
+/// string if not available.
+pub type AllowWasmCodeGenerationCallback =
+  extern "C" fn(context: Local<Context>, source: Local<String>) -> bool;
+
 /// Collection of V8 heap information.
 ///
 /// Instances of this class can be passed to v8::Isolate::GetHeapStatistics to
@@ -167,6 +318,94 @@ pub type PrepareStackTraceCallback<'s> = extern "C" fn(
   Local<'s, Array>,
 ) -> *const Value;
 
+/// The result of a `ModifyCodeGenerationFromStringsCallback`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ModifyCodeGenerationFromStringsResult<'s> {
+  /// If true, proceed with the codegen algorithm. Otherwise, block it.
+  pub codegen_allowed: bool,
+  /// Overwrite the original source with this string, if present.
+  /// Use the original source if `None`.
+  /// This field is considered only if `codegen_allowed` is true.
+  pub modified_source: Option<Local<'s, String>>,
+}
+
+/// The layout of `v8::ModifyCodeGenerationFromStringsResult`, which is what
+/// a `ModifyCodeGenerationFromStringsCallback` returns to V8. The callback
+/// trampoline converts a `ModifyCodeGenerationFromStringsResult` into it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RawModifyCodeGenerationFromStringsResult {
+  codegen_allowed: bool,
+  modified_source: *const String,
+}
+
+impl<'s> From<ModifyCodeGenerationFromStringsResult<'s>>
+  for RawModifyCodeGenerationFromStringsResult
+{
+  fn from(result: ModifyCodeGenerationFromStringsResult<'s>) -> Self {
+    Self {
+      codegen_allowed: result.codegen_allowed,
+      modified_source: result
+        .modified_source
+        .map_or(ptr::null(), |source| &*source as *const String),
+    }
+  }
+}
+
+/// Callback to check if code generation from strings (`eval()`,
+/// `new Function()`) is allowed, and to optionally rewrite the source.
+/// `is_code_like` is true if the source is a `TrustedScript`-like object.
+// The two-word result struct is returned the same way by C and C++ functions
+// on both the System V and the Windows x64 ABI.
+pub type ModifyCodeGenerationFromStringsCallback<'s> =
+  extern "C" fn(
+    Local<'s, Context>,
+    Local<'s, Value>,
+    bool,
+  ) -> RawModifyCodeGenerationFromStringsResult;
+
+/// Whether `Isolate::date_time_configuration_change_notification()` should
+/// re-detect the host time zone or keep using the one V8 already knows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum TimeZoneDetection {
+  Skip,
+  Redetect,
+}
+
+/// Keys of the crash annotations V8 reports through the callback installed
+/// with `Isolate::set_add_crash_key_callback()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub enum CrashKeyId {
+  IsolateAddress,
+  ReadonlySpaceFirstPageAddress,
+  MapSpaceFirstPageAddress,
+  CodeSpaceFirstPageAddress,
+  DumpType,
+}
+
+pub type AddCrashKeyCallback = extern "C" fn(CrashKeyId, *const u8, usize);
+
+/// Receives the global handles enumerated by
+/// `Isolate::visit_handles_with_class_ids()`.
+///
+/// The garbage collector must not run while handles are being visited, so
+/// the visitor must not allocate JavaScript objects, and must not create or
+/// drop `Global` handles.
+pub trait PersistentHandleVisitor {
+  fn visit_persistent_handle(&mut self, value: Local<Value>, class_id: u16);
+}
+
+impl<F> PersistentHandleVisitor for F
+where
+  F: for<'a> FnMut(Local<'a, Value>, u16),
+{
+  fn visit_persistent_handle(&mut self, value: Local<Value>, class_id: u16) {
+    self(value, class_id)
+  }
+}
+
 extern "C" {
   fn v8__Isolate__New(params: *const raw::CreateParams) -> *mut Isolate;
   fn v8__Isolate__Dispose(this: *mut Isolate);
@@ -175,8 +414,17 @@ extern "C" {
   fn v8__Isolate__GetNumberOfDataSlots(this: *const Isolate) -> u32;
   fn v8__Isolate__Enter(this: *mut Isolate);
   fn v8__Isolate__Exit(this: *mut Isolate);
+  fn v8__Isolate__IsInUse(this: *const Isolate) -> bool;
   fn v8__Isolate__ClearKeptObjects(isolate: *mut Isolate);
   fn v8__Isolate__LowMemoryNotification(isolate: *mut Isolate);
+  fn v8__Isolate__InstallConditionalFeatures(
+    isolate: *mut Isolate,
+    context: *const Context,
+  );
+  fn v8__Isolate__DateTimeConfigurationChangeNotification(
+    isolate: *mut Isolate,
+    time_zone_detection: TimeZoneDetection,
+  );
   fn v8__Isolate__GetHeapStatistics(this: *mut Isolate, s: *mut HeapStatistics);
   fn v8__Isolate__SetCaptureStackTraceForUncaughtExceptions(
     this: *mut Isolate,
@@ -205,10 +453,15 @@ extern "C" {
     isolate: *mut Isolate,
     change_in_bytes: i64,
   ) -> i64;
+  fn v8__Isolate__GetExternalMemory(isolate: *const Isolate) -> i64;
   fn v8__Isolate__SetPrepareStackTraceCallback(
     isolate: *mut Isolate,
     callback: PrepareStackTraceCallback,
   );
+  fn v8__Isolate__SetModifyCodeGenerationFromStringsCallback(
+    isolate: *mut Isolate,
+    callback: ModifyCodeGenerationFromStringsCallback,
+  );
   fn v8__Isolate__SetPromiseHook(isolate: *mut Isolate, hook: PromiseHook);
   fn v8__Isolate__SetPromiseRejectCallback(
     isolate: *mut Isolate,
@@ -242,13 +495,65 @@ extern "C" {
     isolate: *mut Isolate,
     function: *const Function,
   );
+  fn v8__Isolate__EnqueueMicrotask__with_callback(
+    isolate: *mut Isolate,
+    callback: extern "C" fn(*mut c_void),
+    data: *mut c_void,
+  );
   fn v8__Isolate__SetAllowAtomicsWait(isolate: *mut Isolate, allow: bool);
   fn v8__Isolate__SetWasmStreamingCallback(
     isolate: *mut Isolate,
     callback: extern "C" fn(*const FunctionCallbackInfo),
   );
+  fn v8__Isolate__SetAllowWasmCodeGenerationCallback(
+    isolate: *mut Isolate,
+    callback: AllowWasmCodeGenerationCallback,
+  );
+  fn v8__Isolate__SetWasmModuleCallback(
+    isolate: *mut Isolate,
+    callback: ExtensionCallback,
+  );
+  fn v8__Isolate__SetWasmInstanceCallback(
+    isolate: *mut Isolate,
+    callback: ExtensionCallback,
+  );
+  fn v8__Isolate__SetAbortOnUncaughtExceptionCallback(
+    isolate: *mut Isolate,
+    callback: AbortOnUncaughtExceptionCallback,
+  );
+  fn v8__Isolate__SetCounterFunction(
+    isolate: *mut Isolate,
+    callback: CounterLookupCallback,
+  );
+  fn v8__Isolate__SetCreateHistogramFunction(
+    isolate: *mut Isolate,
+    callback: CreateHistogramCallback,
+  );
+  fn v8__Isolate__SetAddHistogramSampleFunction(
+    isolate: *mut Isolate,
+    callback: AddHistogramSampleCallback,
+  );
+  fn v8__Isolate__SetUseCounterCallback(
+    isolate: *mut Isolate,
+    callback: UseCounterCallback,
+  );
+  fn v8__Isolate__SetAddCrashKeyCallback(
+    isolate: *mut Isolate,
+    callback: AddCrashKeyCallback,
+  );
+  fn v8__Isolate__AttachCppHeap(isolate: *mut Isolate, heap: *mut Heap);
+  fn v8__Isolate__DetachCppHeap(isolate: *mut Isolate);
   fn v8__Isolate__HasPendingBackgroundTasks(isolate: *const Isolate) -> bool;
 
+  fn v8__HeapProfiler__GetObjectId(
+    isolate: *mut Isolate,
+    value: *const Value,
+  ) -> SnapshotObjectId;
+  fn v8__Isolate__VisitHandlesWithClassIds(
+    isolate: *mut Isolate,
+    callback: extern "C" fn(*mut c_void, *const Value, u16),
+    arg: *mut c_void,
+  );
   fn v8__HeapProfiler__TakeHeapSnapshot(
     isolate: *mut Isolate,
     callback: extern "C" fn(*mut c_void, *const u8, usize) -> bool,
@@ -391,22 +696,28 @@ impl Isolate {
   }
 
   /// Associate embedder-specific data with the isolate. `slot` has to be
-  /// between 0 and `Isolate::get_number_of_data_slots()`.
-  unsafe fn set_data(&mut self, slot: u32, ptr: *mut c_void) {
-    v8__Isolate__SetData(self, slot + Self::INTERNAL_SLOT_COUNT, ptr)
+  /// between 0 and `Isolate::get_number_of_data_slots() - 1`.
+  ///
+  /// V8 never dereferences `ptr`; it is up to the embedder to keep whatever
+  /// it points to alive for as long as it may be read back with `get_data()`.
+  // `ptr` is only stored, never dereferenced, so this is sound as a safe fn;
+  // reading through the pointer returned by `get_data()` is what's unsafe.
+  #[allow(clippy::not_unsafe_ptr_arg_deref)]
+  pub fn set_data(&mut self, slot: u32, ptr: *mut c_void) {
+    assert!(slot < self.get_number_of_data_slots());
+    unsafe { v8__Isolate__SetData(self, slot + Self::INTERNAL_SLOT_COUNT, ptr) }
   }
 
   /// Retrieve embedder-specific data from the isolate.
   /// Returns NULL if SetData has never been called for the given `slot`.
-  #[allow(dead_code)]
-  fn get_data(&self, slot: u32) -> *mut c_void {
+  pub fn get_data(&self, slot: u32) -> *mut c_void {
+    assert!(slot < self.get_number_of_data_slots());
     unsafe { v8__Isolate__GetData(self, slot + Self::INTERNAL_SLOT_COUNT) }
   }
 
   /// Returns the maximum number of available embedder data slots. Valid slots
   /// are in the range of 0 - `Isolate::get_number_of_data_slots() - 1`.
-  #[allow(dead_code)]
-  fn get_number_of_data_slots(&self) -> u32 {
+  pub fn get_number_of_data_slots(&self) -> u32 {
     unsafe {
       v8__Isolate__GetNumberOfDataSlots(self) - Self::INTERNAL_SLOT_COUNT
     }
@@ -479,6 +790,17 @@ impl Isolate {
       .map(|slot| unsafe { slot.into_inner::<T>() })
   }
 
+  /// Opens a new `HandleScope`, runs `f` inside it and closes the scope
+  /// again. Handles created in the scope can't outlive it, so `f` can only
+  /// return values that are not bound to the scope, such as Rust primitives
+  /// or `Global` handles.
+  pub fn with_handle_scope<R>(
+    &mut self,
+    f: impl for<'s> FnOnce(&mut HandleScope<'s, ()>) -> R,
+  ) -> R {
+    f(&mut HandleScope::new(self))
+  }
+
   /// Sets this isolate as the entered one for the current thread.
   /// Saves the previously entered one (if any), so that it can be
   /// restored when exiting.  Re-entering an isolate is allowed.
@@ -486,7 +808,8 @@ impl Isolate {
   /// rusty_v8 note: Unlike in the C++ API, the isolate is entered when it is
   /// constructed and exited when dropped.
   pub unsafe fn enter(&mut self) {
-    v8__Isolate__Enter(self)
+    v8__Isolate__Enter(self);
+    self.record_entered();
   }
 
   /// Exits this isolate by restoring the previously entered one in the
@@ -498,9 +821,58 @@ impl Isolate {
   /// rusty_v8 note: Unlike in the C++ API, the isolate is entered when it is
   /// constructed and exited when dropped.
   pub unsafe fn exit(&mut self) {
+    self.record_exited();
     v8__Isolate__Exit(self)
   }
 
+  /// Returns true if at least one thread has entered this isolate.
+  pub fn is_in_use(&self) -> bool {
+    unsafe { v8__Isolate__IsInUse(self) }
+  }
+
+  /// Records that the current thread has entered this isolate. This is used
+  /// to verify that scopes are only created on a thread that has entered the
+  /// isolate. For performance reasons this is not tracked in release builds.
+  pub(crate) fn record_entered(&self) {
+    #[cfg(debug_assertions)]
+    self
+      .get_annex()
+      .entered_threads
+      .lock()
+      .unwrap()
+      .push(std::thread::current().id());
+  }
+
+  fn record_exited(&self) {
+    #[cfg(debug_assertions)]
+    {
+      let current = std::thread::current().id();
+      let mut entered_threads =
+        self.get_annex().entered_threads.lock().unwrap();
+      if let Some(index) = entered_threads.iter().rposition(|&t| t == current) {
+        entered_threads.remove(index);
+      }
+    }
+  }
+
+  /// Panics if the isolate has not been entered on the current thread.
+  /// This check is only performed in debug builds.
+  pub(crate) fn assert_entered_on_current_thread(&self) {
+    #[cfg(debug_assertions)]
+    {
+      let current = std::thread::current().id();
+      assert!(
+        self
+          .get_annex()
+          .entered_threads
+          .lock()
+          .unwrap()
+          .contains(&current),
+        "Isolate is not entered on the current thread"
+      );
+    }
+  }
+
   /// Clears the set of objects held strongly by the heap. This set of
   /// objects are originally built when a WeakRef is created or
   /// successfully dereferenced.
@@ -522,6 +894,38 @@ impl Isolate {
     unsafe { v8__Isolate__LowMemoryNotification(self) }
   }
 
+  /// Some language and WebAssembly features are only installed into a
+  /// context if the embedder enables them for it, e.g. through a callback or
+  /// an origin-trial-style flag. If such a feature was enabled after
+  /// `context` was created, this installs its bindings into the context.
+  /// Contexts created afterwards get them automatically. Calling it when
+  /// nothing changed is harmless.
+  pub fn install_conditional_features(&mut self, context: Local<Context>) {
+    unsafe { v8__Isolate__InstallConditionalFeatures(self, &*context) }
+  }
+
+  /// Notification that the embedder has changed the time zone, daylight
+  /// savings time or other date / time configuration parameters. V8 keeps a
+  /// cache of various values used for date / time computation. This
+  /// notification will reset those cached values for the current context so
+  /// that date / time configuration changes would be reflected by `Date` and
+  /// `Intl` objects.
+  ///
+  /// With `TimeZoneDetection::Redetect` the host time zone is detected again
+  /// (e.g. after the `TZ` environment variable has been changed); with
+  /// `TimeZoneDetection::Skip` only the caches are cleared.
+  pub fn date_time_configuration_change_notification(
+    &mut self,
+    time_zone_detection: TimeZoneDetection,
+  ) {
+    unsafe {
+      v8__Isolate__DateTimeConfigurationChangeNotification(
+        self,
+        time_zone_detection,
+      )
+    }
+  }
+
   /// Get statistics about the heap memory usage.
   pub fn get_heap_statistics(&mut self, s: &mut HeapStatistics) {
     unsafe { v8__Isolate__GetHeapStatistics(self, s) }
@@ -573,6 +977,26 @@ impl Isolate {
     };
   }
 
+  /// Set the callback that decides whether code generation from strings
+  /// (`eval()`, `new Function()`) is allowed, with the source at hand. It
+  /// may also replace the source that gets compiled.
+  ///
+  /// The callback is only consulted in contexts for which
+  /// `Context::allow_code_generation_from_strings(false)` has been called;
+  /// otherwise code generation is always allowed. If the callback blocks
+  /// code generation an `EvalError` is thrown.
+  pub fn set_modify_code_generation_from_strings_callback<'s>(
+    &mut self,
+    callback: impl MapFnTo<ModifyCodeGenerationFromStringsCallback<'s>>,
+  ) {
+    unsafe {
+      v8__Isolate__SetModifyCodeGenerationFromStringsCallback(
+        self,
+        callback.map_fn_to(),
+      )
+    }
+  }
+
   /// Set the PromiseHook callback for various promise lifecycle
   /// events.
   pub fn set_promise_hook(&mut self, hook: PromiseHook) {
@@ -651,6 +1075,13 @@ impl Isolate {
     }
   }
 
+  /// Returns the amount of external memory currently registered with
+  /// `adjust_amount_of_external_allocated_memory()`. Unlike calling that
+  /// method with a change of 0, this never triggers a garbage collection.
+  pub fn get_external_memory(&self) -> i64 {
+    unsafe { v8__Isolate__GetExternalMemory(self) }
+  }
+
   pub fn set_oom_error_handler(&mut self, callback: OomErrorCallback) {
     unsafe { v8__Isolate__SetOOMErrorHandler(self, callback) };
   }
@@ -669,8 +1100,53 @@ impl Isolate {
   /// microtask checkpoint steps, such as calling ClearKeptObjects. Asserts that
   /// the MicrotasksPolicy is not kScoped. Any exceptions thrown by microtask
   /// callbacks are swallowed.
+  ///
+  /// Although the checkpoint itself does not return them, such exceptions
+  /// can still be observed:
+  /// - An exception thrown by a promise reaction (e.g. a `.then()` callback)
+  ///   rejects the promise returned by `.then()`. If that promise has no
+  ///   handler, the callback installed with `set_promise_reject_callback()`
+  ///   is called with `PromiseRejectWithNoHandler` and the exception as its
+  ///   value.
+  /// - An exception thrown by a function enqueued with `enqueue_microtask()`
+  ///   is reported to the listeners added with `add_message_listener()`.
+  ///
+  /// # Aborts
+  ///
+  /// In debug builds, this function prints a message to stderr and aborts
+  /// the process when it is called reentrantly, i.e. from a microtask that
+  /// runs as part of a checkpoint started with this function. V8 silently
+  /// ignores such nested checkpoints, which usually points at a host
+  /// function that unexpectedly drains the queue. Release builds do not
+  /// check for reentrancy.
+  ///
+  /// The guard aborts rather than panics on purpose: the nested call always
+  /// happens inside a callback called by V8, so a panic would unwind across
+  /// V8's C++ frames, which is undefined behavior. The panic could not be
+  /// caught either way.
   pub fn perform_microtask_checkpoint(&mut self) {
+    let annex = self.get_annex_mut();
+    annex.microtask_checkpoint_count += 1;
+    #[cfg(debug_assertions)]
+    {
+      if annex.in_microtask_checkpoint {
+        eprintln!("perform_microtask_checkpoint() called reentrantly");
+        std::process::abort();
+      }
+      annex.in_microtask_checkpoint = true;
+    }
     unsafe { v8__Isolate__PerformMicrotaskCheckpoint(self) }
+    #[cfg(debug_assertions)]
+    {
+      self.get_annex_mut().in_microtask_checkpoint = false;
+    }
+  }
+
+  /// Returns the number of times `perform_microtask_checkpoint()` has been
+  /// called on this isolate. Checkpoints that V8 performs by itself, e.g.
+  /// under `MicrotasksPolicy::Auto`, are not counted.
+  pub fn get_microtask_checkpoint_count(&self) -> usize {
+    self.get_annex().microtask_checkpoint_count
   }
 
   /// An alias for PerformMicrotaskCheckpoint.
@@ -684,6 +1160,36 @@ impl Isolate {
     unsafe { v8__Isolate__EnqueueMicrotask(self, &*microtask) }
   }
 
+  /// Enqueues a Rust closure to the default MicrotaskQueue. The closure runs
+  /// inside a `CallbackScope` that has no context entered. If the isolate is
+  /// disposed before the microtask runs, the closure is dropped without being
+  /// called.
+  pub fn enqueue_microtask_closure<F>(&mut self, microtask: F)
+  where
+    F: FnOnce(&mut HandleScope<()>) + 'static,
+  {
+    extern "C" fn trampoline(data: *mut c_void) {
+      let isolate = unsafe { &mut *(*(data as *const PendingMicrotask)).0 };
+      let pending = isolate
+        .get_annex_mut()
+        .pending_microtasks
+        .remove(&(data as usize))
+        .unwrap();
+      let scope = &mut unsafe { CallbackScope::new(isolate) };
+      (pending.1)(scope);
+    }
+
+    let pending = Box::new(PendingMicrotask(self, Box::new(microtask)));
+    let data = &*pending as *const PendingMicrotask as *mut c_void;
+    self
+      .get_annex_mut()
+      .pending_microtasks
+      .insert(data as usize, pending);
+    unsafe {
+      v8__Isolate__EnqueueMicrotask__with_callback(self, trampoline, data)
+    }
+  }
+
   /// Set whether calling Atomics.wait (a function that may block) is allowed in
   /// this isolate. This can also be configured via
   /// CreateParams::allow_atomics_wait.
@@ -705,9 +1211,109 @@ impl Isolate {
     unsafe { v8__Isolate__SetWasmStreamingCallback(self, trampoline::<F>()) }
   }
 
+  /// Set the callback to invoke to check if WebAssembly code generation
+  /// should be allowed in a context.
+  pub fn set_allow_wasm_code_generation_callback(
+    &mut self,
+    callback: AllowWasmCodeGenerationCallback,
+  ) {
+    unsafe { v8__Isolate__SetAllowWasmCodeGenerationCallback(self, callback) }
+  }
+
+  /// Embedder override for the `WebAssembly.Module` constructor. See
+  /// [ExtensionCallback].
+  pub fn set_wasm_module_callback(
+    &mut self,
+    callback: impl MapFnTo<ExtensionCallback>,
+  ) {
+    unsafe { v8__Isolate__SetWasmModuleCallback(self, callback.map_fn_to()) }
+  }
+
+  /// Embedder override for the `WebAssembly.Instance` constructor. See
+  /// [ExtensionCallback].
+  pub fn set_wasm_instance_callback(
+    &mut self,
+    callback: impl MapFnTo<ExtensionCallback>,
+  ) {
+    unsafe { v8__Isolate__SetWasmInstanceCallback(self, callback.map_fn_to()) }
+  }
+
+  /// Sets the callback that is consulted before aborting on an uncaught
+  /// exception when V8 runs with `--abort-on-uncaught-exception`. See
+  /// [AbortOnUncaughtExceptionCallback].
+  pub fn set_abort_on_uncaught_exception_callback(
+    &mut self,
+    callback: AbortOnUncaughtExceptionCallback,
+  ) {
+    unsafe { v8__Isolate__SetAbortOnUncaughtExceptionCallback(self, callback) }
+  }
+
+  /// Enables the host application to provide a mechanism for recording
+  /// statistics counters. Counter names start with a "c:" prefix.
+  pub fn set_counter_function(&mut self, callback: CounterLookupCallback) {
+    unsafe { v8__Isolate__SetCounterFunction(self, callback) }
+  }
+
+  /// Enables the host application to provide a mechanism for recording
+  /// histograms. The CreateHistogram function returns a histogram which will
+  /// later be passed to the AddHistogramSample function.
+  pub fn set_create_histogram_function(
+    &mut self,
+    callback: CreateHistogramCallback,
+  ) {
+    unsafe { v8__Isolate__SetCreateHistogramFunction(self, callback) }
+  }
+
+  /// See [`Isolate::set_create_histogram_function`].
+  pub fn set_add_histogram_sample_function(
+    &mut self,
+    callback: AddHistogramSampleCallback,
+  ) {
+    unsafe { v8__Isolate__SetAddHistogramSampleFunction(self, callback) }
+  }
+
+  /// Sets a callback for counting the number of times a feature of V8 is
+  /// used.
+  pub fn set_use_counter_callback(&mut self, callback: UseCounterCallback) {
+    unsafe { v8__Isolate__SetUseCounterCallback(self, callback) }
+  }
+
+  /// Enables the host application to provide a mechanism for recording
+  /// key/value pairs that are reported with crash dumps. V8 reports an
+  /// initial set of keys right away, from within this call.
+  ///
+  /// V8 gives the callback no way to carry per-isolate state, so it is
+  /// stored in a single process-wide slot: installing a callback on one
+  /// isolate silently replaces the one installed on any other, and every
+  /// isolate that has a callback installed reports to the most recent one.
+  pub fn set_add_crash_key_callback(
+    &mut self,
+    callback: impl MapFnTo<AddCrashKeyCallback>,
+  ) {
+    unsafe { v8__Isolate__SetAddCrashKeyCallback(self, callback.map_fn_to()) }
+  }
+
+  /// Attaches a managed C++ heap as an extension to the JavaScript heap. The
+  /// embedder maintains ownership of the heap, which must be detached with
+  /// `detach_cpp_heap()` before it is dropped. Only one heap can be attached
+  /// to an isolate at a time.
+  pub fn attach_cpp_heap(&mut self, heap: &mut Heap) {
+    unsafe { v8__Isolate__AttachCppHeap(self, heap) }
+  }
+
+  /// Detaches the managed C++ heap previously attached with
+  /// `attach_cpp_heap()`.
+  pub fn detach_cpp_heap(&mut self) {
+    unsafe { v8__Isolate__DetachCppHeap(self) }
+  }
+
   /// Returns true if there is ongoing background work within V8 that will
   /// eventually post a foreground task, like asynchronous WebAssembly
   /// compilation.
+  ///
+  /// Before dropping an isolate, an embedder can drain this work by pumping
+  /// the message loop with `Platform::pump_message_loop()` until this
+  /// returns false and no foreground task is left to run.
   pub fn has_pending_background_tasks(&self) -> bool {
     unsafe { v8__Isolate__HasPendingBackgroundTasks(self) }
   }
@@ -739,10 +1345,13 @@ impl Isolate {
     // Clear slots and drop owned objects that were taken out of `CreateParams`.
     annex.create_param_allocations = Box::new(());
     annex.slots.clear();
+    annex.pending_microtasks.clear();
 
-    // Subtract one from the Arc<IsolateAnnex> reference count.
+    // Subtract one from the Arc<IsolateAnnex> reference count. The annex may
+    // be freed now, so clear the slot that points to it. Embedder data slot 0
+    // belongs to the embedder and is left alone.
     Arc::from_raw(annex);
-    self.set_data(0, null_mut());
+    v8__Isolate__SetData(self, Self::ANNEX_SLOT, null_mut());
 
     // No test case in rusty_v8 show this, but there have been situations in
     // deno where dropping Annex before the states causes a segfault.
@@ -776,12 +1385,59 @@ impl Isolate {
     let arg = &mut callback as *mut F as *mut c_void;
     unsafe { v8__HeapProfiler__TakeHeapSnapshot(self, trampoline::<F>, arg) }
   }
+
+  /// Returns the id of the heap object the given value refers to. The same
+  /// id is used for the object in heap snapshots, and remains stable while
+  /// the object is alive. Ids are only assigned to objects once a heap
+  /// snapshot has been taken; before that, `None` is returned.
+  pub fn get_heap_object_id(
+    &mut self,
+    value: Local<Value>,
+  ) -> Option<SnapshotObjectId> {
+    match unsafe { v8__HeapProfiler__GetObjectId(self, &*value) } {
+      0 => None,
+      id => Some(id),
+    }
+  }
+
+  /// Calls `visitor` for every global handle in this isolate that was
+  /// assigned a class id with `Global::set_wrapper_class_id()`, passing the
+  /// value the handle refers to and its class id.
+  pub fn visit_handles_with_class_ids(
+    &mut self,
+    mut visitor: &mut dyn PersistentHandleVisitor,
+  ) {
+    extern "C" fn trampoline(
+      arg: *mut c_void,
+      value: *const Value,
+      class_id: u16,
+    ) {
+      let visitor =
+        unsafe { &mut *(arg as *mut &mut dyn PersistentHandleVisitor) };
+      let value = unsafe { Local::from_raw(value) }.unwrap();
+      visitor.visit_persistent_handle(value, class_id);
+    }
+
+    let arg = &mut visitor as *mut &mut dyn PersistentHandleVisitor;
+    unsafe {
+      v8__Isolate__VisitHandlesWithClassIds(self, trampoline, arg as *mut _)
+    }
+  }
 }
 
+/// A closure enqueued with `Isolate::enqueue_microtask_closure()` together
+/// with the isolate it was enqueued on.
+struct PendingMicrotask(*mut Isolate, Box<dyn FnOnce(&mut HandleScope<()>)>);
+
 pub(crate) struct IsolateAnnex {
   create_param_allocations: Box<dyn Any>,
   slots: HashMap<TypeId, RawSlot, BuildTypeIdHasher>,
   finalizer_map: FinalizerMap,
+  pending_microtasks: HashMap<usize, Box<PendingMicrotask>>,
+  microtask_checkpoint_count: usize,
+  // Set while `perform_microtask_checkpoint()` runs, to detect reentrancy.
+  #[cfg(debug_assertions)]
+  in_microtask_checkpoint: bool,
   // The `isolate` and `isolate_mutex` fields are there so an `IsolateHandle`
   // (which may outlive the isolate itself) can determine whether the isolate
   // is still alive, and if so, get a reference to it. Safety rules:
@@ -791,6 +1447,9 @@ pub(crate) struct IsolateAnnex {
   //   `isolate` pointer.
   isolate: *mut Isolate,
   isolate_mutex: Mutex<()>,
+  // The threads that have entered the isolate, once per `enter()` call.
+  #[cfg(debug_assertions)]
+  entered_threads: Mutex<Vec<ThreadId>>,
 }
 
 impl IsolateAnnex {
@@ -802,8 +1461,14 @@ impl IsolateAnnex {
       create_param_allocations,
       slots: HashMap::default(),
       finalizer_map: FinalizerMap::default(),
+      pending_microtasks: HashMap::default(),
+      microtask_checkpoint_count: 0,
+      #[cfg(debug_assertions)]
+      in_microtask_checkpoint: false,
       isolate,
       isolate_mutex: Mutex::new(()),
+      #[cfg(debug_assertions)]
+      entered_threads: Mutex::default(),
     }
   }
 }
@@ -1030,6 +1695,19 @@ impl Default for HeapStatistics {
   }
 }
 
+impl<F> MapFnFrom<F> for AddCrashKeyCallback
+where
+  F: UnitType + Fn(CrashKeyId, &str),
+{
+  fn mapping() -> Self {
+    let f = |id, data, len| {
+      let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+      (F::get())(id, &std::string::String::from_utf8_lossy(bytes))
+    };
+    f.to_c_fn()
+  }
+}
+
 impl<'s, F> MapFnFrom<F> for PrepareStackTraceCallback<'s>
 where
   F: UnitType
@@ -1063,6 +1741,24 @@ where
   }
 }
 
+impl<'s, F> MapFnFrom<F> for ModifyCodeGenerationFromStringsCallback<'s>
+where
+  F: UnitType
+    + Fn(
+      &mut HandleScope<'s>,
+      Local<'s, Value>,
+      bool,
+    ) -> ModifyCodeGenerationFromStringsResult<'s>,
+{
+  fn mapping() -> Self {
+    let f = |context, source, is_code_like| {
+      let mut scope: CallbackScope = unsafe { CallbackScope::new(context) };
+      (F::get())(&mut scope, source, is_code_like).into()
+    };
+    f.to_c_fn()
+  }
+}
+
 /// A special hasher that is optimized for hashing `std::any::TypeId` values.
 /// `TypeId` values are actually 64-bit values which themselves come out of some
 /// hash function, so it's unnecessary to shuffle their bits any further.
@@ -10,6 +10,7 @@ use crate::support::SharedPtr;
 
 use std::any::Any;
 use std::convert::TryFrom;
+use std::ffi::c_void;
 use std::iter::once;
 use std::mem::size_of;
 use std::mem::MaybeUninit;
@@ -20,6 +21,21 @@ use std::ptr::null;
 /// isolate.
 pub type CounterLookupCallback = extern "C" fn(name: *const c_char) -> *mut i32;
 
+/// Creates a histogram with the given name and range, divided into `buckets`
+/// buckets. The returned pointer identifies the histogram in subsequent calls
+/// to the `AddHistogramSampleCallback`.
+pub type CreateHistogramCallback = extern "C" fn(
+  name: *const c_char,
+  min: int,
+  max: int,
+  buckets: usize,
+) -> *mut c_void;
+
+/// Records `sample` in a histogram previously returned by the
+/// `CreateHistogramCallback`.
+pub type AddHistogramSampleCallback =
+  extern "C" fn(histogram: *mut c_void, sample: int);
+
 /// Initial configuration parameters for a new Isolate.
 #[must_use]
 #[derive(Debug, Default)]
@@ -197,8 +213,8 @@ pub(crate) mod raw {
     pub constraints: ResourceConstraints,
     pub snapshot_blob: *const StartupData,
     pub counter_lookup_callback: Option<CounterLookupCallback>,
-    pub create_histogram_callback: *const Opaque, // CreateHistogramCallback
-    pub add_histogram_sample_callback: *const Opaque, // AddHistogramSampleCallback
+    pub create_histogram_callback: Option<CreateHistogramCallback>,
+    pub add_histogram_sample_callback: Option<AddHistogramSampleCallback>,
     pub array_buffer_allocator: *mut ArrayBufferAllocator,
     pub array_buffer_allocator_shared: SharedPtr<ArrayBufferAllocator>,
     pub external_references: *const intptr_t,
@@ -237,7 +253,7 @@ pub(crate) mod raw {
   }
 
   impl StartupData {
-    pub(super) fn boxed_header(data: &Allocation<[u8]>) -> Box<Self> {
+    pub(crate) fn boxed_header(data: &Allocation<[u8]>) -> Box<Self> {
       Box::new(Self {
         data: &data[0] as *const _ as *const char,
         raw_size: int::try_from(data.len()).unwrap(),
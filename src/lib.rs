@@ -33,7 +33,9 @@ mod array_buffer;
 mod array_buffer_view;
 mod bigint;
 mod context;
+pub mod cppgc;
 mod data;
+mod data_view;
 mod date;
 mod exception;
 mod external;
@@ -44,6 +46,7 @@ mod handle;
 pub mod icu;
 mod isolate;
 mod isolate_create_params;
+mod microtask;
 mod module;
 mod name;
 mod number;
@@ -75,6 +78,8 @@ mod wasm;
 pub mod inspector;
 pub mod json;
 pub mod script_compiler;
+#[cfg(feature = "serde")]
+pub mod serde;
 // This module is intentionally named "V8" rather than "v8" to match the
 // C++ namespace "v8::V8".
 #[allow(non_snake_case)]
@@ -82,6 +87,8 @@ pub mod V8;
 
 pub use array_buffer::*;
 pub use bigint::*;
+pub use context::ContextOptions;
+pub use context::ContinuationPreservedEmbedderDataScope;
 pub use data::*;
 pub use exception::*;
 pub use external_references::ExternalReference;
@@ -89,8 +96,14 @@ pub use external_references::ExternalReferences;
 pub use function::*;
 pub use handle::Global;
 pub use handle::Handle;
+pub use handle::HandleError;
 pub use handle::Local;
 pub use handle::Weak;
+pub use handle::WeakKey;
+pub use isolate::AbortOnUncaughtExceptionCallback;
+pub use isolate::AddCrashKeyCallback;
+pub use isolate::AllowWasmCodeGenerationCallback;
+pub use isolate::CrashKeyId;
 pub use isolate::HeapStatistics;
 pub use isolate::HostImportModuleDynamicallyCallback;
 pub use isolate::HostInitializeImportMetaObjectCallback;
@@ -98,12 +111,25 @@ pub use isolate::Isolate;
 pub use isolate::IsolateHandle;
 pub use isolate::MessageCallback;
 pub use isolate::MicrotasksPolicy;
+pub use isolate::ModifyCodeGenerationFromStringsCallback;
+pub use isolate::ModifyCodeGenerationFromStringsResult;
 pub use isolate::NearHeapLimitCallback;
 pub use isolate::OwnedIsolate;
+pub use isolate::PersistentHandleVisitor;
 pub use isolate::PromiseHook;
 pub use isolate::PromiseHookType;
 pub use isolate::PromiseRejectCallback;
+pub use isolate::RawModifyCodeGenerationFromStringsResult;
+pub use isolate::SnapshotObjectId;
+pub use isolate::TimeZoneDetection;
+pub use isolate::UseCounterCallback;
+pub use isolate::UseCounterFeature;
+pub use isolate_create_params::AddHistogramSampleCallback;
+pub use isolate_create_params::CounterLookupCallback;
+pub use isolate_create_params::CreateHistogramCallback;
 pub use isolate_create_params::CreateParams;
+pub use microtask::MicrotasksScope;
+pub use microtask::MicrotasksScopeType;
 pub use module::*;
 pub use object::*;
 pub use platform::new_default_platform;
@@ -132,6 +158,7 @@ pub use support::UniquePtr;
 pub use support::UniqueRef;
 pub use symbol::*;
 pub use template::*;
+pub use value::ValueExt;
 pub use value_deserializer::ValueDeserializer;
 pub use value_deserializer::ValueDeserializerHelper;
 pub use value_deserializer::ValueDeserializerImpl;
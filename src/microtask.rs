@@ -0,0 +1,79 @@
+// Copyright 2019-2021 the Deno authors. All rights reserved. MIT license.
+
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use crate::support::Opaque;
+use crate::Isolate;
+
+extern "C" {
+  fn v8__MicrotasksScope__NEW(
+    isolate: *mut Isolate,
+    scope_type: MicrotasksScopeType,
+  ) -> *mut RawMicrotasksScope;
+  fn v8__MicrotasksScope__DELETE(this: *mut RawMicrotasksScope);
+}
+
+/// Whether a `MicrotasksScope` runs the pending microtasks when it exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum MicrotasksScopeType {
+  /// Microtasks are run when the outermost `RunMicrotasks` scope exits.
+  RunMicrotasks,
+  /// Microtasks are not run when the scope exits, even if it is the
+  /// outermost one.
+  DoNotRunMicrotasks,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+struct RawMicrotasksScope(Opaque);
+
+/// This scope is used to control microtasks when `MicrotasksPolicy::Scoped`
+/// is used on the isolate. In this mode every non-primitive call to V8
+/// should be done inside some `MicrotasksScope`. Microtasks are executed
+/// when the topmost scope of type `RunMicrotasks` exits. A
+/// `DoNotRunMicrotasks` scope can be used to make a call into JavaScript
+/// without running microtasks afterwards, e.g. for a synchronous host
+/// operation that must not be interleaved with user microtasks.
+///
+/// The scope wraps another scope and dereferences to it, so it can be used
+/// everywhere the wrapped scope can be used.
+#[derive(Debug)]
+pub struct MicrotasksScope<'s, P> {
+  param: &'s mut P,
+  raw: *mut RawMicrotasksScope,
+}
+
+impl<'s, P: AsMut<Isolate>> MicrotasksScope<'s, P> {
+  pub fn new(param: &'s mut P, scope_type: MicrotasksScopeType) -> Self {
+    let isolate: *mut Isolate = param.as_mut();
+    let raw = unsafe { v8__MicrotasksScope__NEW(isolate, scope_type) };
+    Self { param, raw }
+  }
+}
+
+impl<'s, P: AsMut<Isolate>> AsMut<Isolate> for MicrotasksScope<'s, P> {
+  fn as_mut(&mut self) -> &mut Isolate {
+    self.param.as_mut()
+  }
+}
+
+impl<'s, P> Deref for MicrotasksScope<'s, P> {
+  type Target = P;
+  fn deref(&self) -> &P {
+    self.param
+  }
+}
+
+impl<'s, P> DerefMut for MicrotasksScope<'s, P> {
+  fn deref_mut(&mut self) -> &mut P {
+    self.param
+  }
+}
+
+impl<'s, P> Drop for MicrotasksScope<'s, P> {
+  fn drop(&mut self) {
+    unsafe { v8__MicrotasksScope__DELETE(self.raw) }
+  }
+}
@@ -374,10 +374,22 @@ impl Module {
     .into()
   }
 
+  /// Returns the context-independent part of this module, which can be used
+  /// to produce a code cache with `UnboundModuleScript::create_code_cache()`.
+  /// Compiling the same source with that cache in another context (or
+  /// isolate) avoids reparsing the module.
+  ///
+  /// # Panics
+  ///
+  /// Panics if this is not a source text module.
   pub fn get_unbound_module_script<'s>(
     &self,
     scope: &mut HandleScope<'s>,
   ) -> Local<'s, UnboundModuleScript> {
+    assert!(
+      self.is_source_text_module(),
+      "get_unbound_module_script() requires a source text module"
+    );
     unsafe {
       scope
         .cast_local(|_| v8__Module__GetUnboundModuleScript(self))
@@ -1,6 +1,7 @@
 use crate::isolate::Isolate;
 use crate::support::int;
 use crate::support::MapFnTo;
+use crate::support::Maybe;
 use crate::support::MaybeBool;
 use crate::AccessorNameGetterCallback;
 use crate::AccessorNameSetterCallback;
@@ -13,9 +14,16 @@ use crate::Name;
 use crate::Object;
 use crate::Private;
 use crate::PropertyAttribute;
+use crate::String;
 use crate::Value;
+use crate::WeakMap;
+use crate::WeakSet;
+use crate::NONE;
 use std::convert::TryFrom;
+use std::ffi::c_void;
 use std::num::NonZeroI32;
+use std::ptr::null;
+use std::ptr::null_mut;
 
 extern "C" {
   fn v8__Object__New(isolate: *mut Isolate) -> *const Object;
@@ -39,6 +47,15 @@ extern "C" {
     getter: AccessorNameGetterCallback,
     setter: AccessorNameSetterCallback,
   ) -> MaybeBool;
+  fn v8__Object__SetAccessorWithConfiguration(
+    this: *const Object,
+    context: *const Context,
+    key: *const Name,
+    getter: AccessorNameGetterCallback,
+    setter: Option<AccessorNameSetterCallback>,
+    data: *const Value,
+    attr: PropertyAttribute,
+  ) -> MaybeBool;
   fn v8__Object__Get(
     this: *const Object,
     context: *const Context,
@@ -105,6 +122,21 @@ extern "C" {
     context: *const Context,
     key: *const Name,
   ) -> MaybeBool;
+  fn v8__Object__GetPropertyAttributes(
+    this: *const Object,
+    context: *const Context,
+    key: *const Value,
+    out: *mut Maybe<PropertyAttribute>,
+  );
+  fn v8__Object__GetOwnPropertyDescriptor(
+    this: *const Object,
+    context: *const Context,
+    key: *const Name,
+  ) -> *const Value;
+  fn v8__Object__ObjectProtoToString(
+    this: *const Object,
+    context: *const Context,
+  ) -> *const String;
   fn v8__Object__Delete(
     this: *const Object,
     context: *const Context,
@@ -130,6 +162,15 @@ extern "C" {
     index: int,
     value: *const Value,
   );
+  fn v8__Object__GetAlignedPointerFromInternalField(
+    this: *const Object,
+    index: int,
+  ) -> *mut c_void;
+  fn v8__Object__SetAlignedPointerInInternalField(
+    this: *const Object,
+    index: int,
+    value: *mut c_void,
+  );
   fn v8__Object__GetPrivate(
     this: *const Object,
     context: *const Context,
@@ -151,6 +192,36 @@ extern "C" {
     context: *const Context,
     key: *const Private,
   ) -> MaybeBool;
+  fn v8__Object__GetRealNamedProperty(
+    this: *const Object,
+    context: *const Context,
+    key: *const Name,
+  ) -> *const Value;
+  fn v8__Object__GetRealNamedPropertyAttributes(
+    this: *const Object,
+    context: *const Context,
+    key: *const Name,
+    out: *mut Maybe<PropertyAttribute>,
+  );
+  fn v8__Object__HasRealNamedProperty(
+    this: *const Object,
+    context: *const Context,
+    key: *const Name,
+  ) -> MaybeBool;
+  fn v8__Object__HasRealIndexedProperty(
+    this: *const Object,
+    context: *const Context,
+    index: u32,
+  ) -> MaybeBool;
+  fn v8__Object__HasRealNamedCallbackProperty(
+    this: *const Object,
+    context: *const Context,
+    key: *const Name,
+  ) -> MaybeBool;
+  fn v8__Object__PreviewEntries(
+    this: *const Object,
+    is_key_value: *mut bool,
+  ) -> *const Array;
 
   fn v8__Array__New(isolate: *mut Isolate, length: int) -> *const Array;
   fn v8__Array__New_with_elements(
@@ -184,6 +255,84 @@ extern "C" {
   ) -> MaybeBool;
   fn v8__Map__Size(map: *const Map) -> usize;
   fn v8__Map__As__Array(this: *const Map) -> *const Array;
+  fn v8__WeakMap__New(isolate: *mut Isolate) -> *const WeakMap;
+  fn v8__WeakMap__Get(
+    this: *const WeakMap,
+    isolate: *mut Isolate,
+    key: *const Object,
+  ) -> *const Value;
+  fn v8__WeakMap__Set(
+    this: *const WeakMap,
+    isolate: *mut Isolate,
+    key: *const Object,
+    value: *const Value,
+  );
+  fn v8__WeakMap__Has(
+    this: *const WeakMap,
+    isolate: *mut Isolate,
+    key: *const Object,
+  ) -> bool;
+  fn v8__WeakMap__Delete(
+    this: *const WeakMap,
+    isolate: *mut Isolate,
+    key: *const Object,
+  ) -> bool;
+  fn v8__WeakSet__New(isolate: *mut Isolate) -> *const WeakSet;
+  fn v8__WeakSet__Add(
+    this: *const WeakSet,
+    isolate: *mut Isolate,
+    key: *const Object,
+  );
+  fn v8__WeakSet__Has(
+    this: *const WeakSet,
+    isolate: *mut Isolate,
+    key: *const Object,
+  ) -> bool;
+  fn v8__WeakSet__Delete(
+    this: *const WeakSet,
+    isolate: *mut Isolate,
+    key: *const Object,
+  ) -> bool;
+}
+
+/// Configuration of an accessor installed with
+/// `Object::set_accessor_with_configuration()`.
+pub struct AccessorConfiguration<'s> {
+  getter: AccessorNameGetterCallback<'s>,
+  setter: Option<AccessorNameSetterCallback<'s>>,
+  data: Option<Local<'s, Value>>,
+  property_attribute: PropertyAttribute,
+}
+
+impl<'s> AccessorConfiguration<'s> {
+  pub fn new(getter: impl MapFnTo<AccessorNameGetterCallback<'s>>) -> Self {
+    Self {
+      getter: getter.map_fn_to(),
+      setter: None,
+      data: None,
+      property_attribute: NONE,
+    }
+  }
+
+  pub fn setter(
+    mut self,
+    setter: impl MapFnTo<AccessorNameSetterCallback<'s>>,
+  ) -> Self {
+    self.setter = Some(setter.map_fn_to());
+    self
+  }
+
+  /// Sets the value returned by `PropertyCallbackArguments::data()` in the
+  /// getter and setter.
+  pub fn data(mut self, data: Local<'s, Value>) -> Self {
+    self.data = Some(data);
+    self
+  }
+
+  pub fn property_attribute(mut self, attr: PropertyAttribute) -> Self {
+    self.property_attribute = attr;
+    self
+  }
 }
 
 impl Object {
@@ -198,13 +347,19 @@ impl Object {
   /// the newly created object won't have a prototype at all). This is similar
   /// to Object.create(). All properties will be created as enumerable,
   /// configurable and writable properties.
+  ///
+  /// Panics if `names` and `values` have different lengths.
   pub fn with_prototype_and_properties<'s>(
     scope: &mut HandleScope<'s>,
     prototype_or_null: Local<'s, Value>,
     names: &[Local<Name>],
     values: &[Local<Value>],
   ) -> Local<'s, Object> {
-    assert_eq!(names.len(), values.len());
+    assert_eq!(
+      names.len(),
+      values.len(),
+      "names and values must have the same length"
+    );
     let names = Local::slice_into_raw(names);
     let values = Local::slice_into_raw(values);
     unsafe {
@@ -380,6 +535,28 @@ impl Object {
     .into()
   }
 
+  /// Sets an accessor on this object, using the setter, data value and
+  /// property attributes given in `configuration`.
+  pub fn set_accessor_with_configuration(
+    &self,
+    scope: &mut HandleScope,
+    name: Local<Name>,
+    configuration: AccessorConfiguration,
+  ) -> Option<bool> {
+    unsafe {
+      v8__Object__SetAccessorWithConfiguration(
+        self,
+        &*scope.get_current_context(),
+        &*name,
+        configuration.getter,
+        configuration.setter,
+        configuration.data.map_or_else(null, |p| &*p),
+        configuration.property_attribute,
+      )
+    }
+    .into()
+  }
+
   /// Returns the V8 hash value for this value. The current implementation
   /// uses a hidden property to store the identity hash.
   ///
@@ -389,7 +566,11 @@ impl Object {
     unsafe { NonZeroI32::new_unchecked(v8__Object__GetIdentityHash(self)) }
   }
 
-  /// Returns the context in which the object was created.
+  /// Returns the context in which the object was created. This is the realm
+  /// whose intrinsics (e.g. `Object.prototype`) the object is associated
+  /// with, which may differ from the current context when objects are passed
+  /// between contexts. Returns `None` if the creation context can't be
+  /// determined, e.g. for remote objects.
   pub fn get_creation_context<'s>(
     &self,
     scope: &mut HandleScope<'s>,
@@ -466,6 +647,113 @@ impl Object {
     .into()
   }
 
+  /// Gets the property attributes of a property of this object. Like the
+  /// `in` operator, the lookup walks the prototype chain, so the attributes
+  /// of an inherited property are returned if there is no own property with
+  /// that name. Note that V8 reports the attributes of a property that does
+  /// not exist anywhere on the chain as `NONE`; use `has()` to tell the two
+  /// cases apart, or `has_own_property()` to tell own properties from
+  /// inherited ones. Returns `None` if an exception was thrown, e.g. by a
+  /// proxy trap.
+  pub fn get_property_attributes<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+    key: Local<Value>,
+  ) -> Option<PropertyAttribute> {
+    let mut out = Maybe::<PropertyAttribute>::default();
+    unsafe {
+      v8__Object__GetPropertyAttributes(
+        self,
+        &*scope.get_current_context(),
+        &*key,
+        &mut out,
+      )
+    };
+    out.into()
+  }
+
+  /// Returns Object.getOwnPropertyDescriptor as per ES2016 section 19.1.2.6,
+  /// i.e. a descriptor object for an own property, or `undefined` if there
+  /// is no such property.
+  pub fn get_own_property_descriptor<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+    key: Local<Name>,
+  ) -> Option<Local<'s, Value>> {
+    unsafe {
+      scope.cast_local(|sd| {
+        v8__Object__GetOwnPropertyDescriptor(
+          self,
+          sd.get_current_context(),
+          &*key,
+        )
+      })
+    }
+  }
+
+  /// Call builtin Object.prototype.toString on this object. This is
+  /// different from `Value::to_string()` in that it does not call a
+  /// user-defined `toString` method, and it returns e.g.
+  /// `"[object Object]"`.
+  pub fn object_proto_to_string<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Option<Local<'s, String>> {
+    unsafe {
+      scope.cast_local(|sd| {
+        v8__Object__ObjectProtoToString(self, sd.get_current_context())
+      })
+    }
+  }
+
+  /// Creates a shallow copy of this object. V8 has no single primitive for
+  /// this, so the copy is built by enumerating properties:
+  ///
+  /// - The copy has the same prototype as this object (which may be null).
+  /// - Only own, enumerable, string-keyed properties are considered, i.e. the
+  ///   keys returned by `get_own_property_names()`. Symbol-keyed and
+  ///   non-enumerable properties are not copied.
+  /// - Data properties are copied by value. Nested objects are shared
+  ///   between the original and the copy, not cloned recursively.
+  /// - Accessor properties are skipped; their getters are not invoked.
+  /// - All copied properties are created as enumerable, configurable and
+  ///   writable, regardless of their attributes on this object.
+  /// - Internal fields, private properties and the identity hash are not
+  ///   copied.
+  ///
+  /// Returns `None` if an exception was thrown while enumerating the
+  /// properties, e.g. by a proxy trap.
+  pub fn shallow_clone<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Option<Local<'s, Object>> {
+    let prototype = self.get_prototype(scope)?;
+    let keys = self.get_own_property_names(scope)?;
+    let value_key = String::new(scope, "value")?.into();
+
+    let mut names: Vec<Local<Name>> = Vec::new();
+    let mut values: Vec<Local<Value>> = Vec::new();
+    for i in 0..keys.length() {
+      let key = keys.get_index(scope, i)?.to_string(scope)?.into();
+      let descriptor = self.get_own_property_descriptor(scope, key)?;
+      let descriptor = match Local::<Object>::try_from(descriptor) {
+        Ok(descriptor) => descriptor,
+        // The property was removed while enumerating, e.g. by a proxy.
+        Err(_) => continue,
+      };
+      if !descriptor.has_own_property(scope, value_key)? {
+        // An accessor property.
+        continue;
+      }
+      names.push(key);
+      values.push(descriptor.get(scope, value_key.into())?);
+    }
+
+    Some(Object::with_prototype_and_properties(
+      scope, prototype, &names, &values,
+    ))
+  }
+
   pub fn delete<'s>(
     &self,
     scope: &mut HandleScope<'s>,
@@ -540,6 +828,48 @@ impl Object {
     false
   }
 
+  /// Gets a 2-byte-aligned native pointer from an internal field. This field
+  /// must have been set by `set_aligned_pointer_in_internal_field()`,
+  /// everything else leads to undefined behavior. Returns a null pointer when
+  /// the index is out of bounds.
+  ///
+  /// # Safety
+  ///
+  /// The internal field at `index` must hold an aligned pointer.
+  pub unsafe fn get_aligned_pointer_from_internal_field(
+    &self,
+    index: usize,
+  ) -> *mut c_void {
+    if index < self.internal_field_count() {
+      if let Ok(index) = int::try_from(index) {
+        return v8__Object__GetAlignedPointerFromInternalField(self, index);
+      }
+    }
+    null_mut()
+  }
+
+  /// Sets a 2-byte-aligned native pointer in an internal field. To retrieve
+  /// such a field, `get_aligned_pointer_from_internal_field()` must be used.
+  /// This is faster than wrapping the pointer in an `External`. Returns false
+  /// when the index is out of bounds, true otherwise.
+  ///
+  /// # Safety
+  ///
+  /// `value` must be aligned to at least 2 bytes; V8 aborts otherwise.
+  pub unsafe fn set_aligned_pointer_in_internal_field(
+    &self,
+    index: usize,
+    value: *mut c_void,
+  ) -> bool {
+    if index < self.internal_field_count() {
+      if let Ok(index) = int::try_from(index) {
+        v8__Object__SetAlignedPointerInInternalField(self, index, value);
+        return true;
+      }
+    }
+    false
+  }
+
   /// Functionality for private properties.
   /// This is an experimental feature, use at your own risk.
   /// Note: Private properties are not inherited. Do not rely on this, since it
@@ -606,6 +936,109 @@ impl Object {
     }
     .into()
   }
+
+  /// Looks up a property on the object or in its prototype chain without
+  /// calling interceptors. Returns None if no real property was located.
+  pub fn get_real_named_property<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+    key: Local<Name>,
+  ) -> Option<Local<'s, Value>> {
+    unsafe {
+      scope.cast_local(|sd| {
+        v8__Object__GetRealNamedProperty(self, sd.get_current_context(), &*key)
+      })
+    }
+  }
+
+  /// Gets the property attributes of a real property on the object or in its
+  /// prototype chain without calling interceptors. Returns None if no real
+  /// property was located.
+  pub fn get_real_named_property_attributes(
+    &self,
+    scope: &mut HandleScope,
+    key: Local<Name>,
+  ) -> Option<PropertyAttribute> {
+    let mut out = Maybe::<PropertyAttribute>::default();
+    unsafe {
+      v8__Object__GetRealNamedPropertyAttributes(
+        self,
+        &*scope.get_current_context(),
+        &*key,
+        &mut out,
+      )
+    };
+    out.into()
+  }
+
+  /// Returns true if the object has an own property named `key`, without
+  /// calling interceptors. Unlike `has_own_property()`, this can be used from
+  /// within an interceptor for the same object without recursing into it.
+  pub fn has_real_named_property(
+    &self,
+    scope: &mut HandleScope,
+    key: Local<Name>,
+  ) -> Option<bool> {
+    unsafe {
+      v8__Object__HasRealNamedProperty(
+        self,
+        &*scope.get_current_context(),
+        &*key,
+      )
+    }
+    .into()
+  }
+
+  /// Returns true if the object has an own element at `index`, without
+  /// calling interceptors.
+  pub fn has_real_indexed_property(
+    &self,
+    scope: &mut HandleScope,
+    index: u32,
+  ) -> Option<bool> {
+    unsafe {
+      v8__Object__HasRealIndexedProperty(
+        self,
+        &*scope.get_current_context(),
+        index,
+      )
+    }
+    .into()
+  }
+
+  /// Returns true if the object has an own accessor property named `key`,
+  /// either a native accessor or a JavaScript getter/setter pair, without
+  /// calling interceptors.
+  pub fn has_real_named_callback_property(
+    &self,
+    scope: &mut HandleScope,
+    key: Local<Name>,
+  ) -> Option<bool> {
+    unsafe {
+      v8__Object__HasRealNamedCallbackProperty(
+        self,
+        &*scope.get_current_context(),
+        &*key,
+      )
+    }
+    .into()
+  }
+
+  /// If this object is a Set, Map, WeakSet or WeakMap, or an iterator over
+  /// one of them, returns a flat array with the entries of the collection,
+  /// without running any JavaScript. The returned boolean is true if the
+  /// array consists of alternating keys and values, as is the case for maps
+  /// and map iterators over entries. Returns None for any other object.
+  pub fn preview_entries<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Option<(Local<'s, Array>, bool)> {
+    let mut is_key_value = false;
+    let entries = unsafe {
+      scope.cast_local(|_| v8__Object__PreviewEntries(self, &mut is_key_value))
+    }?;
+    Some((entries, is_key_value))
+  }
 }
 
 /// Object integrity levels can be used to restrict what can be done to an
@@ -657,6 +1090,18 @@ impl Array {
   pub fn length(&self) -> u32 {
     unsafe { v8__Array__Length(self) }
   }
+
+  /// Returns an iterator that lazily reads the elements of this array, in
+  /// index order, using `get_index()`. The length of the array is read once
+  /// when the iterator is created. Since element access may invoke getters
+  /// that throw, iteration stops at the first element that could not be read;
+  /// the exception is left pending on the isolate.
+  pub fn iter<'a, 's: 'a>(
+    &'a self,
+    scope: &'a mut HandleScope<'s>,
+  ) -> impl Iterator<Item = Local<'s, Value>> + 'a {
+    (0..self.length()).map_while(move |index| self.get_index(scope, index))
+  }
 }
 
 impl Map {
@@ -719,3 +1164,60 @@ impl Map {
     unsafe { scope.cast_local(|_| v8__Map__As__Array(self)) }.unwrap()
   }
 }
+
+impl WeakMap {
+  pub fn new<'s>(scope: &mut HandleScope<'s>) -> Local<'s, WeakMap> {
+    unsafe { scope.cast_local(|sd| v8__WeakMap__New(sd.get_isolate_ptr())) }
+      .unwrap()
+  }
+
+  /// Returns the value stored for `key`, or `undefined` if there is none.
+  pub fn get<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+    key: Local<Object>,
+  ) -> Local<'s, Value> {
+    unsafe {
+      scope.cast_local(|sd| v8__WeakMap__Get(self, sd.get_isolate_ptr(), &*key))
+    }
+    .unwrap()
+  }
+
+  pub fn set(
+    &self,
+    scope: &mut HandleScope,
+    key: Local<Object>,
+    value: Local<Value>,
+  ) {
+    unsafe { v8__WeakMap__Set(self, scope.get_isolate_ptr(), &*key, &*value) }
+  }
+
+  pub fn has(&self, scope: &mut HandleScope, key: Local<Object>) -> bool {
+    unsafe { v8__WeakMap__Has(self, scope.get_isolate_ptr(), &*key) }
+  }
+
+  /// Removes the entry for `key`. Returns true if there was one.
+  pub fn delete(&self, scope: &mut HandleScope, key: Local<Object>) -> bool {
+    unsafe { v8__WeakMap__Delete(self, scope.get_isolate_ptr(), &*key) }
+  }
+}
+
+impl WeakSet {
+  pub fn new<'s>(scope: &mut HandleScope<'s>) -> Local<'s, WeakSet> {
+    unsafe { scope.cast_local(|sd| v8__WeakSet__New(sd.get_isolate_ptr())) }
+      .unwrap()
+  }
+
+  pub fn add(&self, scope: &mut HandleScope, key: Local<Object>) {
+    unsafe { v8__WeakSet__Add(self, scope.get_isolate_ptr(), &*key) }
+  }
+
+  pub fn has(&self, scope: &mut HandleScope, key: Local<Object>) -> bool {
+    unsafe { v8__WeakSet__Has(self, scope.get_isolate_ptr(), &*key) }
+  }
+
+  /// Removes `key` from the set. Returns true if it was in the set.
+  pub fn delete(&self, scope: &mut HandleScope, key: Local<Object>) -> bool {
+    unsafe { v8__WeakSet__Delete(self, scope.get_isolate_ptr(), &*key) }
+  }
+}
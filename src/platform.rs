@@ -55,6 +55,7 @@ pub struct Platform(Opaque);
 /// |thread_pool_size| is the number of worker threads to allocate for
 /// background jobs. If a value of zero is passed, a suitable default
 /// based on the current number of processors online will be chosen.
+/// Values larger than 16 are clamped to 16, the maximum V8 supports.
 /// If |idle_task_support| is enabled then the platform will accept idle
 /// tasks (IdleTasksEnabled will return true) and will rely on the embedder
 /// calling v8::platform::RunIdleTasks to process the idle tasks.
@@ -83,6 +84,7 @@ impl Platform {
   /// |thread_pool_size| is the number of worker threads to allocate for
   /// background jobs. If a value of zero is passed, a suitable default
   /// based on the current number of processors online will be chosen.
+  /// Values larger than 16 are clamped to 16, the maximum V8 supports.
   /// If |idle_task_support| is enabled then the platform will accept idle
   /// tasks (IdleTasksEnabled will return true) and will rely on the embedder
   /// calling v8::platform::RunIdleTasks to process the idle tasks.
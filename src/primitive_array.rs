@@ -41,16 +41,23 @@ impl PrimitiveArray {
     .unwrap()
   }
 
+  /// Returns the number of elements in the array.
   pub fn length(&self) -> usize {
     unsafe { v8__PrimitiveArray__Length(self) as usize }
   }
 
+  /// Stores `item` at `index`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
   pub fn set<'s>(
     &self,
     scope: &mut HandleScope<'s>,
     index: usize,
     item: Local<'_, Primitive>,
   ) {
+    assert!(index < self.length(), "PrimitiveArray index out of bounds");
     unsafe {
       v8__PrimitiveArray__Set(
         self,
@@ -61,11 +68,18 @@ impl PrimitiveArray {
     }
   }
 
+  /// Returns the element at `index`. Elements that were never set are
+  /// `undefined`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
   pub fn get<'s>(
     &self,
     scope: &mut HandleScope<'s>,
     index: usize,
   ) -> Local<'s, Primitive> {
+    assert!(index < self.length(), "PrimitiveArray index out of bounds");
     unsafe {
       scope.cast_local(|sd| {
         v8__PrimitiveArray__Get(self, sd.get_isolate_ptr(), index as int)
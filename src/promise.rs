@@ -28,6 +28,7 @@ extern "C" {
   ) -> MaybeBool;
   fn v8__Promise__State(this: *const Promise) -> PromiseState;
   fn v8__Promise__HasHandler(this: *const Promise) -> bool;
+  fn v8__Promise__MarkAsHandled(this: *const Promise);
   fn v8__Promise__Result(this: *const Promise) -> *const Value;
   fn v8__Promise__Catch(
     this: *const Promise,
@@ -77,6 +78,13 @@ impl Promise {
     unsafe { v8__Promise__HasHandler(&*self) }
   }
 
+  /// Marks this promise as handled to avoid reporting unhandled rejections.
+  /// The promise reject callback is not called with
+  /// `PromiseRejectWithNoHandler` if the promise is rejected afterwards.
+  pub fn mark_as_handled(&self) {
+    unsafe { v8__Promise__MarkAsHandled(&*self) }
+  }
+
   /// Returns the content of the [[PromiseResult]] field. The Promise must not
   /// be pending.
   pub fn result<'s>(&self, scope: &mut HandleScope<'s>) -> Local<'s, Value> {
@@ -82,6 +82,8 @@ use std::alloc::Layout;
 use std::any::type_name;
 use std::cell::Cell;
 use std::convert::TryInto;
+use std::io;
+use std::io::Write;
 
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
@@ -104,6 +106,8 @@ use crate::Object;
 use crate::OwnedIsolate;
 use crate::Primitive;
 use crate::PromiseRejectMessage;
+use crate::SnapshotObjectId;
+use crate::StackTrace;
 use crate::Value;
 
 /// Stack-allocated class which sets the execution context for all operations
@@ -144,6 +148,14 @@ impl<'s, P: param::NewContextScope<'s>> ContextScope<'s, P> {
 /// garbage collector will no longer track the object stored in the
 /// handle and may deallocate it.  The behavior of accessing a handle
 /// for which the handle scope has been deleted is undefined.
+///
+/// The type parameter `C` records whether a context has been entered:
+/// `HandleScope<'s, ()>` is a scope without a context, while
+/// `HandleScope<'s>` (i.e. `HandleScope<'s, Context>`) is one in which a
+/// context is active. APIs that need a context, such as creating objects or
+/// running scripts, only accept the latter. Such a scope is obtained by
+/// wrapping a context-less scope in a `ContextScope`, or in a single step
+/// with `HandleScope::with_context()`.
 #[derive(Debug)]
 pub struct HandleScope<'s, C = Context> {
   _data: NonNull<data::ScopeData>,
@@ -195,6 +207,76 @@ impl<'s> HandleScope<'s> {
       unsafe { raw::v8__Isolate__GetEnteredOrMicrotaskContext(isolate_ptr) };
     unsafe { Local::from_raw(context_ptr) }.unwrap()
   }
+
+  /// Writes the current JavaScript stack trace to `out` as text, one frame
+  /// per line, formatted like the frames of `Error.prototype.stack`, e.g.
+  /// `    at inner (main.js:1:20)`. This is meant for diagnostics, e.g. from a
+  /// panic hook that runs inside a native callback. Nothing is written when
+  /// no JavaScript is running.
+  pub fn print_current_stack_trace(
+    &mut self,
+    out: &mut dyn Write,
+  ) -> io::Result<()> {
+    let scope = &mut HandleScope::new(self);
+    let stack_trace = match StackTrace::current_stack_trace(scope, 1 << 16) {
+      Some(stack_trace) => stack_trace,
+      None => return Ok(()),
+    };
+    for index in 0..stack_trace.get_frame_count() {
+      let frame = match stack_trace.get_frame(scope, index) {
+        Some(frame) => frame,
+        None => continue,
+      };
+      let script_name = frame
+        .get_script_name_or_source_url(scope)
+        .map(|name| name.to_rust_string_lossy(scope))
+        .unwrap_or_else(|| "<anonymous>".to_owned());
+      let location = format!(
+        "{}:{}:{}",
+        script_name,
+        frame.get_line_number(),
+        frame.get_column()
+      );
+      let function_name = frame
+        .get_function_name(scope)
+        .map(|name| name.to_rust_string_lossy(scope))
+        .filter(|name| !name.is_empty());
+      match function_name {
+        Some(function_name) => {
+          writeln!(out, "    at {} ({})", function_name, location)?
+        }
+        None => writeln!(out, "    at {}", location)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<'s, C> HandleScope<'s, C> {
+  /// Opens a nested `HandleScope`, runs `f` inside it and closes the scope
+  /// again. See `Isolate::with_handle_scope()`.
+  pub fn with_handle_scope<R>(
+    &mut self,
+    f: impl for<'t> FnOnce(&mut HandleScope<'t, C>) -> R,
+  ) -> R {
+    f(&mut HandleScope::new(self))
+  }
+
+  /// Opens a nested `EscapableHandleScope`, runs `f` inside it and closes
+  /// the scope again. `f` can use `EscapableHandleScope::escape()` to return
+  /// a single handle that outlives the nested scope and is bound to this
+  /// scope instead.
+  ///
+  /// Helper functions that take a `&mut HandleScope<'s>` and return a
+  /// `Local<'s, T>` can use this to keep their temporary handles from
+  /// accumulating in the caller's scope; such helpers compose, because each
+  /// one escapes its result into the scope it was given.
+  pub fn escapable<R>(
+    &mut self,
+    f: impl for<'t> FnOnce(&mut EscapableHandleScope<'t, 's, C>) -> R,
+  ) -> R {
+    f(&mut EscapableHandleScope::new(self))
+  }
 }
 
 impl<'s> HandleScope<'s, ()> {
@@ -204,6 +286,11 @@ impl<'s> HandleScope<'s, ()> {
   /// after the exception has been handled does it become legal to invoke
   /// JavaScript operations.
   ///
+  /// The exception is observed by the innermost `TryCatch`, if any, for
+  /// which `has_caught()` returns true and `has_terminated()` returns false.
+  /// Calling `TryCatch::reset()` clears it again. Use
+  /// `Isolate::terminate_execution()` for uncatchable termination instead.
+  ///
   /// This function always returns the `undefined` value.
   pub fn throw_exception(
     &mut self,
@@ -217,6 +304,20 @@ impl<'s> HandleScope<'s, ()> {
     .unwrap()
   }
 
+  /// Returns the heap object with the given `id`, as returned by
+  /// `Isolate::get_heap_object_id()`, or `None` if no such object exists
+  /// (e.g. because it has been garbage collected).
+  pub fn find_heap_object_by_id(
+    &mut self,
+    id: SnapshotObjectId,
+  ) -> Option<Local<'s, Value>> {
+    unsafe {
+      self.cast_local(|sd| {
+        raw::v8__HeapProfiler__FindObjectById(sd.get_isolate_ptr(), id)
+      })
+    }
+  }
+
   pub(crate) unsafe fn cast_local<T>(
     &mut self,
     f: impl FnOnce(&mut data::ScopeData) -> *const T,
@@ -498,6 +599,7 @@ where
 ///   - `&mut OwnedIsolate`
 ///
 /// A `CallbackScope`, with context, can be created from:
+///   - `&mut Isolate`, with `CallbackScope::with_current_context()`
 ///   - `Local<Context>`
 ///   - `Local<Message>`
 ///   - `Local<Object>`
@@ -520,6 +622,27 @@ impl<'s> CallbackScope<'s> {
       .new_callback_scope_data(context)
       .as_scope()
   }
+
+  /// Creates a `CallbackScope` that enters the context the isolate is
+  /// currently running in. This is useful in callbacks that only receive an
+  /// `&mut Isolate`, but are invoked while JavaScript is running. Returns
+  /// `None` if the isolate has no current context.
+  ///
+  /// # Safety
+  ///
+  /// Must only be called from inside a callback that V8 invokes on the thread
+  /// that has entered `isolate`, while that isolate is running. No other
+  /// Rust scope for the isolate may be active at the same time, i.e. the
+  /// callback must not have been given one, and the returned scope must be
+  /// dropped before the callback returns.
+  pub unsafe fn with_current_context(isolate: &'s mut Isolate) -> Option<Self> {
+    let context_ptr = raw::v8__Isolate__GetCurrentContext(isolate);
+    let context = Local::<Context>::from_raw(context_ptr)?;
+    let scope = data::ScopeData::get_current_mut(isolate)
+      .new_callback_scope_data(Some(context))
+      .as_scope();
+    Some(scope)
+  }
 }
 
 macro_rules! impl_as {
@@ -1054,6 +1177,7 @@ pub(crate) mod data {
         &mut Option<raw::ContextScope>,
       ),
     {
+      unsafe { self.isolate.as_ref() }.assert_entered_on_current_thread();
       self.new_scope_data_with(|data| {
         let isolate = data.isolate;
         data.scope_type_specific_data.init_with(|| {
@@ -1629,6 +1753,10 @@ mod raw {
       isolate: *mut Isolate,
       exception: *const Value,
     ) -> *const Value;
+    pub(super) fn v8__HeapProfiler__FindObjectById(
+      isolate: *mut Isolate,
+      id: SnapshotObjectId,
+    ) -> *const Value;
     pub(super) fn v8__Isolate__GetDataFromSnapshotOnce(
       this: *mut Isolate,
       index: usize,
@@ -3,6 +3,7 @@ use std::mem::MaybeUninit;
 use std::ptr::null;
 
 use crate::Context;
+use crate::Data;
 use crate::HandleScope;
 use crate::Isolate;
 use crate::Local;
@@ -42,6 +43,7 @@ extern "C" {
     resource_is_opaque: bool,
     is_wasm: bool,
     is_module: bool,
+    host_defined_options: *const Data,
   );
 }
 
@@ -102,6 +104,38 @@ impl<'s> ScriptOrigin<'s> {
     resource_is_opaque: bool,
     is_wasm: bool,
     is_module: bool,
+  ) -> Self {
+    Self::with_host_defined_options(
+      scope,
+      resource_name,
+      resource_line_offset,
+      resource_column_offset,
+      resource_is_shared_cross_origin,
+      script_id,
+      source_map_url,
+      resource_is_opaque,
+      is_wasm,
+      is_module,
+      None,
+    )
+  }
+
+  /// Like `new()`, but also attaches host-defined options to the origin.
+  /// They are passed back to the embedder, e.g. to the dynamic import
+  /// callback, for scripts and modules compiled with this origin.
+  #[allow(clippy::too_many_arguments)]
+  pub fn with_host_defined_options(
+    scope: &mut HandleScope<'s, ()>,
+    resource_name: Local<'s, Value>,
+    resource_line_offset: i32,
+    resource_column_offset: i32,
+    resource_is_shared_cross_origin: bool,
+    script_id: i32,
+    source_map_url: Local<'s, Value>,
+    resource_is_opaque: bool,
+    is_wasm: bool,
+    is_module: bool,
+    host_defined_options: Option<Local<'s, Data>>,
   ) -> Self {
     unsafe {
       let mut buf = std::mem::MaybeUninit::<ScriptOrigin>::uninit();
@@ -117,6 +151,7 @@ impl<'s> ScriptOrigin<'s> {
         resource_is_opaque,
         is_wasm,
         is_module,
+        host_defined_options.map_or_else(null, |h| &*h),
       );
       buf.assume_init()
     }
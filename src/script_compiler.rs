@@ -1,6 +1,8 @@
 // Copyright 2019-2021 the Deno authors. All rights reserved. MIT license.
+use std::ffi::c_void;
 use std::{marker::PhantomData, mem::MaybeUninit};
 
+use crate::support::Opaque;
 use crate::Function;
 use crate::Local;
 use crate::Module;
@@ -56,6 +58,29 @@ extern "C" {
   ) -> *const UnboundScript;
 
   fn v8__ScriptCompiler__CachedDataVersionTag() -> u32;
+
+  fn v8__ScriptCompiler__StreamedSource__NEW(
+    stream: *mut c_void,
+    vtable: *const SourceStreamVtable,
+    encoding: StreamedSourceEncoding,
+  ) -> *mut RawStreamedSource;
+  fn v8__ScriptCompiler__StreamedSource__DELETE(this: *mut RawStreamedSource);
+  fn v8__ScriptCompiler__StartStreaming(
+    isolate: *mut Isolate,
+    source: *mut RawStreamedSource,
+  ) -> *mut RawScriptStreamingTask;
+  fn v8__ScriptCompiler__ScriptStreamingTask__Run(
+    this: *mut RawScriptStreamingTask,
+  );
+  fn v8__ScriptCompiler__ScriptStreamingTask__DELETE(
+    this: *mut RawScriptStreamingTask,
+  );
+  fn v8__ScriptCompiler__Compile__with_StreamedSource(
+    context: *const Context,
+    source: *mut RawStreamedSource,
+    full_source_string: *const String,
+    origin: *const ScriptOrigin,
+  ) -> *const Script;
 }
 
 /// Source code which can then be compiled to a UnboundScript or Script.
@@ -299,6 +324,199 @@ pub fn compile_unbound_script<'s>(
   }
 }
 
+/// A stream of source code chunks for streaming compilation. See
+/// `start_streaming()`.
+///
+/// `get_more_data()` is called on the thread that runs the
+/// `ScriptStreamingTask`. It may block until data is available, and returns
+/// `None` (or an empty chunk) once the end of the source has been reached.
+/// A chunk may end in the middle of a multi-byte character.
+pub trait ExternalSourceStream: Send {
+  fn get_more_data(&mut self) -> Option<Vec<u8>>;
+}
+
+impl<F> ExternalSourceStream for F
+where
+  F: FnMut() -> Option<Vec<u8>> + Send,
+{
+  fn get_more_data(&mut self) -> Option<Vec<u8>> {
+    self()
+  }
+}
+
+/// The encoding of the chunks produced by an `ExternalSourceStream`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamedSourceEncoding {
+  OneByte,
+  TwoByte,
+  Utf8,
+  Windows1252,
+}
+
+#[repr(C)]
+struct SourceStreamVtable {
+  get_more_data:
+    unsafe extern "C" fn(stream: *mut c_void, src: *mut *const u8) -> usize,
+  drop: unsafe extern "C" fn(stream: *mut c_void),
+}
+
+struct SourceStream {
+  stream: Box<dyn ExternalSourceStream>,
+  // The last chunk handed to V8, which copies it before asking for more.
+  chunk: Vec<u8>,
+}
+
+unsafe extern "C" fn source_stream_get_more_data(
+  stream: *mut c_void,
+  src: *mut *const u8,
+) -> usize {
+  let stream = &mut *(stream as *mut SourceStream);
+  stream.chunk = stream.stream.get_more_data().unwrap_or_default();
+  *src = stream.chunk.as_ptr();
+  stream.chunk.len()
+}
+
+unsafe extern "C" fn source_stream_drop(stream: *mut c_void) {
+  drop(Box::from_raw(stream as *mut SourceStream));
+}
+
+static SOURCE_STREAM_VTABLE: SourceStreamVtable = SourceStreamVtable {
+  get_more_data: source_stream_get_more_data,
+  drop: source_stream_drop,
+};
+
+#[repr(C)]
+#[derive(Debug)]
+struct RawStreamedSource(Opaque);
+
+/// Source code that is compiled while it is being downloaded, e.g. from the
+/// network. Pass it to `start_streaming()`.
+#[derive(Debug)]
+pub struct StreamedSource(*mut RawStreamedSource);
+
+unsafe impl Send for StreamedSource {}
+
+impl StreamedSource {
+  pub fn new(
+    stream: impl ExternalSourceStream + 'static,
+    encoding: StreamedSourceEncoding,
+  ) -> Self {
+    let stream = Box::new(SourceStream {
+      stream: Box::new(stream),
+      chunk: Vec::new(),
+    });
+    Self(unsafe {
+      v8__ScriptCompiler__StreamedSource__NEW(
+        Box::into_raw(stream) as *mut c_void,
+        &SOURCE_STREAM_VTABLE,
+        encoding,
+      )
+    })
+  }
+}
+
+impl Drop for StreamedSource {
+  fn drop(&mut self) {
+    unsafe { v8__ScriptCompiler__StreamedSource__DELETE(self.0) }
+  }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+struct RawScriptStreamingTask(Opaque);
+
+/// A task that pulls the source from a `StreamedSource` and parses and
+/// compiles it as the data comes in. Call `run()` on a background thread,
+/// then pass the task to `compile_streamed()` on the isolate's thread.
+///
+/// The task refers to the isolate that created it but doesn't borrow it, see
+/// the safety requirements of `start_streaming()`.
+#[derive(Debug)]
+pub struct ScriptStreamingTask {
+  raw: *mut RawScriptStreamingTask,
+  source: StreamedSource,
+  isolate: *mut Isolate,
+  has_run: bool,
+}
+
+unsafe impl Send for ScriptStreamingTask {}
+
+impl ScriptStreamingTask {
+  /// Reads the stream to the end and compiles the script. This is the
+  /// expensive part of the compilation; it doesn't access the isolate and is
+  /// meant to be run on a background thread.
+  pub fn run(&mut self) {
+    if !self.has_run {
+      self.has_run = true;
+      unsafe { v8__ScriptCompiler__ScriptStreamingTask__Run(self.raw) }
+    }
+  }
+}
+
+impl Drop for ScriptStreamingTask {
+  fn drop(&mut self) {
+    // Dropped before `self.source`, which the task points into.
+    unsafe { v8__ScriptCompiler__ScriptStreamingTask__DELETE(self.raw) }
+  }
+}
+
+/// Starts compiling a classic script from `source` while its data is
+/// streamed in. The returned task has to be run, typically on a background
+/// thread, before `compile_streamed()` finishes the compilation.
+///
+/// # Safety
+///
+/// The task keeps a pointer to `isolate` without borrowing it, so that the
+/// isolate can be used while the task runs on another thread. The caller must
+/// make sure that the isolate outlives the task: it must not be dropped
+/// while the task is running or before the task has been dropped or passed
+/// to `compile_streamed()`.
+pub unsafe fn start_streaming(
+  isolate: &mut Isolate,
+  source: StreamedSource,
+) -> ScriptStreamingTask {
+  let raw = v8__ScriptCompiler__StartStreaming(isolate, source.0);
+  ScriptStreamingTask {
+    raw,
+    source,
+    isolate,
+    has_run: false,
+  }
+}
+
+/// Finishes the compilation of a streamed script. If the task hasn't been run
+/// yet, it is run on the current thread first.
+///
+/// `full_source_string` is the complete source, i.e. the concatenation of
+/// all chunks that were streamed. V8 needs it for things like
+/// `Function.prototype.toString()` and doesn't keep a copy of the chunks.
+///
+/// Panics if `task` was started on a different isolate.
+pub fn compile_streamed<'s>(
+  scope: &mut HandleScope<'s>,
+  mut task: ScriptStreamingTask,
+  full_source_string: Local<String>,
+  origin: &ScriptOrigin,
+) -> Option<Local<'s, Script>> {
+  assert_eq!(
+    task.isolate,
+    scope.get_isolate_ptr(),
+    "streaming task was started on a different isolate"
+  );
+  task.run();
+  unsafe {
+    scope.cast_local(|sd| {
+      v8__ScriptCompiler__Compile__with_StreamedSource(
+        &*sd.get_current_context(),
+        task.source.0,
+        &*full_source_string,
+        origin,
+      )
+    })
+  }
+}
+
 /// Return a version tag for CachedData for the current V8 version & flags.
 ///
 /// This value is meant only for determining whether a previously generated
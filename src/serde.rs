@@ -0,0 +1,850 @@
+// Copyright 2019-2021 the Deno authors. All rights reserved. MIT license.
+//! Conversion between Rust values and JavaScript values using `serde`.
+//!
+//! Rust maps and structs become plain JavaScript objects, sequences and
+//! tuples become arrays, byte buffers become `Uint8Array`s and unit values
+//! become `null`. Enum variants follow the externally tagged representation:
+//! unit variants are serialized as strings, all other variants as an object
+//! with a single property named after the variant.
+//!
+//! This module is only available when the `serde` feature is enabled.
+use std::fmt;
+
+use serde::de;
+use serde::de::DeserializeOwned;
+use serde::de::IntoDeserializer;
+use serde::ser;
+use serde::Serialize;
+
+use crate::Array;
+use crate::ArrayBuffer;
+use crate::ArrayBufferView;
+use crate::BigInt;
+use crate::Boolean;
+use crate::HandleScope;
+use crate::Integer;
+use crate::Local;
+use crate::Number;
+use crate::Object;
+use crate::String;
+use crate::Uint8Array;
+use crate::Value;
+
+/// Largest integer that can be represented exactly by a JavaScript number.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Error returned when a value cannot be converted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error(std::string::String);
+
+impl Error {
+  fn exception() -> Self {
+    Self("a JavaScript exception was thrown".to_owned())
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+  fn custom<T: fmt::Display>(msg: T) -> Self {
+    Self(msg.to_string())
+  }
+}
+
+impl de::Error for Error {
+  fn custom<T: fmt::Display>(msg: T) -> Self {
+    Self(msg.to_string())
+  }
+}
+
+/// Converts `value` into a JavaScript value.
+pub fn to_v8<'s, T>(
+  scope: &mut HandleScope<'s>,
+  value: &T,
+) -> Result<Local<'s, Value>, Error>
+where
+  T: Serialize + ?Sized,
+{
+  value.serialize(Serializer::new(scope))
+}
+
+/// Converts the JavaScript value `value` into a Rust value. Property getters
+/// may run while the value is being read; if one of them throws, an error is
+/// returned and the exception is left pending.
+pub fn from_v8<'s, T>(
+  scope: &mut HandleScope<'s>,
+  value: Local<Value>,
+) -> Result<T, Error>
+where
+  T: DeserializeOwned,
+{
+  T::deserialize(Deserializer::new(scope, value))
+}
+
+fn new_string<'s>(
+  scope: &mut HandleScope<'s>,
+  value: &str,
+) -> Result<Local<'s, Value>, Error> {
+  String::new(scope, value)
+    .map(Into::into)
+    .ok_or_else(|| Error("string is too long".to_owned()))
+}
+
+fn set_property(
+  scope: &mut HandleScope,
+  object: Local<Object>,
+  key: Local<Value>,
+  value: Local<Value>,
+) -> Result<(), Error> {
+  object
+    .set(scope, key, value)
+    .map(|_| ())
+    .ok_or_else(Error::exception)
+}
+
+/// Wraps `value` in an object of the form `{ [variant]: value }` when it is
+/// the payload of an enum variant.
+fn wrap_variant<'s>(
+  scope: &mut HandleScope<'s>,
+  variant: Option<&'static str>,
+  value: Local<'s, Value>,
+) -> Result<Local<'s, Value>, Error> {
+  match variant {
+    None => Ok(value),
+    Some(variant) => {
+      let object = Object::new(scope);
+      let key = new_string(scope, variant)?;
+      set_property(scope, object, key, value)?;
+      Ok(object.into())
+    }
+  }
+}
+
+/// A `serde::Serializer` that produces JavaScript values.
+pub struct Serializer<'a, 's> {
+  scope: &'a mut HandleScope<'s>,
+}
+
+impl<'a, 's> Serializer<'a, 's> {
+  pub fn new(scope: &'a mut HandleScope<'s>) -> Self {
+    Self { scope }
+  }
+}
+
+impl<'a, 's> ser::Serializer for Serializer<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  type SerializeSeq = SerializeArray<'a, 's>;
+  type SerializeTuple = SerializeArray<'a, 's>;
+  type SerializeTupleStruct = SerializeArray<'a, 's>;
+  type SerializeTupleVariant = SerializeArray<'a, 's>;
+  type SerializeMap = SerializeObject<'a, 's>;
+  type SerializeStruct = SerializeObject<'a, 's>;
+  type SerializeStructVariant = SerializeObject<'a, 's>;
+
+  fn serialize_bool(self, v: bool) -> Result<Self::Ok, Error> {
+    Ok(Boolean::new(self.scope, v).into())
+  }
+
+  fn serialize_i8(self, v: i8) -> Result<Self::Ok, Error> {
+    self.serialize_i32(v.into())
+  }
+
+  fn serialize_i16(self, v: i16) -> Result<Self::Ok, Error> {
+    self.serialize_i32(v.into())
+  }
+
+  fn serialize_i32(self, v: i32) -> Result<Self::Ok, Error> {
+    Ok(Integer::new(self.scope, v).into())
+  }
+
+  fn serialize_i64(self, v: i64) -> Result<Self::Ok, Error> {
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&v) {
+      Ok(Number::new(self.scope, v as f64).into())
+    } else {
+      Ok(BigInt::new_from_i64(self.scope, v).into())
+    }
+  }
+
+  fn serialize_u8(self, v: u8) -> Result<Self::Ok, Error> {
+    self.serialize_u32(v.into())
+  }
+
+  fn serialize_u16(self, v: u16) -> Result<Self::Ok, Error> {
+    self.serialize_u32(v.into())
+  }
+
+  fn serialize_u32(self, v: u32) -> Result<Self::Ok, Error> {
+    Ok(Integer::new_from_unsigned(self.scope, v).into())
+  }
+
+  fn serialize_u64(self, v: u64) -> Result<Self::Ok, Error> {
+    if v <= MAX_SAFE_INTEGER as u64 {
+      Ok(Number::new(self.scope, v as f64).into())
+    } else {
+      Ok(BigInt::new_from_u64(self.scope, v).into())
+    }
+  }
+
+  fn serialize_f32(self, v: f32) -> Result<Self::Ok, Error> {
+    self.serialize_f64(v.into())
+  }
+
+  fn serialize_f64(self, v: f64) -> Result<Self::Ok, Error> {
+    Ok(Number::new(self.scope, v).into())
+  }
+
+  fn serialize_char(self, v: char) -> Result<Self::Ok, Error> {
+    self.serialize_str(v.encode_utf8(&mut [0; 4]))
+  }
+
+  fn serialize_str(self, v: &str) -> Result<Self::Ok, Error> {
+    new_string(self.scope, v)
+  }
+
+  fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Error> {
+    let backing_store =
+      ArrayBuffer::new_backing_store_from_vec(v.to_vec()).make_shared();
+    let buffer = ArrayBuffer::with_backing_store(self.scope, &backing_store);
+    Uint8Array::new(self.scope, buffer, 0, v.len())
+      .map(Into::into)
+      .ok_or_else(Error::exception)
+  }
+
+  fn serialize_none(self) -> Result<Self::Ok, Error> {
+    self.serialize_unit()
+  }
+
+  fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<Self::Ok, Error> {
+    Ok(crate::null(self.scope).into())
+  }
+
+  fn serialize_unit_struct(
+    self,
+    _name: &'static str,
+  ) -> Result<Self::Ok, Error> {
+    self.serialize_unit()
+  }
+
+  fn serialize_unit_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+  ) -> Result<Self::Ok, Error> {
+    self.serialize_str(variant)
+  }
+
+  fn serialize_newtype_struct<T>(
+    self,
+    _name: &'static str,
+    value: &T,
+  ) -> Result<Self::Ok, Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T>(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    value: &T,
+  ) -> Result<Self::Ok, Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    let value = value.serialize(Serializer::new(self.scope))?;
+    wrap_variant(self.scope, Some(variant), value)
+  }
+
+  fn serialize_seq(
+    self,
+    len: Option<usize>,
+  ) -> Result<Self::SerializeSeq, Error> {
+    Ok(SerializeArray::new(self.scope, len.unwrap_or(0), None))
+  }
+
+  fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+    Ok(SerializeArray::new(self.scope, len, None))
+  }
+
+  fn serialize_tuple_struct(
+    self,
+    _name: &'static str,
+    len: usize,
+  ) -> Result<Self::SerializeTupleStruct, Error> {
+    Ok(SerializeArray::new(self.scope, len, None))
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    len: usize,
+  ) -> Result<Self::SerializeTupleVariant, Error> {
+    Ok(SerializeArray::new(self.scope, len, Some(variant)))
+  }
+
+  fn serialize_map(
+    self,
+    _len: Option<usize>,
+  ) -> Result<Self::SerializeMap, Error> {
+    Ok(SerializeObject::new(self.scope, None))
+  }
+
+  fn serialize_struct(
+    self,
+    _name: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeStruct, Error> {
+    Ok(SerializeObject::new(self.scope, None))
+  }
+
+  fn serialize_struct_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeStructVariant, Error> {
+    Ok(SerializeObject::new(self.scope, Some(variant)))
+  }
+}
+
+/// Collects serialized elements into a JavaScript array.
+pub struct SerializeArray<'a, 's> {
+  scope: &'a mut HandleScope<'s>,
+  elements: Vec<Local<'s, Value>>,
+  variant: Option<&'static str>,
+}
+
+impl<'a, 's> SerializeArray<'a, 's> {
+  fn new(
+    scope: &'a mut HandleScope<'s>,
+    len: usize,
+    variant: Option<&'static str>,
+  ) -> Self {
+    Self {
+      scope,
+      elements: Vec::with_capacity(len),
+      variant,
+    }
+  }
+
+  fn push<T>(&mut self, value: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    let value = value.serialize(Serializer::new(self.scope))?;
+    self.elements.push(value);
+    Ok(())
+  }
+
+  fn finish(self) -> Result<Local<'s, Value>, Error> {
+    let array = Array::new_with_elements(self.scope, &self.elements);
+    wrap_variant(self.scope, self.variant, array.into())
+  }
+}
+
+impl<'a, 's> ser::SerializeSeq for SerializeArray<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Error> {
+    self.finish()
+  }
+}
+
+impl<'a, 's> ser::SerializeTuple for SerializeArray<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Error> {
+    self.finish()
+  }
+}
+
+impl<'a, 's> ser::SerializeTupleStruct for SerializeArray<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Error> {
+    self.finish()
+  }
+}
+
+impl<'a, 's> ser::SerializeTupleVariant for SerializeArray<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.push(value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Error> {
+    self.finish()
+  }
+}
+
+/// Collects serialized entries and fields into a JavaScript object.
+pub struct SerializeObject<'a, 's> {
+  scope: &'a mut HandleScope<'s>,
+  object: Local<'s, Object>,
+  key: Option<Local<'s, Value>>,
+  variant: Option<&'static str>,
+}
+
+impl<'a, 's> SerializeObject<'a, 's> {
+  fn new(
+    scope: &'a mut HandleScope<'s>,
+    variant: Option<&'static str>,
+  ) -> Self {
+    let object = Object::new(scope);
+    Self {
+      scope,
+      object,
+      key: None,
+      variant,
+    }
+  }
+
+  fn set<T>(&mut self, key: Local<Value>, value: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    let value = value.serialize(Serializer::new(self.scope))?;
+    set_property(self.scope, self.object, key, value)
+  }
+
+  fn finish(self) -> Result<Local<'s, Value>, Error> {
+    wrap_variant(self.scope, self.variant, self.object.into())
+  }
+}
+
+impl<'a, 's> ser::SerializeMap for SerializeObject<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.key = Some(key.serialize(Serializer::new(self.scope))?);
+    Ok(())
+  }
+
+  fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    let key = self.key.take().ok_or_else(|| {
+      Error("serialize_value() called before serialize_key()".to_owned())
+    })?;
+    self.set(key, value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Error> {
+    self.finish()
+  }
+}
+
+impl<'a, 's> ser::SerializeStruct for SerializeObject<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  fn serialize_field<T>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    let key = new_string(self.scope, key)?;
+    self.set(key, value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Error> {
+    self.finish()
+  }
+}
+
+impl<'a, 's> ser::SerializeStructVariant for SerializeObject<'a, 's> {
+  type Ok = Local<'s, Value>;
+  type Error = Error;
+
+  fn serialize_field<T>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    let key = new_string(self.scope, key)?;
+    self.set(key, value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Error> {
+    self.finish()
+  }
+}
+
+/// A `serde::Deserializer` that reads JavaScript values.
+///
+/// Object keys are always presented to the visitor as strings, so maps
+/// should be keyed by a string type.
+pub struct Deserializer<'a, 's> {
+  scope: &'a mut HandleScope<'s>,
+  input: Local<'a, Value>,
+}
+
+impl<'a, 's> Deserializer<'a, 's> {
+  pub fn new(scope: &'a mut HandleScope<'s>, input: Local<'a, Value>) -> Self {
+    Self { scope, input }
+  }
+}
+
+impl<'de, 'a, 's> de::Deserializer<'de> for Deserializer<'a, 's> {
+  type Error = Error;
+
+  fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    let value = self.input;
+    if value.is_null_or_undefined() {
+      visitor.visit_unit()
+    } else if value.is_boolean() {
+      visitor.visit_bool(value.is_true())
+    } else if value.is_number() {
+      let n = value
+        .number_value(self.scope)
+        .ok_or_else(Error::exception)?;
+      if n.fract() != 0.0 || n.abs() > MAX_SAFE_INTEGER as f64 {
+        visitor.visit_f64(n)
+      } else if n < 0.0 {
+        visitor.visit_i64(n as i64)
+      } else {
+        visitor.visit_u64(n as u64)
+      }
+    } else if value.is_big_int() {
+      let n: Local<BigInt> = value.try_into().unwrap();
+      match (n.i64_value(), n.u64_value()) {
+        ((n, true), _) => visitor.visit_i64(n),
+        (_, (n, true)) => visitor.visit_u64(n),
+        _ => Err(Error("BigInt is out of range".to_owned())),
+      }
+    } else if value.is_string() {
+      visitor.visit_string(value.to_rust_string_lossy(self.scope))
+    } else if value.is_array_buffer_view() {
+      let view: Local<ArrayBufferView> = value.try_into().unwrap();
+      let mut buf = vec![0; view.byte_length()];
+      view.copy_contents(&mut buf);
+      visitor.visit_byte_buf(buf)
+    } else if value.is_array() {
+      let array: Local<Array> = value.try_into().unwrap();
+      visitor.visit_seq(ArrayAccess {
+        scope: self.scope,
+        array,
+        index: 0,
+        length: array.length(),
+      })
+    } else if value.is_object() {
+      self.deserialize_map(visitor)
+    } else {
+      Err(Error("unsupported value type".to_owned()))
+    }
+  }
+
+  fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    if self.input.is_null_or_undefined() {
+      visitor.visit_none()
+    } else {
+      visitor.visit_some(self)
+    }
+  }
+
+  fn deserialize_newtype_struct<V>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    let object: Local<Object> = self
+      .input
+      .try_into()
+      .map_err(|_| Error("expected an object".to_owned()))?;
+    let keys = object
+      .get_own_property_names(self.scope)
+      .ok_or_else(Error::exception)?;
+    visitor.visit_map(ObjectAccess {
+      scope: self.scope,
+      object,
+      keys,
+      index: 0,
+      length: keys.length(),
+      key: None,
+    })
+  }
+
+  fn deserialize_struct<V>(
+    self,
+    _name: &'static str,
+    _fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    self.deserialize_map(visitor)
+  }
+
+  fn deserialize_enum<V>(
+    self,
+    _name: &'static str,
+    _variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    if self.input.is_string() {
+      let variant = self.input.to_rust_string_lossy(self.scope);
+      return visitor.visit_enum(variant.into_deserializer());
+    }
+    let object: Local<Object> = self
+      .input
+      .try_into()
+      .map_err(|_| Error("expected a string or an object".to_owned()))?;
+    let keys = object
+      .get_own_property_names(self.scope)
+      .ok_or_else(Error::exception)?;
+    if keys.length() != 1 {
+      return Err(Error(
+        "expected an object with a single property".to_owned(),
+      ));
+    }
+    let variant = keys.get_index(self.scope, 0).ok_or_else(Error::exception)?;
+    let payload = object
+      .get(self.scope, variant)
+      .ok_or_else(Error::exception)?;
+    visitor.visit_enum(EnumAccess {
+      scope: self.scope,
+      variant,
+      payload,
+    })
+  }
+
+  serde::forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+    bytes byte_buf unit unit_struct seq tuple tuple_struct identifier
+    ignored_any
+  }
+}
+
+/// Reads object keys as strings; see `Deserializer`.
+fn key_to_string<'s>(
+  scope: &mut HandleScope<'s>,
+  key: Local<Value>,
+) -> Result<Local<'s, Value>, Error> {
+  key
+    .to_string(scope)
+    .map(Into::into)
+    .ok_or_else(Error::exception)
+}
+
+struct ArrayAccess<'a, 's> {
+  scope: &'a mut HandleScope<'s>,
+  array: Local<'a, Array>,
+  index: u32,
+  length: u32,
+}
+
+impl<'de, 'a, 's> de::SeqAccess<'de> for ArrayAccess<'a, 's> {
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+  where
+    T: de::DeserializeSeed<'de>,
+  {
+    if self.index >= self.length {
+      return Ok(None);
+    }
+    let value = self
+      .array
+      .get_index(self.scope, self.index)
+      .ok_or_else(Error::exception)?;
+    self.index += 1;
+    seed
+      .deserialize(Deserializer::new(self.scope, value))
+      .map(Some)
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some((self.length - self.index) as usize)
+  }
+}
+
+struct ObjectAccess<'a, 's> {
+  scope: &'a mut HandleScope<'s>,
+  object: Local<'a, Object>,
+  keys: Local<'a, Array>,
+  index: u32,
+  length: u32,
+  key: Option<Local<'a, Value>>,
+}
+
+impl<'de, 'a, 's> de::MapAccess<'de> for ObjectAccess<'a, 's> {
+  type Error = Error;
+
+  fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+  where
+    K: de::DeserializeSeed<'de>,
+  {
+    if self.index >= self.length {
+      return Ok(None);
+    }
+    let key = self
+      .keys
+      .get_index(self.scope, self.index)
+      .ok_or_else(Error::exception)?;
+    self.index += 1;
+    self.key = Some(key);
+    let key = key_to_string(self.scope, key)?;
+    seed
+      .deserialize(Deserializer::new(self.scope, key))
+      .map(Some)
+  }
+
+  fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+  where
+    V: de::DeserializeSeed<'de>,
+  {
+    let key = self.key.take().ok_or_else(|| {
+      Error("next_value_seed() called before next_key_seed()".to_owned())
+    })?;
+    let value = self
+      .object
+      .get(self.scope, key)
+      .ok_or_else(Error::exception)?;
+    seed.deserialize(Deserializer::new(self.scope, value))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some((self.length - self.index) as usize)
+  }
+}
+
+struct EnumAccess<'a, 's> {
+  scope: &'a mut HandleScope<'s>,
+  variant: Local<'a, Value>,
+  payload: Local<'a, Value>,
+}
+
+impl<'de, 'a, 's> de::EnumAccess<'de> for EnumAccess<'a, 's> {
+  type Error = Error;
+  type Variant = Self;
+
+  fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+  where
+    V: de::DeserializeSeed<'de>,
+  {
+    let variant = key_to_string(self.scope, self.variant)?;
+    let variant = seed.deserialize(Deserializer::new(self.scope, variant))?;
+    Ok((variant, self))
+  }
+}
+
+impl<'de, 'a, 's> de::VariantAccess<'de> for EnumAccess<'a, 's> {
+  type Error = Error;
+
+  fn unit_variant(self) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+  where
+    T: de::DeserializeSeed<'de>,
+  {
+    seed.deserialize(Deserializer::new(self.scope, self.payload))
+  }
+
+  fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    de::Deserializer::deserialize_seq(
+      Deserializer::new(self.scope, self.payload),
+      visitor,
+    )
+  }
+
+  fn struct_variant<V>(
+    self,
+    _fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error>
+  where
+    V: de::Visitor<'de>,
+  {
+    de::Deserializer::deserialize_map(
+      Deserializer::new(self.scope, self.payload),
+      visitor,
+    )
+  }
+}
@@ -1,8 +1,11 @@
 use crate::external_references::ExternalReferences;
+use crate::isolate_create_params::raw;
 use crate::scope::data::ScopeData;
 use crate::support::char;
 use crate::support::int;
 use crate::support::intptr_t;
+use crate::support::Allocated;
+use crate::support::Allocation;
 use crate::Context;
 use crate::Data;
 use crate::Isolate;
@@ -16,27 +19,28 @@ use std::ops::Deref;
 
 extern "C" {
   fn v8__SnapshotCreator__CONSTRUCT(
-    buf: *mut MaybeUninit<SnapshotCreator>,
+    buf: *mut MaybeUninit<RawSnapshotCreator>,
     external_references: *const intptr_t,
+    existing_blob: *const raw::StartupData,
   );
-  fn v8__SnapshotCreator__DESTRUCT(this: *mut SnapshotCreator);
+  fn v8__SnapshotCreator__DESTRUCT(this: *mut RawSnapshotCreator);
   fn v8__SnapshotCreator__GetIsolate(
-    this: *const SnapshotCreator,
+    this: *const RawSnapshotCreator,
   ) -> *mut Isolate;
   fn v8__SnapshotCreator__CreateBlob(
-    this: *mut SnapshotCreator,
+    this: *mut RawSnapshotCreator,
     function_code_handling: FunctionCodeHandling,
   ) -> StartupData;
   fn v8__SnapshotCreator__SetDefaultContext(
-    this: *mut SnapshotCreator,
+    this: *mut RawSnapshotCreator,
     context: *const Context,
   );
   fn v8__SnapshotCreator__AddData_to_isolate(
-    this: *mut SnapshotCreator,
+    this: *mut RawSnapshotCreator,
     data: *const Data,
   ) -> usize;
   fn v8__SnapshotCreator__AddData_to_context(
-    this: *mut SnapshotCreator,
+    this: *mut RawSnapshotCreator,
     context: *const Context,
     data: *const Data,
   ) -> usize;
@@ -86,34 +90,82 @@ pub enum FunctionCodeHandling {
   Keep,
 }
 
-/// Helper class to create a snapshot data blob.
 #[repr(C)]
 #[derive(Debug)]
-pub struct SnapshotCreator([usize; 1]);
+struct RawSnapshotCreator([usize; 1]);
+
+/// Helper class to create a snapshot data blob.
+#[derive(Debug)]
+pub struct SnapshotCreator {
+  raw: RawSnapshotCreator,
+  // The blob that the isolate was deserialized from, if any. V8 keeps
+  // pointers to both the header and the data for the lifetime of the
+  // isolate.
+  _existing_blob_data: Option<Allocation<[u8]>>,
+  _existing_blob_header: Option<Box<raw::StartupData>>,
+}
 
 impl SnapshotCreator {
   /// Create and enter an isolate, and set it up for serialization.
   /// The isolate is created from scratch.
   pub fn new(external_references: Option<&'static ExternalReferences>) -> Self {
-    let mut snapshot_creator: MaybeUninit<Self> = MaybeUninit::uninit();
+    Self::new_impl(external_references, None)
+  }
+
+  /// Create and enter an isolate, and set it up for serialization.
+  /// The isolate is deserialized from `existing_snapshot_blob`, so the
+  /// resulting snapshot contains everything in the existing one plus
+  /// whatever is set up on top of it. `external_references` must list the
+  /// same references that the existing snapshot was created with, followed
+  /// by any new ones.
+  ///
+  /// Data attached with `add_isolate_data()` or `add_context_data()` when
+  /// the existing snapshot was created is not carried over.
+  pub fn from_existing_snapshot(
+    existing_snapshot_blob: impl Allocated<[u8]>,
+    external_references: Option<&'static ExternalReferences>,
+  ) -> Self {
+    Self::new_impl(
+      external_references,
+      Some(Allocation::of(existing_snapshot_blob)),
+    )
+  }
+
+  fn new_impl(
+    external_references: Option<&'static ExternalReferences>,
+    existing_blob_data: Option<Allocation<[u8]>>,
+  ) -> Self {
+    let mut buf = MaybeUninit::uninit();
     let external_references_ptr = if let Some(er) = external_references {
       er.as_ptr()
     } else {
       std::ptr::null()
     };
+    let existing_blob_header = existing_blob_data
+      .as_ref()
+      .map(raw::StartupData::boxed_header);
+    let existing_blob_ptr = match &existing_blob_header {
+      Some(header) => &**header as *const raw::StartupData,
+      None => std::ptr::null(),
+    };
     unsafe {
       v8__SnapshotCreator__CONSTRUCT(
-        &mut snapshot_creator,
+        &mut buf,
         external_references_ptr,
+        existing_blob_ptr,
       );
-      snapshot_creator.assume_init()
+      Self {
+        raw: buf.assume_init(),
+        _existing_blob_data: existing_blob_data,
+        _existing_blob_header: existing_blob_header,
+      }
     }
   }
 }
 
 impl Drop for SnapshotCreator {
   fn drop(&mut self) {
-    unsafe { v8__SnapshotCreator__DESTRUCT(self) };
+    unsafe { v8__SnapshotCreator__DESTRUCT(&mut self.raw) };
   }
 }
 
@@ -122,7 +174,7 @@ impl SnapshotCreator {
   /// The snapshot will not contain the global proxy, and we expect one or a
   /// global object template to create one, to be provided upon deserialization.
   pub fn set_default_context(&mut self, context: Local<Context>) {
-    unsafe { v8__SnapshotCreator__SetDefaultContext(self, &*context) };
+    unsafe { v8__SnapshotCreator__SetDefaultContext(&mut self.raw, &*context) };
   }
 
   /// Attach arbitrary `v8::Data` to the isolate snapshot, which can be
@@ -133,7 +185,9 @@ impl SnapshotCreator {
   where
     for<'l> Local<'l, T>: Into<Local<'l, Data>>,
   {
-    unsafe { v8__SnapshotCreator__AddData_to_isolate(self, &*data.into()) }
+    unsafe {
+      v8__SnapshotCreator__AddData_to_isolate(&mut self.raw, &*data.into())
+    }
   }
 
   /// Attach arbitrary `v8::Data` to the context snapshot, which can be
@@ -149,7 +203,11 @@ impl SnapshotCreator {
     for<'l> Local<'l, T>: Into<Local<'l, Data>>,
   {
     unsafe {
-      v8__SnapshotCreator__AddData_to_context(self, &*context, &*data.into())
+      v8__SnapshotCreator__AddData_to_context(
+        &mut self.raw,
+        &*context,
+        &*data.into(),
+      )
     }
   }
 
@@ -160,11 +218,12 @@ impl SnapshotCreator {
     function_code_handling: FunctionCodeHandling,
   ) -> Option<StartupData> {
     {
-      let isolate = unsafe { &mut *v8__SnapshotCreator__GetIsolate(self) };
+      let isolate = unsafe { &mut *v8__SnapshotCreator__GetIsolate(&self.raw) };
       ScopeData::get_root_mut(isolate);
     }
-    let blob =
-      unsafe { v8__SnapshotCreator__CreateBlob(self, function_code_handling) };
+    let blob = unsafe {
+      v8__SnapshotCreator__CreateBlob(&mut self.raw, function_code_handling)
+    };
     if blob.data.is_null() {
       debug_assert!(blob.raw_size == 0);
       None
@@ -180,10 +239,12 @@ impl SnapshotCreator {
   // get an owned handle to it. This is a questionable design which ought to be
   // revisited after the libdeno integration is complete.
   pub unsafe fn get_owned_isolate(&mut self) -> OwnedIsolate {
-    let isolate_ptr = v8__SnapshotCreator__GetIsolate(self);
+    let isolate_ptr = v8__SnapshotCreator__GetIsolate(&self.raw);
     let mut owned_isolate = OwnedIsolate::new(isolate_ptr);
     ScopeData::new_root(&mut owned_isolate);
     owned_isolate.create_annex(Box::new(()));
+    // The `SnapshotCreator` constructor has entered the isolate.
+    owned_isolate.record_entered();
     owned_isolate
   }
 }
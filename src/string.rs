@@ -36,6 +36,12 @@ extern "C" {
     length: int,
   ) -> *const String;
 
+  fn v8__String__Concat(
+    isolate: *mut Isolate,
+    left: *const String,
+    right: *const String,
+  ) -> *const String;
+
   fn v8__String__Length(this: *const String) -> int;
 
   fn v8__String__Utf8Length(this: *const String, isolate: *mut Isolate) -> int;
@@ -83,15 +89,19 @@ extern "C" {
   fn v8__String__IsExternal(this: *const String) -> bool;
   fn v8__String__IsExternalOneByte(this: *const String) -> bool;
   fn v8__String__IsExternalTwoByte(this: *const String) -> bool;
-  #[allow(dead_code)]
   fn v8__String__IsOneByte(this: *const String) -> bool;
   fn v8__String__ContainsOnlyOneByte(this: *const String) -> bool;
 }
 
+/// Selects how the `String::new_from_*()` constructors allocate a string.
 #[repr(C)]
 #[derive(Debug)]
 pub enum NewStringType {
+  /// Create a new string, always allocating new storage memory.
   Normal,
+  /// Acts as a hint that the string should be created in the old generation
+  /// heap space and be deduplicated if an identical string already exists.
+  /// Use this for strings that are used many times as property keys.
   Internalized,
 }
 
@@ -194,6 +204,23 @@ impl String {
     }
   }
 
+  /// Creates a new string by concatenating the left and the right strings
+  /// passed in as parameters. The result may be a cons string that
+  /// references both inputs rather than a copy of their characters; short
+  /// results are copied into a new flat string instead. Returns `None` when
+  /// the length of the result would exceed `max_length()`.
+  pub fn concat<'s>(
+    scope: &mut HandleScope<'s, ()>,
+    left: Local<String>,
+    right: Local<String>,
+  ) -> Option<Local<'s, String>> {
+    unsafe {
+      scope.cast_local(|sd| {
+        v8__String__Concat(sd.get_isolate_ptr(), &*left, &*right)
+      })
+    }
+  }
+
   /// Returns the number of characters (UTF-16 code units) in this string.
   pub fn length(&self) -> usize {
     unsafe { v8__String__Length(self) as usize }
@@ -272,7 +299,10 @@ impl String {
     bytes as usize
   }
 
-  // Convenience function not present in the original V8 API.
+  /// Convenience function not present in the original V8 API.
+  ///
+  /// Creates a string with `NewStringType::Normal`. Use `new_internalized()`
+  /// or `new_from_utf8()` to pick the string type explicitly.
   pub fn new<'s>(
     scope: &mut HandleScope<'s, ()>,
     value: &str,
@@ -280,6 +310,17 @@ impl String {
     Self::new_from_utf8(scope, value.as_ref(), NewStringType::Normal)
   }
 
+  /// Creates an internalized string from `value`. Internalized strings are
+  /// deduplicated by V8: creating one with the same contents as an existing
+  /// internalized string returns the existing instance. They are faster to
+  /// use as property keys.
+  pub fn new_internalized<'s>(
+    scope: &mut HandleScope<'s, ()>,
+    value: &str,
+  ) -> Option<Local<'s, String>> {
+    Self::new_from_utf8(scope, value.as_ref(), NewStringType::Internalized)
+  }
+
   // Creates a v8::String from a `&'static [u8]`,
   // must be Latin-1 or ASCII, not UTF-8 !
   pub fn new_external_onebyte_static<'s>(
@@ -338,15 +379,16 @@ impl String {
     unsafe { v8__String__IsExternalTwoByte(self) }
   }
 
-  /// True if string is known to contain only one-byte data.
-  /// Doesn't read the string so can return false positives.
+  /// True if string is known to contain only one-byte data, i.e. if it is
+  /// stored in a one-byte (Latin-1) representation. Doesn't read the string
+  /// so can return false negatives.
   ///
-  /// For a method that will not return false positives at the cost of
+  /// For a method that will not return false negatives at the cost of
   /// potentially reading the entire string, use [`contains_only_onebyte()`].
   ///
   /// [`contains_only_onebyte()`]: String::contains_only_onebyte
   pub fn is_onebyte(&self) -> bool {
-    unsafe { v8__String__IsExternalOneByte(self) }
+    unsafe { v8__String__IsOneByte(self) }
   }
 
   /// True if the string contains only one-byte data.
@@ -296,6 +296,12 @@ impl<T: Shared> SharedRef<T> {
   pub fn assert_use_count_eq(&self, expected: usize) {
     assert_shared_ptr_use_count_eq("SharedRef", &self.0, expected);
   }
+
+  /// Returns true if the two `SharedRef`s point to the same C++ object, in a
+  /// manner similar to `Arc::ptr_eq()`.
+  pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+    <T as Shared>::get(&this.0) == <T as Shared>::get(&other.0)
+  }
 }
 
 impl<T: Shared> Clone for SharedRef<T> {
@@ -808,6 +814,15 @@ mod tests {
     assert_eq!(shared_ptr_b2.unwrap().inner, 22222);
   }
 
+  #[test]
+  fn shared_ref_ptr_eq() {
+    let shared_ref_a1 = SharedRef(MockSharedObj::SHARED_PTR_BASE_A);
+    let shared_ref_a2 = SharedRef(MockSharedObj::SHARED_PTR_BASE_A);
+    let shared_ref_b = SharedRef(MockSharedObj::SHARED_PTR_BASE_B);
+    assert!(SharedRef::ptr_eq(&shared_ref_a1, &shared_ref_a2));
+    assert!(!SharedRef::ptr_eq(&shared_ref_a1, &shared_ref_b));
+  }
+
   #[test]
   #[should_panic(expected = "assertion failed: \
       `SharedPtr<v8::support::tests::MockSharedObj>` reference count \
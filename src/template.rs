@@ -33,6 +33,14 @@ extern "C" {
     value: *const Data,
     attr: PropertyAttribute,
   );
+  fn v8__Template__SetLazyDataProperty(
+    this: *const Template,
+    key: *const Name,
+    getter: AccessorNameGetterCallback,
+    data_or_null: *const Value,
+    attr: PropertyAttribute,
+  );
+
   fn v8__Signature__New(
     isolate: *mut Isolate,
     templ: *const FunctionTemplate,
@@ -64,6 +72,10 @@ extern "C" {
   );
   fn v8__FunctionTemplate__ReadOnlyPrototype(this: *const FunctionTemplate);
   fn v8__FunctionTemplate__RemovePrototype(this: *const FunctionTemplate);
+  fn v8__FunctionTemplate__SetAcceptAnyReceiver(
+    this: *const FunctionTemplate,
+    value: bool,
+  );
 
   fn v8__ObjectTemplate__New(
     isolate: *mut Isolate,
@@ -115,6 +127,29 @@ impl Template {
   ) {
     unsafe { v8__Template__Set(self, &*key, &*value, attr) }
   }
+
+  /// Adds a property to each instance created by this template whose value
+  /// is computed lazily. `getter` is called the first time the property is
+  /// read, and its result replaces the property as a plain data property, so
+  /// `getter` runs at most once per instance. `data` is passed to `getter`
+  /// through `PropertyCallbackArguments::data()`.
+  pub fn set_lazy_data_property(
+    &self,
+    key: Local<Name>,
+    getter: impl for<'s> MapFnTo<AccessorNameGetterCallback<'s>>,
+    data: Option<Local<Value>>,
+    attr: PropertyAttribute,
+  ) {
+    unsafe {
+      v8__Template__SetLazyDataProperty(
+        self,
+        &*key,
+        getter.map_fn_to(),
+        data.map_or_else(null, |p| &*p),
+        attr,
+      )
+    }
+  }
 }
 
 impl<'s> FunctionBuilder<'s, FunctionTemplate> {
@@ -233,15 +268,27 @@ impl FunctionTemplate {
   }
 
   /// Sets the ReadOnly flag in the attributes of the 'prototype' property
-  /// of functions created from this FunctionTemplate to true.
+  /// of functions created from this FunctionTemplate to true. The prototype
+  /// object itself stays extensible; only the `prototype` property of the
+  /// function cannot be reassigned.
   pub fn read_only_prototype(&self) {
     unsafe { v8__FunctionTemplate__ReadOnlyPrototype(self) };
   }
 
   /// Removes the prototype property from functions created from this FunctionTemplate.
+  /// Such functions behave like arrow functions and methods: they cannot be
+  /// called as constructors.
   pub fn remove_prototype(&self) {
     unsafe { v8__FunctionTemplate__RemovePrototype(self) };
   }
+
+  /// Sets the receiver type of functions created from this FunctionTemplate.
+  /// When set to true, no access check is performed on the receiver of a
+  /// function call. Currently defaults to true, but this is subject to
+  /// change.
+  pub fn set_accept_any_receiver(&self, value: bool) {
+    unsafe { v8__FunctionTemplate__SetAcceptAnyReceiver(self, value) };
+  }
 }
 
 impl ObjectTemplate {
@@ -1,7 +1,9 @@
+use crate::support::int;
 use crate::CachedData;
 use crate::Local;
 use crate::Script;
 use crate::UnboundScript;
+use crate::Value;
 use crate::{HandleScope, UniqueRef};
 
 extern "C" {
@@ -11,6 +13,10 @@ extern "C" {
   fn v8__UnboundScript__CreateCodeCache(
     script: *const UnboundScript,
   ) -> *mut CachedData<'static>;
+  fn v8__UnboundScript__GetId(script: *const UnboundScript) -> int;
+  fn v8__UnboundScript__GetScriptName(
+    script: *const UnboundScript,
+  ) -> *const Value;
 }
 
 impl UnboundScript {
@@ -25,6 +31,21 @@ impl UnboundScript {
     .unwrap()
   }
 
+  /// Returns the id of the script, the same as `ScriptOrigin`'s script id.
+  pub fn get_id(&self) -> int {
+    unsafe { v8__UnboundScript__GetId(self) }
+  }
+
+  /// Returns the name that was passed as resource name to the `ScriptOrigin`
+  /// the script was compiled with, or `undefined`.
+  pub fn get_script_name<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Local<'s, Value> {
+    unsafe { scope.cast_local(|_| v8__UnboundScript__GetScriptName(self)) }
+      .unwrap()
+  }
+
   /// Creates and returns code cache for the specified unbound_script.
   /// This will return nullptr if the script cannot be serialized. The
   /// CachedData returned by this function should be owned by the caller.
@@ -1,10 +1,14 @@
+use std::convert::TryFrom;
 use std::num::NonZeroI32;
 
 use crate::support::int;
 use crate::support::Maybe;
+use crate::Array;
 use crate::BigInt;
 use crate::Boolean;
 use crate::Context;
+use crate::DataError;
+use crate::Function;
 use crate::HandleScope;
 use crate::Int32;
 use crate::Integer;
@@ -45,7 +49,6 @@ extern "C" {
   fn v8__Value__IsRegExp(this: *const Value) -> bool;
   fn v8__Value__IsAsyncFunction(this: *const Value) -> bool;
   fn v8__Value__IsGeneratorFunction(this: *const Value) -> bool;
-  #[allow(dead_code)]
   fn v8__Value__IsGeneratorObject(this: *const Value) -> bool;
   fn v8__Value__IsPromise(this: *const Value) -> bool;
   fn v8__Value__IsMap(this: *const Value) -> bool;
@@ -299,6 +302,11 @@ impl Value {
     unsafe { v8__Value__IsGeneratorFunction(self) }
   }
 
+  /// Returns true if this value is a Generator object (iterator).
+  pub fn is_generator_object(&self) -> bool {
+    unsafe { v8__Value__IsGeneratorObject(self) }
+  }
+
   /// Returns true if this value is a Promise.
   pub fn is_promise(&self) -> bool {
     unsafe { v8__Value__IsPromise(self) }
@@ -504,6 +512,9 @@ impl Value {
       .map_or_else(std::string::String::new, |s| s.to_rust_string_lossy(scope))
   }
 
+  /// Returns V8's internal debug representation of this value. Unlike
+  /// `to_string()`, this never invokes user-defined `toString()` or
+  /// `Symbol.toPrimitive` methods, so it is safe to use for logging.
   pub fn to_detail_string<'s>(
     &self,
     scope: &mut HandleScope<'s>,
@@ -515,6 +526,16 @@ impl Value {
     }
   }
 
+  /// Convenience function not present in the original V8 API.
+  pub fn to_rust_detail_string_lossy<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> std::string::String {
+    self
+      .to_detail_string(scope)
+      .map_or_else(std::string::String::new, |s| s.to_rust_string_lossy(scope))
+  }
+
   pub fn to_object<'s>(
     &self,
     scope: &mut HandleScope<'s>,
@@ -615,6 +636,47 @@ impl Value {
     out.into()
   }
 
+  /// Convenience function not present in the original V8 API.
+  ///
+  /// Reads this value as a signed 64-bit integer. A Number is converted
+  /// directly; any other value is first converted to a BigInt with
+  /// `to_big_int()`, which throws for values such as `undefined` or symbols,
+  /// in which case `None` is returned.
+  ///
+  /// The `bool` indicates whether the conversion was lossless. It is `false`
+  /// for a BigInt that does not fit in an `i64`, and for a Number that is not
+  /// an integer in the range of `i64`; the returned value is then truncated.
+  pub fn int64_value<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Option<(i64, bool)> {
+    if self.is_number() {
+      let n = self.number_value(scope)?;
+      let lossless =
+        n.fract() == 0.0 && n >= i64::MIN as f64 && n < -(i64::MIN as f64);
+      return Some((n as i64, lossless));
+    }
+    self.to_big_int(scope).map(|b| b.i64_value())
+  }
+
+  /// Convenience function not present in the original V8 API.
+  ///
+  /// Reads this value as an unsigned 64-bit integer. See `int64_value()` for
+  /// how values are converted; here the `bool` is also `false` for negative
+  /// values.
+  pub fn uint64_value<'s>(
+    &self,
+    scope: &mut HandleScope<'s>,
+  ) -> Option<(u64, bool)> {
+    if self.is_number() {
+      let n = self.number_value(scope)?;
+      let lossless =
+        n.fract() == 0.0 && n >= 0.0 && n < -2.0 * (i64::MIN as f64);
+      return Some((n as u64, lossless));
+    }
+    self.to_big_int(scope).map(|b| b.u64_value())
+  }
+
   pub fn boolean_value<'s>(&self, scope: &mut HandleScope<'s, ()>) -> bool {
     unsafe { v8__Value__BooleanValue(self, scope.get_isolate_ptr()) }
   }
@@ -622,9 +684,102 @@ impl Value {
   /// Returns the V8 hash value for this value. The current implementation
   /// uses a hidden property to store the identity hash on some object types.
   ///
+  /// Primitives are hashed by value, objects by identity. The hash of a value
+  /// doesn't change when the garbage collector moves it, so unlike the
+  /// address of a handle it can be used to key a map by JavaScript value.
+  /// The `Hash` implementation of `Value` uses this method.
+  ///
   /// The return value will never be 0. Also, it is not guaranteed to be
   /// unique.
   pub fn get_hash(&self) -> NonZeroI32 {
     unsafe { NonZeroI32::new_unchecked(v8__Value__GetHash(self)) }
   }
 }
+
+/// Convenience trait not present in the original V8 API.
+///
+/// Extracts Rust values from a `Local<Value>` with `Result` return types, so
+/// that conversions can be chained with the `?` operator. None of these
+/// methods coerce: a value of the wrong type results in
+/// `DataError::BadType`, which names both the expected and the actual type.
+/// Use the `to_*()` and `*_value()` methods of `Value` to apply JavaScript's
+/// conversion rules instead.
+pub trait ValueExt<'s> {
+  /// Copies the contents of a string value into a new Rust `String`.
+  fn to_rust_string(
+    &self,
+    scope: &mut HandleScope,
+  ) -> Result<std::string::String, DataError>;
+
+  /// Returns the value of a number.
+  fn as_f64(&self) -> Result<f64, DataError>;
+
+  /// Returns the value of a number that is an integer in the range of `i64`,
+  /// or of a BigInt that fits in an `i64`.
+  fn as_i64(&self) -> Result<i64, DataError>;
+
+  /// Returns the value of a boolean.
+  fn as_bool(&self) -> Result<bool, DataError>;
+
+  fn as_object(&self) -> Result<Local<'s, Object>, DataError>;
+
+  fn as_array(&self) -> Result<Local<'s, Array>, DataError>;
+
+  fn as_function(&self) -> Result<Local<'s, Function>, DataError>;
+}
+
+impl<'s> ValueExt<'s> for Local<'s, Value> {
+  fn to_rust_string(
+    &self,
+    scope: &mut HandleScope,
+  ) -> Result<std::string::String, DataError> {
+    let string =
+      Local::<String>::try_from(*self).map_err(|_| bad_type::<String>(self))?;
+    Ok(string.to_rust_string_lossy(scope))
+  }
+
+  fn as_f64(&self) -> Result<f64, DataError> {
+    Local::<Number>::try_from(*self)
+      .map(|n| n.value())
+      .map_err(|_| bad_type::<Number>(self))
+  }
+
+  fn as_i64(&self) -> Result<i64, DataError> {
+    if let Ok(b) = Local::<BigInt>::try_from(*self) {
+      return match b.i64_value() {
+        (value, true) => Ok(value),
+        (_, false) => Err(bad_type::<i64>(self)),
+      };
+    }
+    let n = self.as_f64().map_err(|_| bad_type::<i64>(self))?;
+    if n.fract() == 0.0 && n >= i64::MIN as f64 && n < -(i64::MIN as f64) {
+      Ok(n as i64)
+    } else {
+      Err(bad_type::<i64>(self))
+    }
+  }
+
+  fn as_bool(&self) -> Result<bool, DataError> {
+    Local::<Boolean>::try_from(*self)
+      .map(|b| b.is_true())
+      .map_err(|_| bad_type::<Boolean>(self))
+  }
+
+  fn as_object(&self) -> Result<Local<'s, Object>, DataError> {
+    Local::<Object>::try_from(*self).map_err(|_| bad_type::<Object>(self))
+  }
+
+  fn as_array(&self) -> Result<Local<'s, Array>, DataError> {
+    Local::<Array>::try_from(*self).map_err(|_| bad_type::<Array>(self))
+  }
+
+  fn as_function(&self) -> Result<Local<'s, Function>, DataError> {
+    Local::<Function>::try_from(*self).map_err(|_| bad_type::<Function>(self))
+  }
+}
+
+/// Like `DataError::bad_type()`, but reports the actual type of `value`
+/// rather than the static type `Value`.
+fn bad_type<E: 'static>(value: &Value) -> DataError {
+  DataError::bad_type_of::<E, Value>(value)
+}
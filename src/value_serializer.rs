@@ -176,6 +176,10 @@ extern "C" {
     array_buffer: Local<ArrayBuffer>,
   );
 
+  fn v8__ValueSerializer__SetTreatArrayBufferViewsAsHostObjects(
+    this: *mut CxxValueSerializer,
+    mode: bool,
+  );
   fn v8__ValueSerializer__WriteHeader(this: *mut CxxValueSerializer);
   fn v8__ValueSerializer__WriteValue(
     this: *mut CxxValueSerializer,
@@ -372,6 +376,18 @@ pub trait ValueSerializerHelper {
       )
     };
   }
+
+  /// Indicate whether to treat ArrayBufferView objects as host objects,
+  /// i.e. pass them to `ValueSerializerImpl::write_host_object()` instead
+  /// of serializing them together with their backing buffer.
+  fn set_treat_array_buffer_views_as_host_objects(&mut self, mode: bool) {
+    unsafe {
+      v8__ValueSerializer__SetTreatArrayBufferViewsAsHostObjects(
+        self.get_cxx_value_serializer(),
+        mode,
+      )
+    };
+  }
 }
 
 impl ValueSerializerHelper for CxxValueSerializer {
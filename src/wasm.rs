@@ -154,7 +154,10 @@ impl CompiledWasmModule {
   }
 }
 
-// TODO(andreubotella): Safety???
+// SAFETY: v8::CompiledWasmModule holds a std::shared_ptr to a
+// wasm::NativeModule, which V8's process-wide WasmEngine already shares
+// between isolates on different threads. The wire bytes and source URL
+// exposed here are immutable once the module has been compiled.
 unsafe impl Send for CompiledWasmModule {}
 unsafe impl Sync for CompiledWasmModule {}
 
@@ -0,0 +1,10 @@
+// Copyright 2019-2021 the Deno authors. All rights reserved. MIT license.
+
+pub fn main() {
+  let mut isolate = v8::Isolate::new(mock());
+  let _local = isolate.with_handle_scope(|scope| v8::Integer::new(scope, 123));
+}
+
+fn mock<T>() -> T {
+  unimplemented!()
+}
@@ -244,3 +244,22 @@ fn slots_auto_boxing() {
   assert_eq!(Some(value2), core_isolate.remove_slot::<Box<TestData>>());
   assert_eq!(None, core_isolate.get_slot::<Box<TestData>>());
 }
+
+#[test]
+fn data_slots() {
+  let mut core_isolate = CoreIsolate::new(Default::default());
+  assert!(core_isolate.get_number_of_data_slots() > 0);
+
+  let mut value = TestData([1, 2, 3, 4]);
+  assert!(core_isolate.get_data(0).is_null());
+  core_isolate.set_data(0, &mut value as *mut TestData as *mut _);
+  assert_eq!(
+    core_isolate.get_data(0) as *mut TestData,
+    &mut value as *mut TestData
+  );
+  let last_slot = core_isolate.get_number_of_data_slots() - 1;
+  assert!(core_isolate.get_data(last_slot).is_null());
+
+  core_isolate.set_data(0, std::ptr::null_mut());
+  assert!(core_isolate.get_data(0).is_null());
+}
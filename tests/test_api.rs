@@ -77,6 +77,28 @@ fn handle_scope_numbers() {
   }
 }
 
+#[test]
+fn local_raw_ptr_roundtrip() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let value = v8::String::new(scope, "raw").unwrap();
+  let ptr = value.as_ptr();
+  assert!(!ptr.is_null());
+
+  let restored = unsafe { v8::Local::<v8::String>::from_raw_ptr(scope, ptr) };
+  let restored = restored.unwrap();
+  assert_eq!(restored, value);
+  assert_eq!(restored.to_rust_string_lossy(scope), "raw");
+
+  let empty =
+    unsafe { v8::Local::<v8::String>::from_raw_ptr(scope, std::ptr::null()) };
+  assert!(empty.is_none());
+}
+
 #[test]
 fn handle_scope_non_lexical_lifetime() {
   let _setup_guard = setup();
@@ -92,6 +114,30 @@ fn handle_scope_non_lexical_lifetime() {
   assert_eq!(local.value(), 123);
 }
 
+#[test]
+fn with_handle_scope() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+
+  let value = isolate.with_handle_scope(|scope| {
+    let local = v8::Integer::new(scope, 123);
+    local.value()
+  });
+  assert_eq!(value, 123);
+
+  let global = isolate.with_handle_scope(|scope| {
+    let context = v8::Context::new(scope);
+    v8::Global::new(scope, context)
+  });
+
+  let scope = &mut v8::HandleScope::with_context(isolate, &global);
+  let result = scope.with_handle_scope(|scope| {
+    let value = eval(scope, "'abc'.length").unwrap();
+    value.integer_value(scope).unwrap()
+  });
+  assert_eq!(result, 3);
+}
+
 #[test]
 fn global_handles() {
   let _setup_guard = setup();
@@ -204,6 +250,65 @@ fn global_handle_drop() {
   let _g2 = v8::Global::new(scope, l2);
 }
 
+#[test]
+fn global_try_clone() {
+  let _setup_guard = setup();
+
+  let global = {
+    let isolate = &mut v8::Isolate::new(Default::default());
+    let scope = &mut v8::HandleScope::new(isolate);
+    let local = v8::String::new(scope, "cloned").unwrap();
+    let global = v8::Global::new(scope, local);
+
+    let clone = global.try_clone().unwrap();
+    assert!(clone == global);
+    drop(global);
+    let local = v8::Local::new(scope, &clone);
+    assert_eq!(local.to_rust_string_lossy(scope), "cloned");
+    clone
+  };
+
+  // The host isolate has been disposed at this point.
+  assert!(global.try_clone().is_none());
+}
+
+#[test]
+fn local_try_new() {
+  let _setup_guard = setup();
+
+  let disposed_global = {
+    let isolate = &mut v8::Isolate::new(Default::default());
+    let scope = &mut v8::HandleScope::new(isolate);
+    let local = v8::String::new(scope, "disposed").unwrap();
+    v8::Global::new(scope, local)
+  };
+
+  let isolate1 = &mut v8::Isolate::new(Default::default());
+  let global1 = {
+    let scope1 = &mut v8::HandleScope::new(isolate1);
+    let local = v8::String::new(scope1, "one").unwrap();
+    v8::Global::new(scope1, local)
+  };
+
+  {
+    let isolate2 = &mut v8::Isolate::new(Default::default());
+    let scope2 = &mut v8::HandleScope::new(isolate2);
+    assert_eq!(
+      v8::Local::try_new(scope2, &global1).unwrap_err(),
+      v8::HandleError::WrongIsolate
+    );
+    assert_eq!(
+      v8::Local::try_new(scope2, &disposed_global).unwrap_err(),
+      v8::HandleError::DisposedIsolate
+    );
+  }
+
+  let scope1 = &mut v8::HandleScope::new(isolate1);
+  let local = v8::Local::try_new(scope1, &global1).unwrap();
+  assert_eq!(local.to_rust_string_lossy(scope1), "one");
+  drop(disposed_global);
+}
+
 #[test]
 fn test_string() {
   let _setup_guard = setup();
@@ -296,6 +401,46 @@ fn test_string() {
     );
     assert!(none.is_none());
   }
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let left = v8::String::new(scope, "caf").unwrap();
+    let right = v8::String::new_from_one_byte(
+      scope,
+      b"\xE9",
+      v8::NewStringType::Internalized,
+    )
+    .unwrap();
+    assert_eq!(1, right.length());
+    let local = v8::String::concat(scope, left, right).unwrap();
+    assert_eq!(4, local.length());
+    assert_eq!("café", local.to_rust_string_lossy(scope));
+    let empty = v8::String::empty(scope);
+    let local = v8::String::concat(scope, local, empty).unwrap();
+    assert_eq!("café", local.to_rust_string_lossy(scope));
+  }
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let key1 = v8::String::new_internalized(scope, "ключ").unwrap();
+    let key2 = v8::String::new_internalized(scope, "ключ").unwrap();
+    assert_eq!(4, key1.length());
+    // Both calls return the same internalized string, not just two strings
+    // with equal contents. `==` on `Local<String>` compares contents like
+    // `strict_equals()`, so compare the heap objects the handles point to.
+    fn heap_object(s: v8::Local<v8::String>) -> usize {
+      unsafe { *(&*s as *const v8::String as *const usize) }
+    }
+    assert_eq!(heap_object(key1), heap_object(key2));
+    let copy1 = v8::String::new(scope, "ключ").unwrap();
+    let copy2 = v8::String::new(scope, "ключ").unwrap();
+    assert!(copy1 == copy2);
+    assert_ne!(heap_object(copy1), heap_object(copy2));
+    let obj = v8::Object::new(scope);
+    let value = v8::Integer::new(scope, 1).into();
+    obj.set(scope, key1.into(), value).unwrap();
+    assert!(obj.get(scope, key2.into()).unwrap().strict_equals(value));
+  }
 }
 
 #[test]
@@ -337,6 +482,69 @@ fn escapable_handle_scope() {
   }
 }
 
+#[test]
+fn escapable_handle_scope_chaining() {
+  // Each helper escapes its result into the scope it was given, so helpers
+  // can be nested arbitrarily deep.
+  fn parse<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    json: &str,
+  ) -> v8::Local<'s, v8::Object> {
+    scope.escapable(|scope| {
+      let json = v8::String::new(scope, json).unwrap();
+      let value = v8::json::parse(scope, json).unwrap();
+      let object = v8::Local::<v8::Object>::try_from(value).unwrap();
+      scope.escape(object)
+    })
+  }
+
+  fn transform<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    input: v8::Local<v8::Object>,
+  ) -> v8::Local<'s, v8::Object> {
+    scope.escapable(|scope| {
+      let output = v8::Object::new(scope);
+      let names = input.get_own_property_names(scope).unwrap();
+      for i in 0..names.length() {
+        let name = names.get_index(scope, i).unwrap();
+        let value = input.get(scope, name).unwrap();
+        let doubled = value.number_value(scope).unwrap() * 2.0;
+        let doubled = v8::Number::new(scope, doubled);
+        output.set(scope, name, doubled.into()).unwrap();
+      }
+      scope.escape(output)
+    })
+  }
+
+  fn pipeline<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    json: &str,
+  ) -> v8::Local<'s, v8::Object> {
+    scope.escapable(|scope| {
+      let parsed = parse(scope, json);
+      let transformed = transform(scope, parsed);
+      scope.escape(transformed)
+    })
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let result = pipeline(scope, r#"{"a": 1, "b": 2.5}"#);
+  let result = v8::json::stringify(scope, result.into()).unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), r#"{"a":2,"b":5}"#);
+
+  // Values that don't need to be handles can be returned directly.
+  let length = scope.escapable(|scope| {
+    let object = parse(scope, "[1, 2, 3]");
+    v8::Local::<v8::Array>::try_from(object).unwrap().length()
+  });
+  assert_eq!(length, 3);
+}
+
 #[test]
 #[should_panic(expected = "EscapableHandleScope::escape() called twice")]
 fn escapable_handle_scope_can_escape_only_once() {
@@ -379,6 +587,60 @@ fn context_scope() {
   assert!(scope.get_entered_or_microtask_context() == context1);
 }
 
+#[test]
+fn get_entered_or_microtask_context_in_microtask() {
+  static CURRENT: AtomicUsize = AtomicUsize::new(0);
+  static ENTERED_OR_MICROTASK: AtomicUsize = AtomicUsize::new(0);
+
+  fn context_id(
+    scope: &mut v8::HandleScope,
+    context: v8::Local<v8::Context>,
+  ) -> usize {
+    let key = v8::String::new(scope, "id").unwrap();
+    let global = context.global(scope);
+    let id = global.get(scope, key.into()).unwrap();
+    id.uint32_value(scope).unwrap() as usize
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context1 = v8::Context::new(scope);
+  let context2 = v8::Context::new(scope);
+  {
+    let scope = &mut v8::ContextScope::new(scope, context2);
+    eval(scope, "globalThis.id = 2").unwrap();
+    let callback = v8::Function::new(
+      scope,
+      |scope: &mut v8::HandleScope,
+       _: v8::FunctionCallbackArguments,
+       _: v8::ReturnValue| {
+        let current = scope.get_current_context();
+        let id = context_id(scope, current);
+        CURRENT.store(id, Ordering::SeqCst);
+        let entered = scope.get_entered_or_microtask_context();
+        let id = context_id(scope, entered);
+        ENTERED_OR_MICROTASK.store(id, Ordering::SeqCst);
+      },
+    )
+    .unwrap();
+    let name = v8::String::new(scope, "callback").unwrap();
+    context2
+      .global(scope)
+      .set(scope, name.into(), callback.into());
+    eval(scope, "Promise.resolve().then(callback)").unwrap();
+  }
+
+  let scope = &mut v8::ContextScope::new(scope, context1);
+  eval(scope, "globalThis.id = 1").unwrap();
+  assert!(scope.get_entered_or_microtask_context() == context1);
+  scope.perform_microtask_checkpoint();
+  assert_eq!(CURRENT.load(Ordering::SeqCst), 2);
+  assert_eq!(ENTERED_OR_MICROTASK.load(Ordering::SeqCst), 2);
+}
+
 #[test]
 #[should_panic(
   expected = "HandleScope<()> and Context do not belong to the same Isolate"
@@ -431,7 +693,9 @@ fn microtasks() {
     v8::MicrotasksPolicy::Explicit
   );
 
+  assert_eq!(isolate.get_microtask_checkpoint_count(), 0);
   isolate.perform_microtask_checkpoint();
+  assert_eq!(isolate.get_microtask_checkpoint_count(), 1);
 
   {
     let scope = &mut v8::HandleScope::new(isolate);
@@ -456,6 +720,7 @@ fn microtasks() {
     assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 0);
     scope.perform_microtask_checkpoint();
     assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(scope.get_microtask_checkpoint_count(), 2);
 
     scope.set_microtasks_policy(v8::MicrotasksPolicy::Auto);
     assert_eq!(scope.get_microtasks_policy(), v8::MicrotasksPolicy::Auto);
@@ -464,7 +729,158 @@ fn microtasks() {
     let _ = eval(scope, "").unwrap();
 
     assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 2);
+    // Checkpoints performed by V8 itself are not counted.
+    assert_eq!(scope.get_microtask_checkpoint_count(), 2);
+  }
+}
+
+// The reentrant checkpoint aborts the process, so the test runs itself in a
+// child process and checks how that process exited.
+#[cfg(debug_assertions)]
+#[test]
+fn microtask_checkpoint_reentrancy_aborts() {
+  const CHILD_ENV: &str = "RUSTY_V8_TEST_REENTRANT_MICROTASK_CHECKPOINT";
+
+  if std::env::var_os(CHILD_ENV).is_none() {
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+      .args(["--exact", "microtask_checkpoint_reentrancy_aborts"])
+      .args(["--nocapture", "--test-threads=1"])
+      .env(CHILD_ENV, "1")
+      .output()
+      .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+      stderr.contains("perform_microtask_checkpoint() called reentrantly")
+    );
+    // The process was aborted rather than unwound by a panic.
+    assert!(!stderr.contains("panicked at"));
+    return;
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let function = v8::Function::new(
+    scope,
+    |scope: &mut v8::HandleScope,
+     _: v8::FunctionCallbackArguments,
+     _: v8::ReturnValue| {
+      scope.perform_microtask_checkpoint();
+    },
+  )
+  .unwrap();
+  scope.enqueue_microtask(function);
+  scope.perform_microtask_checkpoint();
+  unreachable!("the reentrant checkpoint should have aborted the process");
+}
+
+#[test]
+fn microtask_ordering() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let order = run_and_record_microtasks(
+    scope,
+    r#"
+      record("sync 1");
+      Promise.resolve()
+        .then(() => record("then 1"))
+        .then(() => record("then 2"));
+      queueMicrotask(() => record("queueMicrotask"));
+      (async () => {
+        record("async start");
+        await null;
+        record("after await");
+      })();
+      Promise.reject().catch(() => record("catch"));
+      record("sync 2");
+    "#,
+  );
+  assert_eq!(
+    order,
+    [
+      "sync 1",
+      "async start",
+      "sync 2",
+      "then 1",
+      "queueMicrotask",
+      "after await",
+      "catch",
+      "then 2",
+    ]
+  );
+
+  // Resolving with a thenable takes an extra job before the reaction runs.
+  // Microtasks enqueued from Rust run in order with the ones from JavaScript.
+  scope.enqueue_microtask_closure(|scope| {
+    let log = scope.get_slot_mut::<MicrotaskLog>().unwrap();
+    log.0.push("rust".to_string());
+  });
+  let order = run_and_record_microtasks(
+    scope,
+    r#"
+      const thenable = { then(resolve) { record("thenable"); resolve(); } };
+      Promise.resolve(thenable).then(() => record("resolved"));
+      Promise.resolve()
+        .then(() => record("a"))
+        .then(() => record("b"))
+        .then(() => record("c"));
+    "#,
+  );
+  assert_eq!(order, ["rust", "thenable", "a", "resolved", "b", "c"]);
+}
+
+#[test]
+fn microtask_closures() {
+  struct DropGuard(Arc<AtomicUsize>);
+
+  impl Drop for DropGuard {
+    fn drop(&mut self) {
+      self.0.fetch_add(1, Ordering::SeqCst);
+    }
+  }
+
+  let _setup_guard = setup();
+  let call_count = Arc::new(AtomicUsize::new(0));
+  let drop_count = Arc::new(AtomicUsize::new(0));
+  {
+    let isolate = &mut v8::Isolate::new(Default::default());
+    isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+
+    let call_count_ = call_count.clone();
+    let guard = DropGuard(drop_count.clone());
+    isolate.enqueue_microtask_closure(move |scope| {
+      let _guard = guard;
+      let context = v8::Context::new(scope);
+      let scope = &mut v8::ContextScope::new(scope, context);
+      let result = eval(scope, "1 + 2").unwrap();
+      assert_eq!(result.uint32_value(scope), Some(3));
+      call_count_.fetch_add(1, Ordering::SeqCst);
+    });
+    assert_eq!(call_count.load(Ordering::SeqCst), 0);
+    isolate.perform_microtask_checkpoint();
+    assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    assert_eq!(drop_count.load(Ordering::SeqCst), 1);
+
+    // Closures that never run are dropped together with the isolate.
+    let call_count_ = call_count.clone();
+    let guard = DropGuard(drop_count.clone());
+    isolate.enqueue_microtask_closure(move |_| {
+      let _guard = guard;
+      call_count_.fetch_add(1, Ordering::SeqCst);
+    });
+    assert_eq!(drop_count.load(Ordering::SeqCst), 1);
   }
+  assert_eq!(call_count.load(Ordering::SeqCst), 1);
+  assert_eq!(drop_count.load(Ordering::SeqCst), 2);
 }
 
 #[test]
@@ -559,8 +975,10 @@ fn array_buffer() {
     assert_eq!(42, ab.byte_length());
 
     assert!(ab.is_detachable());
+    assert!(!ab.was_detached());
     ab.detach();
     assert_eq!(0, ab.byte_length());
+    assert!(ab.was_detached());
     ab.detach(); // Calling it twice should be a no-op.
 
     let bs = v8::ArrayBuffer::new_backing_store(scope, 84);
@@ -645,6 +1063,39 @@ fn array_buffer() {
   }
 }
 
+#[test]
+fn backing_store_from_vec_without_copy() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  // A vector with spare capacity: the deleter must free the whole allocation.
+  let mut data = Vec::with_capacity(64);
+  data.extend_from_slice(b"hello");
+  let data_ptr = data.as_ptr();
+  let backing_store =
+    v8::ArrayBuffer::new_backing_store_from_vec(data).make_shared();
+  assert_eq!(5, backing_store.byte_length());
+  assert_eq!(
+    backing_store.data().unwrap().as_ptr() as *const u8,
+    data_ptr
+  );
+
+  let ab = v8::ArrayBuffer::with_backing_store(scope, &backing_store);
+  let global = context.global(scope);
+  let name = v8::String::new(scope, "ab").unwrap();
+  global.set(scope, name.into(), ab.into()).unwrap();
+  let result = eval(
+    scope,
+    "new Uint8Array(ab)[4] = 33; new Uint8Array(ab).join()",
+  )
+  .unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "104,101,108,108,33");
+  assert_eq!(backing_store[4].get(), b'!');
+}
+
 #[test]
 fn backing_store_segfault() {
   let _setup_guard = setup();
@@ -670,6 +1121,21 @@ fn backing_store_segfault() {
   drop(shared_bs); // Error occurred here.
 }
 
+#[test]
+fn array_buffer_new_resizable_without_flag() {
+  // This process doesn't run with --harmony-rab-gsab, see
+  // test_api_resizable_array_buffer.rs for the flag-enabled case.
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let scope = &mut v8::TryCatch::new(scope);
+
+  assert!(v8::ArrayBuffer::new_resizable(scope, 4, 16).is_none());
+  assert!(!scope.has_caught());
+}
+
 #[test]
 fn shared_array_buffer_allocator() {
   let alloc1 = v8::new_default_allocator().make_shared();
@@ -761,6 +1227,56 @@ fn deref_empty_backing_store() {
   assert!(!slice.as_ptr().is_null());
 }
 
+#[test]
+fn zero_length_array_buffer_and_views() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let ab = v8::ArrayBuffer::new(scope, 0);
+  assert_eq!(ab.byte_length(), 0);
+  let backing_store = ab.get_backing_store();
+  assert_eq!(backing_store.byte_length(), 0);
+  let slice: &[std::cell::Cell<u8>] = &backing_store;
+  assert!(slice.is_empty());
+  assert!(!slice.as_ptr().is_null());
+
+  let view = v8::Uint8Array::new(scope, ab, 0, 0).unwrap();
+  assert_eq!(view.byte_length(), 0);
+  assert!(view.data(scope).is_none());
+  assert_eq!(unsafe { view.with_slice(scope, |bytes| bytes.len()) }, 0);
+  assert_eq!(view.copy_contents(&mut [0; 4]), 0);
+
+  fn check_empty(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+  ) {
+    let view = v8::Local::<v8::ArrayBufferView>::try_from(args.get(0)).unwrap();
+    let empty = view.byte_length() == 0
+      && view.data(scope).is_none()
+      && unsafe { view.with_slice(scope, |bytes| bytes.is_empty()) }
+      && view.get_backing_store(scope).is_some();
+    rv.set(v8::Boolean::new(scope, empty).into());
+  }
+  let check_empty = v8::Function::new(scope, check_empty).unwrap();
+  let name = v8::String::new(scope, "checkEmpty").unwrap();
+  let global = context.global(scope);
+  global.set(scope, name.into(), check_empty.into()).unwrap();
+
+  let source = r#"
+    [
+      checkEmpty(new Uint8Array()),
+      checkEmpty(new Uint8Array(8).subarray(8)),
+      checkEmpty(new DataView(new ArrayBuffer(0))),
+    ].join()
+  "#;
+  let result = eval(scope, source).unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "true,true,true");
+}
+
 fn eval<'s>(
   scope: &mut v8::HandleScope<'s>,
   code: &str,
@@ -772,6 +1288,38 @@ fn eval<'s>(
   r.map(|v| scope.escape(v))
 }
 
+/// The invocation order recorded by `run_and_record_microtasks()`.
+struct MicrotaskLog(Vec<String>);
+
+/// Runs `code` and then drains the microtask queue. The script can call the
+/// global function `record(label)` to log the order in which its callbacks
+/// run; the labels are returned in that order. Rust microtasks can log to the
+/// same list through the `MicrotaskLog` slot of the isolate.
+fn run_and_record_microtasks(
+  scope: &mut v8::HandleScope,
+  code: &str,
+) -> Vec<String> {
+  fn record(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _rv: v8::ReturnValue,
+  ) {
+    let label = args.get(0).to_rust_string_lossy(scope);
+    scope.get_slot_mut::<MicrotaskLog>().unwrap().0.push(label);
+  }
+
+  scope.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+  scope.set_slot(MicrotaskLog(Vec::new()));
+  let record = v8::Function::new(scope, record).unwrap();
+  let name = v8::String::new(scope, "record").unwrap();
+  let global = scope.get_current_context().global(scope);
+  global.set(scope, name.into(), record.into()).unwrap();
+
+  eval(scope, code).unwrap();
+  scope.perform_microtask_checkpoint();
+  scope.remove_slot::<MicrotaskLog>().unwrap().0
+}
+
 #[test]
 fn external() {
   let _setup_guard = setup();
@@ -931,18 +1479,69 @@ fn throw_exception() {
 }
 
 #[test]
-fn isolate_termination_methods() {
-  let _setup_guard = setup();
-  let isolate = v8::Isolate::new(Default::default());
-  let handle = isolate.thread_safe_handle();
-  drop(isolate);
-  assert!(!handle.terminate_execution());
-  assert!(!handle.cancel_terminate_execution());
-  assert!(!handle.is_execution_terminating());
-  static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
-  extern "C" fn callback(
-    _isolate: &mut v8::Isolate,
-    data: *mut std::ffi::c_void,
+fn throw_exception_in_callback() {
+  fn throw_callback(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+  ) {
+    let exception = args.get(0);
+    let undefined = scope.throw_exception(exception);
+    assert!(undefined.is_undefined());
+    rv.set(v8::Integer::new(scope, 42).into());
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let function = v8::Function::new(scope, throw_callback).unwrap();
+  let name = v8::String::new(scope, "throwIt").unwrap();
+  context
+    .global(scope)
+    .set(scope, name.into(), function.into());
+
+  // The exception can be caught from JavaScript.
+  let result = eval(
+    scope,
+    "try { throwIt('inner'); 'not thrown' } catch (e) { 'caught ' + e }",
+  )
+  .unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "caught inner");
+
+  // Or from Rust, by a TryCatch.
+  let tc = &mut v8::TryCatch::new(scope);
+  assert!(eval(tc, "throwIt('outer')").is_none());
+  assert!(tc.has_caught());
+  assert!(!tc.has_terminated());
+  assert!(tc.can_continue());
+  let exception = tc.exception().unwrap();
+  assert_eq!(exception.to_rust_string_lossy(tc), "outer");
+
+  // Resetting the TryCatch clears the pending exception, so JavaScript can
+  // run again.
+  tc.reset();
+  assert!(!tc.has_caught());
+  assert!(tc.exception().is_none());
+  let result = eval(tc, "1 + 2").unwrap();
+  assert_eq!(result.int32_value(tc), Some(3));
+  assert!(!tc.has_caught());
+}
+
+#[test]
+fn isolate_termination_methods() {
+  let _setup_guard = setup();
+  let isolate = v8::Isolate::new(Default::default());
+  let handle = isolate.thread_safe_handle();
+  drop(isolate);
+  assert!(!handle.terminate_execution());
+  assert!(!handle.cancel_terminate_execution());
+  assert!(!handle.is_execution_terminating());
+  static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+  extern "C" fn callback(
+    _isolate: &mut v8::Isolate,
+    data: *mut std::ffi::c_void,
   ) {
     assert_eq!(data, std::ptr::null_mut());
     CALL_COUNT.fetch_add(1, Ordering::SeqCst);
@@ -951,6 +1550,58 @@ fn isolate_termination_methods() {
   assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 0);
 }
 
+#[test]
+fn isolate_is_in_use() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  assert!(isolate.is_in_use());
+}
+
+#[test]
+fn isolate_data_slots() {
+  let _setup_guard = setup();
+  let mut isolate = v8::Isolate::new(Default::default());
+  let handle = isolate.thread_safe_handle();
+  assert!(isolate.get_number_of_data_slots() > 0);
+
+  let mut value = 42u32;
+  let ptr = &mut value as *mut u32 as *mut std::ffi::c_void;
+  assert!(isolate.get_data(0).is_null());
+  isolate.set_data(0, ptr);
+  assert_eq!(isolate.get_data(0), ptr);
+
+  // The crate keeps its own per-isolate state in a slot of its own, so slot 0
+  // is not in its way.
+  assert!(isolate.set_slot(1u32));
+  assert_eq!(isolate.get_slot::<u32>(), Some(&1));
+
+  // Dropping the isolate still releases that state.
+  drop(isolate);
+  assert!(!handle.terminate_execution());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn handle_scope_on_thread_that_did_not_enter_isolate() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let isolate_ptr = &mut **isolate as *mut v8::Isolate as usize;
+
+  let result = std::thread::spawn(move || {
+    let isolate = unsafe { &mut *(isolate_ptr as *mut v8::Isolate) };
+    let _scope = v8::HandleScope::new(isolate);
+  })
+  .join();
+  let message = *result.unwrap_err().downcast::<&str>().unwrap();
+  assert_eq!(message, "Isolate is not entered on the current thread");
+
+  // The isolate is still usable on the thread that entered it.
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  assert!(eval(scope, "true").unwrap().is_true());
+}
+
 #[test]
 fn thread_safe_handle_drop_after_isolate() {
   let _setup_guard = setup();
@@ -1205,11 +1856,78 @@ fn script_origin() {
   }
 }
 
+#[test]
+fn message_script_id_and_wasm_function_index() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  {
+    let tc = &mut v8::TryCatch::new(scope);
+    let source = v8::String::new(tc, "throw new Error('js')").unwrap();
+    let script = v8::Script::compile(tc, source, None).unwrap();
+    let script_id = script.get_unbound_script(tc).get_id();
+    assert!(script.run(tc).is_none());
+    let message = tc.message().unwrap();
+    assert_eq!(message.get_script_id(), script_id);
+    assert_eq!(message.get_wasm_function_index(), -1);
+  }
+
+  {
+    // A module exporting a single function `f` whose body is `unreachable`.
+    let tc = &mut v8::TryCatch::new(scope);
+    let result = eval(
+      tc,
+      "const bytes = new Uint8Array([
+         0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+         0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+         0x03, 0x02, 0x01, 0x00,
+         0x07, 0x05, 0x01, 0x01, 0x66, 0x00, 0x00,
+         0x0a, 0x05, 0x01, 0x03, 0x00, 0x00, 0x0b,
+       ]);
+       new WebAssembly.Instance(new WebAssembly.Module(bytes)).exports.f();",
+    );
+    assert!(result.is_none());
+    let message = tc.message().unwrap();
+    assert_eq!(message.get_wasm_function_index(), 0);
+  }
+}
+
 #[test]
 fn get_version() {
   assert!(v8::V8::get_version().len() > 3);
 }
 
+#[test]
+fn initialize_icu_and_external_startup_data() {
+  let _setup_guard = setup();
+  // This build links ICU data and the snapshot into the binary, so the paths
+  // are ignored and these calls must not disturb the running instance.
+  let exe = std::env::current_exe().unwrap();
+  let exe = exe.to_str().unwrap();
+  assert!(v8::V8::initialize_icu(None));
+  assert!(v8::V8::initialize_icu_default_location(exe, None));
+  assert!(v8::V8::initialize_icu_default_location(
+    exe,
+    Some("icudtl.dat")
+  ));
+  let dir = std::env::temp_dir();
+  v8::V8::initialize_external_startup_data(dir.to_str().unwrap());
+  v8::V8::initialize_external_startup_data_from_file(
+    dir.join("snapshot_blob.bin").to_str().unwrap(),
+  );
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let result = eval(scope, "new Intl.NumberFormat('en-US').format(1234.5)");
+  let result = result.unwrap().to_rust_string_lossy(scope);
+  assert_eq!(result, "1,234.5");
+}
+
 #[test]
 fn set_flags_from_command_line() {
   let r = v8::V8::set_flags_from_command_line(vec![
@@ -1304,6 +2022,35 @@ fn exception() {
   assert!(v8::Exception::get_stack_trace(scope, exception).is_none());
 }
 
+#[test]
+fn exception_get_stack_trace() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_capture_stack_trace_for_uncaught_exceptions(true, 10);
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let tc = &mut v8::TryCatch::new(scope);
+  let result = eval(
+    tc,
+    "function thrower() { throw new Error('boom') }\n thrower()",
+  );
+  assert!(result.is_none());
+  let exception = tc.exception().unwrap();
+
+  let stack_trace = v8::Exception::get_stack_trace(tc, exception).unwrap();
+  assert!(stack_trace.get_frame_count() > 0);
+  let frame = stack_trace.get_frame(tc, 0).unwrap();
+  let function_name = frame.get_function_name(tc).unwrap();
+  assert_eq!(function_name.to_rust_string_lossy(tc), "thrower");
+
+  let stack = tc.stack_trace().unwrap();
+  let stack = stack.to_rust_string_lossy(tc);
+  assert!(stack.starts_with("Error: boom"));
+  assert!(stack.contains("at thrower"));
+}
+
 #[test]
 fn create_message_argument_lifetimes() {
   let _setup_guard = setup();
@@ -1356,6 +2103,76 @@ fn json() {
   }
 }
 
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+  use serde::{Deserialize, Serialize};
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  enum Shape {
+    Point,
+    Circle(f64),
+    Rect { w: u32, h: u32 },
+  }
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  struct Doc {
+    name: String,
+    tags: Vec<String>,
+    count: i64,
+    ratio: f64,
+    missing: Option<u8>,
+    shapes: Vec<Shape>,
+    big: u64,
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let doc = Doc {
+      name: "hello".to_string(),
+      tags: vec!["a".to_string(), "b".to_string()],
+      count: -3,
+      ratio: 0.5,
+      missing: None,
+      shapes: vec![
+        Shape::Point,
+        Shape::Circle(1.5),
+        Shape::Rect { w: 2, h: 3 },
+      ],
+      big: u64::MAX,
+    };
+    let value = v8::serde::to_v8(scope, &doc).unwrap();
+    assert!(value.is_object());
+    let global = context.global(scope);
+    let key = v8::String::new(scope, "doc").unwrap();
+    global.set(scope, key.into(), value);
+
+    let json =
+      eval(scope, "JSON.stringify({ ...doc, big: typeof doc.big })").unwrap();
+    assert_eq!(
+      json.to_rust_string_lossy(scope),
+      r#"{"name":"hello","tags":["a","b"],"count":-3,"ratio":0.5,"missing":null,"shapes":["Point",{"Circle":1.5},{"Rect":{"w":2,"h":3}}],"big":"bigint"}"#
+    );
+
+    let round_trip: Doc = v8::serde::from_v8(scope, value).unwrap();
+    assert_eq!(round_trip, doc);
+
+    let value = eval(scope, "({ a: [1, 2], b: [] })").unwrap();
+    let map: HashMap<String, Vec<u32>> =
+      v8::serde::from_v8(scope, value).unwrap();
+    assert_eq!(map["a"], vec![1, 2]);
+    assert!(map["b"].is_empty());
+
+    let value = eval(scope, "'nope'").unwrap();
+    assert!(v8::serde::from_v8::<u32>(scope, value).is_err());
+  }
+}
+
 #[test]
 fn no_internal_field() {
   let _setup_guard = setup();
@@ -1433,6 +2250,31 @@ fn object_template() {
   }
 }
 
+#[test]
+fn object_aligned_pointer_in_internal_field() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let object_templ = v8::ObjectTemplate::new(scope);
+    object_templ.set_internal_field_count(1);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let object = object_templ.new_instance(scope).unwrap();
+    let data = Box::into_raw(Box::new(42u64));
+    unsafe {
+      assert!(object.set_aligned_pointer_in_internal_field(0, data as _));
+      assert!(!object.set_aligned_pointer_in_internal_field(1, data as _));
+      let ptr = object.get_aligned_pointer_from_internal_field(0);
+      assert_eq!(ptr as *mut u64, data);
+      assert_eq!(*(ptr as *mut u64), 42);
+      assert!(object.get_aligned_pointer_from_internal_field(1).is_null());
+      drop(Box::from_raw(data));
+    }
+  }
+}
+
 #[test]
 fn object_template_from_function_template() {
   let _setup_guard = setup();
@@ -1493,6 +2335,35 @@ fn function_template_signature() {
   }
 }
 
+#[test]
+fn function_template_accept_any_receiver() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let global = context.global(scope);
+
+  for (name, accept_any_receiver) in [("f", true), ("g", false)] {
+    let templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
+    templ.set_accept_any_receiver(accept_any_receiver);
+    let function = templ.get_function(scope).unwrap();
+    let name = v8::String::new(scope, name).unwrap();
+    global.set(scope, name.into(), function.into()).unwrap();
+  }
+
+  // Receivers that don't need access checks are accepted either way,
+  // including primitives.
+  let source = r#"
+    String.prototype.f = f;
+    String.prototype.g = g;
+    [f.call({}), g.call({}), 'abc'.f(), 'abc'.g(), f.call(1)].join()
+  "#;
+  let actual = eval(scope, source).unwrap();
+  let expected = v8::String::new(scope, "42,42,42,42,42").unwrap();
+  assert!(expected.strict_equals(actual));
+}
+
 #[test]
 fn function_template_prototype() {
   let _setup_guard = setup();
@@ -1566,6 +2437,149 @@ fn function_template_prototype() {
   }
 }
 
+#[test]
+fn function_template_prototype_method_attributes() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let function_templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
+    let prototype_templ = function_templ.prototype_template(scope);
+    let method_templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
+    let hidden = v8::String::new(scope, "hidden").unwrap();
+    prototype_templ.set_with_attr(
+      hidden.into(),
+      method_templ.into(),
+      v8::DONT_ENUM,
+    );
+    let visible = v8::String::new(scope, "visible").unwrap();
+    prototype_templ.set(visible.into(), method_templ.into());
+
+    let function = function_templ.get_function(scope).unwrap();
+    let name = v8::String::new(scope, "C").unwrap();
+    context
+      .global(scope)
+      .set(scope, name.into(), function.into());
+
+    let source = r#"
+      {
+        const keys = [];
+        const c = new C();
+        for (const key in c) keys.push(key);
+        [keys.join(), c.hidden(), c.visible()].join()
+      }
+    "#;
+    let actual = eval(scope, source).unwrap();
+    let expected = v8::String::new(scope, "visible,42,42").unwrap();
+    assert!(expected.strict_equals(actual));
+  }
+}
+
+#[test]
+fn function_template_read_only_and_remove_prototype() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let global = context.global(scope);
+
+    let read_only_templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
+    read_only_templ.read_only_prototype();
+    let read_only = read_only_templ.get_function(scope).unwrap();
+    let name = v8::String::new(scope, "ReadOnly").unwrap();
+    global.set(scope, name.into(), read_only.into());
+
+    let no_proto_templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
+    no_proto_templ.remove_prototype();
+    let no_proto = no_proto_templ.get_function(scope).unwrap();
+    let name = v8::String::new(scope, "NoProto").unwrap();
+    global.set(scope, name.into(), no_proto.into());
+
+    let source = r#"
+      {
+        const desc = Object.getOwnPropertyDescriptor(ReadOnly, "prototype");
+        const before = ReadOnly.prototype;
+        ReadOnly.prototype = {};
+        [
+          desc.writable,
+          ReadOnly.prototype === before,
+          "prototype" in NoProto,
+          NoProto(),
+        ].join()
+      }
+    "#;
+    let actual = eval(scope, source).unwrap();
+    let expected = v8::String::new(scope, "false,true,false,42").unwrap();
+    assert!(expected.strict_equals(actual));
+
+    // Functions without a prototype cannot be used as constructors.
+    let scope = &mut v8::TryCatch::new(scope);
+    assert!(eval(scope, "new NoProto()").is_none());
+    assert!(scope.has_caught());
+  }
+}
+
+#[test]
+fn template_set_lazy_data_property() {
+  static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+
+  let getter = |scope: &mut v8::HandleScope,
+                key: v8::Local<v8::Name>,
+                args: v8::PropertyCallbackArguments,
+                mut rv: v8::ReturnValue| {
+    CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+    let expected_key = v8::String::new(scope, "env").unwrap();
+    assert!(key.strict_equals(expected_key.into()));
+    let env = v8::Object::new(scope);
+    let key = v8::String::new(scope, "HOME").unwrap();
+    env.set(scope, key.into(), args.data());
+    rv.set(env.into());
+  };
+
+  let templ = v8::ObjectTemplate::new(scope);
+  let key = v8::String::new(scope, "env").unwrap();
+  let data = v8::String::new(scope, "/home/deno").unwrap();
+  templ.set_lazy_data_property(
+    key.into(),
+    getter,
+    Some(data.into()),
+    v8::READ_ONLY,
+  );
+
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let obj = templ.new_instance(scope).unwrap();
+  let name = v8::String::new(scope, "process").unwrap();
+  context.global(scope).set(scope, name.into(), obj.into());
+  assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 0);
+
+  let result = eval(scope, "process.env.HOME").unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "/home/deno");
+  assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
+
+  // The result is cached as a read-only data property.
+  assert!(eval(scope, "process.env === process.env")
+    .unwrap()
+    .is_true());
+  let result = eval(
+    scope,
+    "const desc = Object.getOwnPropertyDescriptor(process, 'env');
+     'value' in desc && !desc.writable",
+  )
+  .unwrap();
+  assert!(result.is_true());
+  assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
+}
+
 #[test]
 fn object_template_set_accessor() {
   let _setup_guard = setup();
@@ -1771,6 +2785,51 @@ fn object() {
   }
 }
 
+#[test]
+#[should_panic(expected = "names and values must have the same length")]
+fn object_with_prototype_and_properties_length_mismatch() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let null: v8::Local<v8::Value> = v8::null(scope).into();
+  let n1: v8::Local<v8::Name> = v8::String::new(scope, "a").unwrap().into();
+  let n2: v8::Local<v8::Name> = v8::String::new(scope, "b").unwrap().into();
+  let v1: v8::Local<v8::Value> = v8::Number::new(scope, 1.0).into();
+  v8::Object::with_prototype_and_properties(scope, null, &[n1, n2], &[v1]);
+}
+
+#[test]
+fn object_get_creation_context_cross_realm() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+
+  let home = v8::Context::new(scope);
+  let object = {
+    let scope = &mut v8::ContextScope::new(scope, home);
+    eval(scope, "globalThis.realm = 'home'").unwrap();
+    let object = eval(scope, "({ realm() { return globalThis.realm; } })");
+    v8::Local::<v8::Object>::try_from(object.unwrap()).unwrap()
+  };
+
+  let other = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, other);
+  eval(scope, "globalThis.realm = 'other'").unwrap();
+  let creation_context = object.get_creation_context(scope).unwrap();
+  assert!(creation_context == home);
+  assert!(creation_context != other);
+
+  // Dispatch into the object's home realm.
+  let scope = &mut v8::ContextScope::new(scope, creation_context);
+  let key = v8::String::new(scope, "realm").unwrap();
+  let method = object.get(scope, key.into()).unwrap();
+  let method = v8::Local::<v8::Function>::try_from(method).unwrap();
+  let result = method.call(scope, object.into(), &[]).unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "home");
+}
+
 #[test]
 fn map() {
   let _setup_guard = setup();
@@ -1816,25 +2875,145 @@ fn map() {
 }
 
 #[test]
-fn array() {
+fn weak_map_and_weak_set() {
   let _setup_guard = setup();
   let isolate = &mut v8::Isolate::new(Default::default());
   {
     let scope = &mut v8::HandleScope::new(isolate);
     let context = v8::Context::new(scope);
     let scope = &mut v8::ContextScope::new(scope, context);
-    let s1 = v8::String::new(scope, "a").unwrap();
-    let s2 = v8::String::new(scope, "b").unwrap();
-    let array = v8::Array::new(scope, 2);
-    assert_eq!(array.length(), 2);
-    let lhs = array.get_creation_context(scope).unwrap().global(scope);
-    let rhs = context.global(scope);
-    assert!(lhs.strict_equals(rhs.into()));
-    array.set_index(scope, 0, s1.into());
-    array.set_index(scope, 1, s2.into());
 
-    let maybe_v1 = array.get_index(scope, 0);
-    assert!(maybe_v1.is_some());
+    let map = v8::WeakMap::new(scope);
+    assert!(map.is_weak_map());
+    let key = v8::Object::new(scope);
+    let value = v8::Integer::new(scope, 1337).into();
+    assert!(!map.has(scope, key));
+    assert!(map.get(scope, key).is_undefined());
+    map.set(scope, key, value);
+    assert!(map.has(scope, key));
+    assert_eq!(map.get(scope, key), value);
+    let undefined = v8::undefined(scope).into();
+    map.set(scope, key, undefined);
+    assert!(map.has(scope, key));
+    assert!(map.delete(scope, key));
+    assert!(!map.has(scope, key));
+    assert!(!map.delete(scope, key));
+    let fresh = v8::Object::new(scope);
+    assert!(!map.delete(scope, fresh));
+
+    let set = v8::WeakSet::new(scope);
+    assert!(set.is_weak_set());
+    assert!(!set.has(scope, key));
+    set.add(scope, key);
+    assert!(set.has(scope, key));
+    assert!(set.delete(scope, key));
+    assert!(!set.has(scope, key));
+
+    // Entries added from Rust are visible to scripts and vice versa.
+    map.set(scope, key, value);
+    set.add(scope, key);
+    let global = context.global(scope);
+    for (name, value) in [
+      ("map", map.into()),
+      ("set", set.into()),
+      ("key", key.into()),
+    ] {
+      let name = v8::String::new(scope, name).unwrap();
+      global.set(scope, name.into(), value).unwrap();
+    }
+    let result = eval(scope, "map.get(key) === 1337 && set.has(key)").unwrap();
+    assert!(result.is_true());
+    let other = eval(
+      scope,
+      "other = {}; map.set(other, 1); set.add(other); other",
+    )
+    .unwrap();
+    let other = v8::Local::<v8::Object>::try_from(other).unwrap();
+    assert_eq!(map.get(scope, other).int32_value(scope), Some(1));
+    assert!(set.has(scope, other));
+
+    // Neither collection keeps its keys alive, and an entry goes away with
+    // its key, which the value being collected as well shows.
+    let (weak_key, weak_value, weak_set_key) = {
+      let scope = &mut v8::HandleScope::new(scope);
+      let key = v8::Object::new(scope);
+      let value = v8::Object::new(scope);
+      map.set(scope, key, value.into());
+      let set_key = v8::Object::new(scope);
+      set.add(scope, set_key);
+      assert!(map.has(scope, key));
+      assert!(set.has(scope, set_key));
+      (
+        v8::Weak::new(scope, key),
+        v8::Weak::new(scope, value),
+        v8::Weak::new(scope, set_key),
+      )
+    };
+    eval(scope, "gc()").unwrap();
+    assert!(weak_key.is_empty());
+    assert!(weak_value.is_empty());
+    assert!(weak_set_key.is_empty());
+
+    // Entries whose key is still reachable survive the collection.
+    assert_eq!(map.get(scope, key), value);
+    assert!(set.has(scope, key));
+  }
+}
+
+#[test]
+fn object_preview_entries() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let map = eval(scope, "new Map([['a', 1], ['b', 2]])").unwrap();
+  let map = v8::Local::<v8::Object>::try_from(map).unwrap();
+  let (entries, is_key_value) = map.preview_entries(scope).unwrap();
+  assert!(is_key_value);
+  assert_eq!(entries.length(), 4);
+  let key = entries.get_index(scope, 2).unwrap();
+  assert_eq!(key.to_rust_string_lossy(scope), "b");
+  let value = entries.get_index(scope, 3).unwrap();
+  assert_eq!(value.int32_value(scope), Some(2));
+
+  let set = eval(scope, "new Set(['x', 'y', 'z'])").unwrap();
+  let set = v8::Local::<v8::Object>::try_from(set).unwrap();
+  let (entries, is_key_value) = set.preview_entries(scope).unwrap();
+  assert!(!is_key_value);
+  assert_eq!(entries.length(), 3);
+
+  let iterator = eval(scope, "new Map([['a', 1]]).keys()").unwrap();
+  let iterator = v8::Local::<v8::Object>::try_from(iterator).unwrap();
+  let (entries, is_key_value) = iterator.preview_entries(scope).unwrap();
+  assert!(!is_key_value);
+  assert_eq!(entries.length(), 1);
+
+  let object = v8::Object::new(scope);
+  assert!(object.preview_entries(scope).is_none());
+}
+
+#[test]
+fn array() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let s1 = v8::String::new(scope, "a").unwrap();
+    let s2 = v8::String::new(scope, "b").unwrap();
+    let array = v8::Array::new(scope, 2);
+    assert_eq!(array.length(), 2);
+    let lhs = array.get_creation_context(scope).unwrap().global(scope);
+    let rhs = context.global(scope);
+    assert!(lhs.strict_equals(rhs.into()));
+    array.set_index(scope, 0, s1.into());
+    array.set_index(scope, 1, s2.into());
+
+    let maybe_v1 = array.get_index(scope, 0);
+    assert!(maybe_v1.is_some());
     assert!(maybe_v1.unwrap().same_value(s1.into()));
     let maybe_v2 = array.get_index(scope, 1);
     assert!(maybe_v2.is_some());
@@ -1859,6 +3038,42 @@ fn array() {
   }
 }
 
+#[test]
+fn array_iter() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let array = v8::Array::new(scope, 0);
+    assert_eq!(array.iter(scope).count(), 0);
+
+    let array: v8::Local<v8::Array> =
+      eval(scope, "[1, 'two', 3]").unwrap().try_into().unwrap();
+    let values = array.iter(scope).collect::<Vec<_>>();
+    assert_eq!(values.len(), 3);
+    assert!(values[0].is_number());
+    assert_eq!(values[1].to_rust_string_lossy(scope), "two");
+    assert!(values[2].is_number());
+
+    let scope = &mut v8::TryCatch::new(scope);
+    let array: v8::Local<v8::Array> = eval(
+      scope,
+      "const a = [1, 2, 3];
+       Object.defineProperty(a, 1, { get() { throw new Error('boom'); } });
+       a",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert!(!scope.has_caught());
+    assert_eq!(array.iter(scope).count(), 1);
+    assert!(scope.has_caught());
+  }
+}
+
 #[test]
 fn create_data_property() {
   let _setup_guard = setup();
@@ -1889,6 +3104,233 @@ fn create_data_property() {
   }
 }
 
+#[test]
+fn object_get_real_named_property() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let obj: v8::Local<v8::Object> = eval(
+      scope,
+      "const proto = { inherited: 1 };
+       const o = Object.create(proto);
+       Object.defineProperty(o, 'fixed', { value: 2, enumerable: true });
+       o",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    let key = v8::String::new(scope, "inherited").unwrap();
+    let value = obj.get_real_named_property(scope, key.into()).unwrap();
+    assert_eq!(value.int32_value(scope), Some(1));
+    let attrs = obj
+      .get_real_named_property_attributes(scope, key.into())
+      .unwrap();
+    assert!(attrs.is_none());
+
+    let key = v8::String::new(scope, "fixed").unwrap();
+    let value = obj.get_real_named_property(scope, key.into()).unwrap();
+    assert_eq!(value.int32_value(scope), Some(2));
+    let attrs = obj
+      .get_real_named_property_attributes(scope, key.into())
+      .unwrap();
+    assert!(attrs.is_read_only());
+    assert!(attrs.is_dont_delete());
+    assert!(!attrs.is_dont_enum());
+
+    let key = v8::String::new(scope, "missing").unwrap();
+    assert!(obj.get_real_named_property(scope, key.into()).is_none());
+    assert!(obj
+      .get_real_named_property_attributes(scope, key.into())
+      .is_none());
+  }
+}
+
+#[test]
+fn object_property_attributes_and_descriptor() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let obj: v8::Local<v8::Object> = eval(
+      scope,
+      "const proto = {};
+       Object.defineProperty(proto, 'inherited', { value: 3 });
+       const o = Object.create(proto);
+       o.plain = 1;
+       o.toString = function() { return 'custom'; };
+       Object.defineProperty(o, 'fixed', { value: 2, enumerable: true });
+       o",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    let plain = v8::String::new(scope, "plain").unwrap();
+    let attrs = obj.get_property_attributes(scope, plain.into()).unwrap();
+    assert!(attrs.is_none());
+
+    let fixed = v8::String::new(scope, "fixed").unwrap();
+    let attrs = obj.get_property_attributes(scope, fixed.into()).unwrap();
+    assert!(attrs.is_read_only());
+    assert!(attrs.is_dont_delete());
+    assert!(!attrs.is_dont_enum());
+
+    // The lookup walks the prototype chain.
+    let inherited = v8::String::new(scope, "inherited").unwrap();
+    let attrs = obj
+      .get_property_attributes(scope, inherited.into())
+      .unwrap();
+    assert!(attrs.is_read_only());
+    assert!(attrs.is_dont_enum());
+    assert!(attrs.is_dont_delete());
+    assert!(!obj.has_own_property(scope, inherited.into()).unwrap());
+
+    let desc = obj
+      .get_own_property_descriptor(scope, fixed.into())
+      .unwrap();
+    let desc: v8::Local<v8::Object> = desc.try_into().unwrap();
+    let key = v8::String::new(scope, "configurable").unwrap();
+    let configurable = desc.get(scope, key.into()).unwrap();
+    assert!(configurable.is_false());
+    let key = v8::String::new(scope, "enumerable").unwrap();
+    let enumerable = desc.get(scope, key.into()).unwrap();
+    assert!(enumerable.is_true());
+    let key = v8::String::new(scope, "value").unwrap();
+    let value = desc.get(scope, key.into()).unwrap();
+    assert_eq!(value.int32_value(scope), Some(2));
+
+    let missing = v8::String::new(scope, "missing").unwrap();
+    let desc = obj.get_own_property_descriptor(scope, missing.into());
+    assert!(desc.unwrap().is_undefined());
+
+    let tag = obj.object_proto_to_string(scope).unwrap();
+    assert_eq!(tag.to_rust_string_lossy(scope), "[object Object]");
+    let array = v8::Array::new(scope, 0);
+    let tag = array.object_proto_to_string(scope).unwrap();
+    assert_eq!(tag.to_rust_string_lossy(scope), "[object Array]");
+  }
+}
+
+#[test]
+fn object_shallow_clone() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let obj: v8::Local<v8::Object> = eval(
+      scope,
+      "globalThis.getterCalls = 0;
+       globalThis.proto = { inherited: true };
+       globalThis.config = Object.create(proto);
+       config.name = 'base';
+       config.nested = { deep: 1 };
+       config[0] = 'zero';
+       config[Symbol('sym')] = 'symbol';
+       Object.defineProperty(config, 'hidden', { value: 1 });
+       Object.defineProperty(config, 'fixed', { value: 2, enumerable: true });
+       Object.defineProperty(config, 'computed', {
+         get() { getterCalls++; return 3; },
+         enumerable: true,
+       });
+       config",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    let copy = obj.shallow_clone(scope).unwrap();
+    assert!(!copy.strict_equals(obj.into()));
+    let global = context.global(scope);
+    let name = v8::String::new(scope, "copy").unwrap();
+    global.set(scope, name.into(), copy.into()).unwrap();
+
+    let check = |scope: &mut v8::HandleScope, code: &str| {
+      assert!(eval(scope, code).unwrap().is_true(), "{}", code);
+    };
+    check(scope, "Object.getPrototypeOf(copy) === proto");
+    check(scope, "copy.inherited === true");
+    check(scope, "Object.keys(copy).join() === '0,name,nested,fixed'");
+    check(scope, "copy[0] === 'zero' && copy.name === 'base'");
+    check(scope, "copy.nested === config.nested");
+    check(scope, "Object.getOwnPropertySymbols(copy).length === 0");
+    check(scope, "!('hidden' in copy) && !('computed' in copy)");
+    check(scope, "getterCalls === 0");
+    check(
+      scope,
+      "Object.getOwnPropertyDescriptor(copy, 'fixed').writable",
+    );
+
+    // Mutating the copy leaves the original untouched.
+    check(scope, "copy.name = 'request'; config.name === 'base'");
+
+    let obj: v8::Local<v8::Object> =
+      eval(scope, "Object.assign(Object.create(null), { a: 1 })")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let copy = obj.shallow_clone(scope).unwrap();
+    assert!(copy.get_prototype(scope).unwrap().is_null());
+    let key = v8::String::new(scope, "a").unwrap();
+    let value = copy.get(scope, key.into()).unwrap();
+    assert_eq!(value.int32_value(scope), Some(1));
+  }
+}
+
+#[test]
+fn object_has_real_property() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let obj: v8::Local<v8::Object> = eval(
+      scope,
+      "const p = { inherited: 1 };
+       const o = Object.create(p);
+       o.own = 2;
+       o[0] = 3;
+       Object.defineProperty(o, 'getter', { get() { return 4; } });
+       o",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    let own = v8::String::new(scope, "own").unwrap().into();
+    assert_eq!(obj.has_real_named_property(scope, own), Some(true));
+    assert_eq!(
+      obj.has_real_named_callback_property(scope, own),
+      Some(false)
+    );
+
+    let inherited = v8::String::new(scope, "inherited").unwrap().into();
+    assert_eq!(obj.has_real_named_property(scope, inherited), Some(false));
+
+    let getter = v8::String::new(scope, "getter").unwrap().into();
+    assert_eq!(obj.has_real_named_property(scope, getter), Some(true));
+    assert_eq!(
+      obj.has_real_named_callback_property(scope, getter),
+      Some(true)
+    );
+
+    assert_eq!(obj.has_real_indexed_property(scope, 0), Some(true));
+    assert_eq!(obj.has_real_indexed_property(scope, 1), Some(false));
+  }
+}
+
 #[test]
 fn object_set_accessor() {
   let _setup_guard = setup();
@@ -2035,6 +3477,99 @@ fn object_set_accessor_with_setter() {
   }
 }
 
+#[test]
+fn object_set_accessor_should_throw_on_error() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let getter = |_: &mut v8::HandleScope,
+                _: v8::Local<v8::Name>,
+                _: v8::PropertyCallbackArguments,
+                _: v8::ReturnValue| {};
+
+  // Emulates a read-only property: fail silently in sloppy mode, throw in
+  // strict mode.
+  let setter = |scope: &mut v8::HandleScope,
+                _: v8::Local<v8::Name>,
+                _: v8::Local<v8::Value>,
+                args: v8::PropertyCallbackArguments| {
+    if args.should_throw_on_error() {
+      let message = v8::String::new(scope, "read-only").unwrap();
+      let exception = v8::Exception::type_error(scope, message);
+      scope.throw_exception(exception);
+    }
+  };
+
+  let obj = v8::Object::new(scope);
+  let key = v8::String::new(scope, "key").unwrap();
+  obj.set_accessor_with_setter(scope, key.into(), getter, setter);
+
+  let obj_name = v8::String::new(scope, "obj").unwrap();
+  context
+    .global(scope)
+    .set(scope, obj_name.into(), obj.into());
+
+  let scope = &mut v8::TryCatch::new(scope);
+  assert!(eval(scope, "obj.key = 1").is_some());
+  assert!(!scope.has_caught());
+  assert!(eval(scope, "'use strict'; obj.key = 1").is_none());
+  assert!(scope.has_caught());
+}
+
+#[test]
+fn object_set_accessor_with_configuration() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let getter = |scope: &mut v8::HandleScope,
+                _: v8::Local<v8::Name>,
+                args: v8::PropertyCallbackArguments,
+                mut rv: v8::ReturnValue| {
+    let data = v8::Local::<v8::Object>::try_from(args.data()).unwrap();
+    let key = v8::String::new(scope, "value").unwrap();
+    rv.set(data.get(scope, key.into()).unwrap());
+  };
+
+  let setter = |scope: &mut v8::HandleScope,
+                _: v8::Local<v8::Name>,
+                value: v8::Local<v8::Value>,
+                args: v8::PropertyCallbackArguments| {
+    let data = v8::Local::<v8::Object>::try_from(args.data()).unwrap();
+    let key = v8::String::new(scope, "value").unwrap();
+    data.set(scope, key.into(), value).unwrap();
+  };
+
+  let data = eval(scope, "({ value: 1 })").unwrap();
+  let obj = v8::Object::new(scope);
+  let key = v8::String::new(scope, "key").unwrap();
+  assert!(obj
+    .set_accessor_with_configuration(
+      scope,
+      key.into(),
+      v8::AccessorConfiguration::new(getter)
+        .setter(setter)
+        .data(data)
+        .property_attribute(v8::DONT_ENUM),
+    )
+    .unwrap());
+
+  let obj_name = v8::String::new(scope, "obj").unwrap();
+  context
+    .global(scope)
+    .set(scope, obj_name.into(), obj.into());
+
+  let actual =
+    eval(scope, "obj.key = 2; [obj.key, Object.keys(obj).length]").unwrap();
+  let actual = actual.to_string(scope).unwrap();
+  assert_eq!(actual.to_rust_string_lossy(scope), "2,0");
+}
+
 #[test]
 fn promise_resolved() {
   let _setup_guard = setup();
@@ -2091,6 +3626,105 @@ fn promise_rejected() {
     assert_eq!(result.to_rust_string_lossy(scope), "test".to_string());
   }
 }
+
+#[test]
+fn promise_then_catch() {
+  fn on_fulfilled(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+  ) {
+    let value = args.get(0).number_value(scope).unwrap();
+    rv.set(v8::Number::new(scope, value * 2.0).into());
+  }
+
+  fn on_rejected(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+  ) {
+    let reason = args.get(0).to_rust_string_lossy(scope);
+    let result = v8::String::new(scope, &format!("recovered {}", reason));
+    rv.set(result.unwrap().into());
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let on_fulfilled = v8::Function::new(scope, on_fulfilled).unwrap();
+  let on_rejected = v8::Function::new(scope, on_rejected).unwrap();
+
+  let resolver = v8::PromiseResolver::new(scope).unwrap();
+  let promise = resolver.get_promise(scope);
+  let then = promise.then(scope, on_fulfilled).unwrap();
+  let then2 = promise.then2(scope, on_fulfilled, on_rejected).unwrap();
+  assert!(promise.has_handler());
+  let value = v8::Number::new(scope, 21.0);
+  resolver.resolve(scope, value.into()).unwrap();
+  // Handlers run as microtasks.
+  assert_eq!(then.state(), v8::PromiseState::Pending);
+  scope.perform_microtask_checkpoint();
+  assert_eq!(then.state(), v8::PromiseState::Fulfilled);
+  assert_eq!(then.result(scope).number_value(scope), Some(42.0));
+  assert_eq!(then2.state(), v8::PromiseState::Fulfilled);
+  assert_eq!(then2.result(scope).number_value(scope), Some(42.0));
+
+  let promise = v8::Local::<v8::Promise>::try_from(
+    eval(scope, "Promise.reject('oops')").unwrap(),
+  )
+  .unwrap();
+  let then = promise.then(scope, on_fulfilled).unwrap();
+  let catch = promise.catch(scope, on_rejected).unwrap();
+  let then2 = then.then2(scope, on_fulfilled, on_rejected).unwrap();
+  scope.perform_microtask_checkpoint();
+  assert_eq!(then.state(), v8::PromiseState::Rejected);
+  assert_eq!(catch.state(), v8::PromiseState::Fulfilled);
+  assert_eq!(
+    catch.result(scope).to_rust_string_lossy(scope),
+    "recovered oops"
+  );
+  assert_eq!(then2.state(), v8::PromiseState::Fulfilled);
+  assert_eq!(
+    then2.result(scope).to_rust_string_lossy(scope),
+    "recovered oops"
+  );
+}
+
+#[test]
+fn promise_mark_as_handled() {
+  static UNHANDLED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn promise_reject_callback(msg: v8::PromiseRejectMessage) {
+    if msg.get_event() == v8::PromiseRejectEvent::PromiseRejectWithNoHandler {
+      UNHANDLED_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_promise_reject_callback(promise_reject_callback);
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let resolver = v8::PromiseResolver::new(scope).unwrap();
+  let promise = resolver.get_promise(scope);
+  assert!(!promise.has_handler());
+  promise.mark_as_handled();
+  assert!(promise.has_handler());
+  let value = v8::String::new(scope, "handled").unwrap();
+  resolver.reject(scope, value.into()).unwrap();
+  assert_eq!(UNHANDLED_COUNT.load(Ordering::SeqCst), 0);
+
+  let resolver = v8::PromiseResolver::new(scope).unwrap();
+  let value = v8::String::new(scope, "unhandled").unwrap();
+  resolver.reject(scope, value.into()).unwrap();
+  assert_eq!(UNHANDLED_COUNT.load(Ordering::SeqCst), 1);
+}
+
 #[test]
 fn proxy() {
   let _setup_guard = setup();
@@ -2146,6 +3780,7 @@ fn fn_callback_new(
 ) {
   assert_eq!(args.length(), 0);
   assert!(args.new_target().is_object());
+  assert!(args.is_construct_call());
   let recv = args.this();
   let key = v8::String::new(scope, "works").unwrap();
   let value = v8::Boolean::new(scope, true);
@@ -2209,6 +3844,64 @@ fn nested_builder<'a>(
   .build(scope);
 }
 
+#[test]
+fn function_callback_arguments() {
+  fn describe_args(
+    scope: &mut v8::HandleScope,
+    mut args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+  ) {
+    let mut description = if args.is_construct_call() {
+      assert!(args.new_target().is_function());
+      "construct".to_owned()
+    } else {
+      assert!(args.new_target().is_undefined());
+      "call".to_owned()
+    };
+    description += &format!(" {}", args.length());
+    // Missing trailing arguments read as undefined.
+    for i in 0..3 {
+      description += &format!(" {}", args.get(i).to_rust_string_lossy(scope));
+    }
+    assert!(args.get(-1).is_undefined());
+    assert!(args.get(100).is_undefined());
+    let isolate_ptr = unsafe { args.get_isolate() } as *mut v8::Isolate;
+    let scope_isolate: &mut v8::Isolate = scope;
+    assert_eq!(isolate_ptr, scope_isolate as *mut v8::Isolate);
+    let result = v8::String::new(scope, &description).unwrap();
+    if args.is_construct_call() {
+      let key = v8::String::new(scope, "description").unwrap();
+      args.this().set(scope, key.into(), result.into());
+    } else {
+      rv.set(result.into());
+    }
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let function = v8::Function::new(scope, describe_args).unwrap();
+  let name = v8::String::new(scope, "describe").unwrap();
+  context
+    .global(scope)
+    .set(scope, name.into(), function.into());
+
+  let result = eval(scope, "describe(1)").unwrap();
+  assert_eq!(
+    result.to_rust_string_lossy(scope),
+    "call 1 1 undefined undefined"
+  );
+  let result = eval(scope, "describe(1, 2, 3, 4)").unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "call 4 1 2 3");
+  let result = eval(scope, "new describe('a', 'b').description").unwrap();
+  assert_eq!(
+    result.to_rust_string_lossy(scope),
+    "construct 2 a b undefined"
+  );
+}
+
 #[test]
 fn function() {
   let _setup_guard = setup();
@@ -2402,6 +4095,17 @@ fn constructor() {
       v8::Local::<v8::Function>::try_from(array_constructor).unwrap();
     let array = array_constructor.new_instance(scope, &[]).unwrap();
     v8::Local::<v8::Array>::try_from(array).unwrap();
+
+    let length = v8::Integer::new(scope, 3).into();
+    let array = array_constructor
+      .new_instance_with_side_effect_type(
+        scope,
+        &[length],
+        v8::SideEffectType::HasNoSideEffect,
+      )
+      .unwrap();
+    let array = v8::Local::<v8::Array>::try_from(array).unwrap();
+    assert_eq!(array.length(), 3);
   }
 }
 
@@ -2462,6 +4166,118 @@ fn promise_reject_callback_no_value() {
   }
 }
 
+#[test]
+fn microtasks_scope() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_microtasks_policy(v8::MicrotasksPolicy::Scoped);
+  assert_eq!(
+    isolate.get_microtasks_policy(),
+    v8::MicrotasksPolicy::Scoped
+  );
+
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  {
+    let scope = &mut v8::MicrotasksScope::new(
+      scope,
+      v8::MicrotasksScopeType::DoNotRunMicrotasks,
+    );
+    eval(
+      scope,
+      "globalThis.ran = false;
+       Promise.resolve().then(() => globalThis.ran = true);",
+    )
+    .unwrap();
+  }
+  {
+    // The microtask was not run when the previous scope exited.
+    let scope = &mut v8::MicrotasksScope::new(
+      scope,
+      v8::MicrotasksScopeType::DoNotRunMicrotasks,
+    );
+    assert!(eval(scope, "ran").unwrap().is_false());
+  }
+  {
+    let scope = &mut v8::MicrotasksScope::new(
+      scope,
+      v8::MicrotasksScopeType::RunMicrotasks,
+    );
+    {
+      // Nested scopes don't run microtasks either.
+      let scope = &mut v8::MicrotasksScope::new(
+        scope,
+        v8::MicrotasksScopeType::RunMicrotasks,
+      );
+      assert!(eval(scope, "ran").unwrap().is_false());
+    }
+    assert!(eval(scope, "ran").unwrap().is_false());
+  }
+  {
+    // The outermost `RunMicrotasks` scope ran the microtask when it exited.
+    let scope = &mut v8::MicrotasksScope::new(
+      scope,
+      v8::MicrotasksScopeType::DoNotRunMicrotasks,
+    );
+    assert!(eval(scope, "ran").unwrap().is_true());
+  }
+}
+
+#[test]
+fn microtask_checkpoint_exceptions() {
+  static REJECT_COUNT: AtomicUsize = AtomicUsize::new(0);
+  static MESSAGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn promise_reject_callback(msg: v8::PromiseRejectMessage) {
+    let scope = &mut unsafe { v8::CallbackScope::new(&msg) };
+    let scope = &mut v8::HandleScope::new(scope);
+    if msg.get_event() == v8::PromiseRejectEvent::PromiseRejectWithNoHandler {
+      let value = msg.get_value().unwrap();
+      assert_eq!(value.to_rust_string_lossy(scope), "Error: from then");
+      REJECT_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+  }
+
+  extern "C" fn message_callback(
+    message: v8::Local<v8::Message>,
+    _exception: v8::Local<v8::Value>,
+  ) {
+    let scope = &mut unsafe { v8::CallbackScope::new(message) };
+    let scope = &mut v8::HandleScope::new(scope);
+    let text = message.get(scope).to_rust_string_lossy(scope);
+    assert_eq!(text, "Uncaught Error: from microtask");
+    MESSAGE_COUNT.fetch_add(1, Ordering::SeqCst);
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+  isolate.set_promise_reject_callback(promise_reject_callback);
+  isolate.add_message_listener(message_callback);
+
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  eval(
+    scope,
+    "Promise.resolve().then(() => { throw new Error('from then') })",
+  )
+  .unwrap();
+  let function =
+    eval(scope, "() => { throw new Error('from microtask') }").unwrap();
+  let function = v8::Local::<v8::Function>::try_from(function).unwrap();
+  scope.enqueue_microtask(function);
+  assert_eq!(REJECT_COUNT.load(Ordering::SeqCst), 0);
+  assert_eq!(MESSAGE_COUNT.load(Ordering::SeqCst), 0);
+
+  scope.perform_microtask_checkpoint();
+  assert_eq!(REJECT_COUNT.load(Ordering::SeqCst), 1);
+  assert_eq!(MESSAGE_COUNT.load(Ordering::SeqCst), 1);
+}
+
 #[test]
 fn promise_hook() {
   extern "C" fn hook(
@@ -2952,6 +4768,19 @@ fn primitive_array() {
   }
 }
 
+#[test]
+#[should_panic(expected = "PrimitiveArray index out of bounds")]
+fn primitive_array_get_out_of_bounds() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let array = v8::PrimitiveArray::new(scope, 2);
+  array.get(scope, 2);
+}
+
 #[test]
 fn equality() {
   let _setup_guard = setup();
@@ -3146,8 +4975,40 @@ fn get_hash() {
     assert!(collision_count <= 2);
   }
 
-  // TODO: add tests for `External` and for types that are not derived from
-  // `v8::Value`, like `Module`, `Function/ObjectTemplate` etc.
+  // TODO: add tests for types that are not derived from `v8::Value`, like
+  // `Module`, `Function/ObjectTemplate` etc.
+}
+
+#[test]
+fn get_hash_is_stable() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let object = v8::Object::new(scope);
+  let external = v8::External::new(scope, std::ptr::null_mut());
+  let string = v8::String::new(scope, "interned?").unwrap();
+  let object_hash = object.get_hash();
+  let external_hash = external.get_hash();
+  let string_hash = string.get_hash();
+
+  // Objects (including externals) are hashed by identity...
+  assert_ne!(object_hash, v8::Object::new(scope).get_hash());
+  assert_ne!(
+    external_hash,
+    v8::External::new(scope, std::ptr::null_mut()).get_hash()
+  );
+  // ...and primitives by value.
+  let other_string = eval(scope, "'interned' + '?'").unwrap();
+  assert_eq!(string_hash, other_string.get_hash());
+
+  // Hashes survive garbage collections, which may move values in memory.
+  scope.low_memory_notification();
+  assert_eq!(object_hash, object.get_hash());
+  assert_eq!(external_hash, external.get_hash());
+  assert_eq!(string_hash, string.get_hash());
 }
 
 #[test]
@@ -3177,6 +5038,68 @@ fn array_buffer_view() {
   }
 }
 
+#[test]
+fn array_buffer_view_with_slice() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  fn invert(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _rv: v8::ReturnValue,
+  ) {
+    let view = v8::Local::<v8::ArrayBufferView>::try_from(args.get(0)).unwrap();
+    // SAFETY: the view is backed by a regular ArrayBuffer and its bytes are
+    // not accessed through any other path while they are borrowed.
+    unsafe {
+      view.with_slice(scope, |bytes| {
+        for byte in bytes {
+          *byte = !*byte;
+        }
+      });
+    }
+  }
+  let invert = v8::Function::new(scope, invert).unwrap();
+  let name = v8::String::new(scope, "invert").unwrap();
+  let global = context.global(scope);
+  global.set(scope, name.into(), invert.into()).unwrap();
+
+  let source = r#"
+    const a = new Uint8Array([0, 1, 254, 255]);
+    invert(a.subarray(1, 3));
+    a.join()
+  "#;
+  let result = eval(scope, source).unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "0,254,1,255");
+
+  let view = eval(scope, "a.subarray(2)").unwrap();
+  let view = v8::Local::<v8::ArrayBufferView>::try_from(view).unwrap();
+  let backing_store = view.get_backing_store(scope).unwrap();
+  let data = view.data(scope).unwrap();
+  assert_eq!(data.as_ptr() as *const u8, unsafe {
+    (backing_store.data().unwrap().as_ptr() as *const u8).add(2)
+  });
+  assert_eq!(unsafe { *data.as_ptr() }, 1);
+
+  // The safe cell view can be used alongside the backing store's own slice.
+  let sum = view.with_cell_slice(scope, |cells| {
+    cells[0].set(7);
+    assert_eq!(backing_store[2].get(), 7);
+    cells.iter().map(|cell| cell.get() as u32).sum::<u32>()
+  });
+  assert_eq!(sum, 7 + 255);
+  let result = eval(scope, "a.join()").unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "0,254,7,255");
+
+  view.buffer(scope).unwrap().detach();
+  assert!(view.data(scope).is_none());
+  assert_eq!(unsafe { view.with_slice(scope, |bytes| bytes.len()) }, 0);
+  assert_eq!(view.with_cell_slice(scope, |cells| cells.len()), 0);
+}
+
 #[test]
 fn snapshot_creator() {
   let _setup_guard = setup();
@@ -3255,6 +5178,52 @@ fn snapshot_creator() {
   }
 }
 
+#[test]
+fn snapshot_creator_from_existing_snapshot() {
+  let _setup_guard = setup();
+  let base_data = {
+    let mut snapshot_creator = v8::SnapshotCreator::new(None);
+    let mut isolate = unsafe { snapshot_creator.get_owned_isolate() };
+    {
+      let scope = &mut v8::HandleScope::new(&mut isolate);
+      let context = v8::Context::new(scope);
+      let scope = &mut v8::ContextScope::new(scope, context);
+      eval(scope, "a = 1 + 2").unwrap();
+      snapshot_creator.set_default_context(context);
+    }
+    std::mem::forget(isolate);
+    snapshot_creator
+      .create_blob(v8::FunctionCodeHandling::Clear)
+      .unwrap()
+  };
+  // Layer a second snapshot on top of the base snapshot.
+  let startup_data = {
+    let mut snapshot_creator =
+      v8::SnapshotCreator::from_existing_snapshot(base_data, None);
+    let mut isolate = unsafe { snapshot_creator.get_owned_isolate() };
+    {
+      let scope = &mut v8::HandleScope::new(&mut isolate);
+      let context = v8::Context::new(scope);
+      let scope = &mut v8::ContextScope::new(scope, context);
+      eval(scope, "b = a * 2").unwrap();
+      snapshot_creator.set_default_context(context);
+    }
+    std::mem::forget(isolate);
+    snapshot_creator
+      .create_blob(v8::FunctionCodeHandling::Clear)
+      .unwrap()
+  };
+  {
+    let params = v8::Isolate::create_params().snapshot_blob(startup_data);
+    let isolate = &mut v8::Isolate::new(params);
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let result = eval(scope, "a === 3 && b === 6").unwrap();
+    assert!(result.is_true());
+  }
+}
+
 #[test]
 fn external_references() {
   let _setup_guard = setup();
@@ -3445,6 +5414,35 @@ fn typed_array_constructors() {
   }
 }
 
+#[test]
+fn data_view() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let ab = v8::ArrayBuffer::new(scope, 8);
+  let view = v8::DataView::new(scope, ab, 2, 4).unwrap();
+  assert!(view.is_data_view());
+  assert!(!view.is_typed_array());
+  assert_eq!(view.byte_offset(), 2);
+  assert_eq!(view.byte_length(), 4);
+  assert_eq!(view.buffer(scope).unwrap(), ab);
+
+  let global = context.global(scope);
+  let name = v8::String::new(scope, "view").unwrap();
+  global.set(scope, name.into(), view.into()).unwrap();
+  eval(
+    scope,
+    "view.setUint16(0, 0x1234); view.setUint16(2, 0x1234, true)",
+  )
+  .unwrap();
+  let mut dest = [0; 4];
+  assert_eq!(view.copy_contents(&mut dest), 4);
+  assert_eq!(dest, [0x12, 0x34, 0x34, 0x12]);
+}
+
 #[test]
 fn dynamic_import() {
   let _setup_guard = setup();
@@ -3461,30 +5459,100 @@ fn dynamic_import() {
   ) -> *mut v8::Promise {
     let scope = &mut unsafe { v8::CallbackScope::new(context) };
     let scope = &mut v8::HandleScope::new(scope);
-    assert!(
-      specifier.strict_equals(v8::String::new(scope, "bar.js").unwrap().into())
+    assert!(
+      specifier.strict_equals(v8::String::new(scope, "bar.js").unwrap().into())
+    );
+    let e = v8::String::new(scope, "boom").unwrap();
+    scope.throw_exception(e.into());
+    CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+    std::ptr::null_mut()
+  }
+  isolate.set_host_import_module_dynamically_callback(dynamic_import_cb);
+
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let result = eval(
+      scope,
+      "(async function () {\n\
+         let x = await import('bar.js');\n\
+       })();",
+    );
+    assert!(result.is_some());
+    assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
+  }
+}
+
+#[test]
+fn dynamic_import_host_defined_options() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+
+  static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn dynamic_import_cb(
+    context: v8::Local<v8::Context>,
+    host_defined_options: v8::Local<v8::Data>,
+    resource_name: v8::Local<v8::Value>,
+    _specifier: v8::Local<v8::String>,
+    _import_assertions: v8::Local<v8::FixedArray>,
+  ) -> *mut v8::Promise {
+    let scope = &mut unsafe { v8::CallbackScope::new(context) };
+    let scope = &mut v8::HandleScope::new(scope);
+    assert_eq!(
+      resource_name.to_rust_string_lossy(scope),
+      "file:///base/main.js"
     );
-    let e = v8::String::new(scope, "boom").unwrap();
-    scope.throw_exception(e.into());
+    let options =
+      v8::Local::<v8::PrimitiveArray>::try_from(host_defined_options).unwrap();
+    assert_eq!(options.length(), 1);
+    let base_url = options.get(scope, 0);
+    assert_eq!(base_url.to_rust_string_lossy(scope), "file:///base/");
     CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+
+    let e = v8::String::new(scope, "not found").unwrap();
+    scope.throw_exception(e.into());
     std::ptr::null_mut()
   }
   isolate.set_host_import_module_dynamically_callback(dynamic_import_cb);
 
-  {
-    let scope = &mut v8::HandleScope::new(isolate);
-    let context = v8::Context::new(scope);
-    let scope = &mut v8::ContextScope::new(scope, context);
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
 
-    let result = eval(
-      scope,
-      "(async function () {\n\
-         let x = await import('bar.js');\n\
-       })();",
-    );
-    assert!(result.is_some());
-    assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
-  }
+  let options = v8::PrimitiveArray::new(scope, 1);
+  let base_url = v8::String::new(scope, "file:///base/").unwrap();
+  options.set(scope, 0, base_url.into());
+  let resource_name = v8::String::new(scope, "file:///base/main.js").unwrap();
+  let source_map_url = v8::undefined(scope);
+  let origin = v8::ScriptOrigin::with_host_defined_options(
+    scope,
+    resource_name.into(),
+    0,
+    0,
+    false,
+    0,
+    source_map_url.into(),
+    false,
+    false,
+    false,
+    Some(options.into()),
+  );
+  // The exception thrown by the callback is rethrown by `import()`, so wrap
+  // it in an async function to turn it into a rejected promise.
+  let source = v8::String::new(
+    scope,
+    "(async function () {\n\
+       await import('./dep.js');\n\
+     })();",
+  )
+  .unwrap();
+  let script = v8::Script::compile(scope, source, Some(&origin)).unwrap();
+  let result = script.run(scope).unwrap();
+  assert!(result.is_promise());
+  assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
 }
 
 #[test]
@@ -3896,6 +5964,152 @@ fn value_checker() {
   }
 }
 
+#[test]
+fn value_to_detail_string() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let value = eval(
+    scope,
+    "({ toString() { globalThis.called = true; return 'custom'; } })",
+  )
+  .unwrap();
+  assert_eq!(value.to_rust_detail_string_lossy(scope), "#<Object>");
+  assert!(eval(scope, "globalThis.called").unwrap().is_undefined());
+  assert_eq!(value.to_rust_string_lossy(scope), "custom");
+  assert!(eval(scope, "globalThis.called").unwrap().is_true());
+
+  let value = eval(scope, "Symbol('foo')").unwrap();
+  assert_eq!(value.to_rust_detail_string_lossy(scope), "Symbol(foo)");
+
+  let value = eval(scope, "'bar'").unwrap();
+  assert_eq!(value.to_rust_detail_string_lossy(scope), "bar");
+}
+
+#[test]
+fn value_checker_exact_types() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  type Checker = fn(&v8::Value) -> bool;
+  let checkers: &[(&str, Checker)] = &[
+    ("is_big_int", v8::Value::is_big_int),
+    ("is_symbol", v8::Value::is_symbol),
+    ("is_promise", v8::Value::is_promise),
+    ("is_map", v8::Value::is_map),
+    ("is_set", v8::Value::is_set),
+    ("is_weak_map", v8::Value::is_weak_map),
+    ("is_weak_set", v8::Value::is_weak_set),
+    ("is_proxy", v8::Value::is_proxy),
+    ("is_data_view", v8::Value::is_data_view),
+    ("is_shared_array_buffer", v8::Value::is_shared_array_buffer),
+    ("is_uint8_array", v8::Value::is_uint8_array),
+    ("is_uint8_clamped_array", v8::Value::is_uint8_clamped_array),
+    ("is_int8_array", v8::Value::is_int8_array),
+    ("is_uint16_array", v8::Value::is_uint16_array),
+    ("is_int16_array", v8::Value::is_int16_array),
+    ("is_uint32_array", v8::Value::is_uint32_array),
+    ("is_int32_array", v8::Value::is_int32_array),
+    ("is_float32_array", v8::Value::is_float32_array),
+    ("is_float64_array", v8::Value::is_float64_array),
+    ("is_big_int64_array", v8::Value::is_big_int64_array),
+    ("is_big_uint64_array", v8::Value::is_big_uint64_array),
+    ("is_date", v8::Value::is_date),
+    ("is_reg_exp", v8::Value::is_reg_exp),
+    ("is_async_function", v8::Value::is_async_function),
+    ("is_generator_function", v8::Value::is_generator_function),
+    ("is_generator_object", v8::Value::is_generator_object),
+  ];
+  let cases = [
+    ("1n", "is_big_int"),
+    ("Symbol()", "is_symbol"),
+    ("Promise.resolve()", "is_promise"),
+    ("new Map()", "is_map"),
+    ("new Set()", "is_set"),
+    ("new WeakMap()", "is_weak_map"),
+    ("new WeakSet()", "is_weak_set"),
+    ("new Proxy({}, {})", "is_proxy"),
+    ("new DataView(new ArrayBuffer(1))", "is_data_view"),
+    ("new SharedArrayBuffer(1)", "is_shared_array_buffer"),
+    ("new Uint8Array()", "is_uint8_array"),
+    ("new Uint8ClampedArray()", "is_uint8_clamped_array"),
+    ("new Int8Array()", "is_int8_array"),
+    ("new Uint16Array()", "is_uint16_array"),
+    ("new Int16Array()", "is_int16_array"),
+    ("new Uint32Array()", "is_uint32_array"),
+    ("new Int32Array()", "is_int32_array"),
+    ("new Float32Array()", "is_float32_array"),
+    ("new Float64Array()", "is_float64_array"),
+    ("new BigInt64Array()", "is_big_int64_array"),
+    ("new BigUint64Array()", "is_big_uint64_array"),
+    ("new Date()", "is_date"),
+    ("/a/", "is_reg_exp"),
+    ("(async () => {})", "is_async_function"),
+    ("(function* () {})", "is_generator_function"),
+    ("(function* () {})()", "is_generator_object"),
+  ];
+
+  for (source, expected) in cases {
+    let value = eval(scope, source).unwrap();
+    for (name, checker) in checkers {
+      assert_eq!(
+        checker(&value),
+        *name == expected,
+        "{}() for `{}`",
+        name,
+        source
+      );
+    }
+    if expected.ends_with("_array") {
+      assert!(value.is_typed_array());
+      assert!(value.is_array_buffer_view());
+    } else {
+      assert!(!value.is_typed_array());
+    }
+  }
+
+  let value = eval(scope, "new DataView(new ArrayBuffer(1))").unwrap();
+  assert!(value.is_array_buffer_view());
+  assert!(!value.is_array_buffer());
+}
+
+#[test]
+fn value_extractors_with_throwing_value_of() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let value = eval(scope, "({ valueOf() { return -2.5 } })").unwrap();
+  assert_eq!(value.number_value(scope), Some(-2.5));
+  assert_eq!(value.integer_value(scope), Some(-2));
+  assert_eq!(value.int32_value(scope), Some(-2));
+  assert_eq!(value.uint32_value(scope), Some(u32::MAX - 1));
+  assert!(value.boolean_value(scope));
+
+  let scope = &mut v8::TryCatch::new(scope);
+  let value =
+    eval(scope, "({ valueOf() { throw new Error('nope') } })").unwrap();
+  assert_eq!(value.number_value(scope), None);
+  assert!(scope.has_caught());
+  scope.reset();
+  assert_eq!(value.integer_value(scope), None);
+  assert_eq!(value.int32_value(scope), None);
+  assert_eq!(value.uint32_value(scope), None);
+  assert!(scope.has_caught());
+  // Conversion to boolean never calls into JavaScript.
+  scope.reset();
+  assert!(value.boolean_value(scope));
+  assert!(!scope.has_caught());
+}
+
 #[test]
 fn try_from_data() {
   let _setup_guard = setup();
@@ -4079,6 +6293,35 @@ fn try_from_value() {
           if expected == type_name::<v8::Set>()
       ));
     }
+
+    // The error reports the runtime type of the value, not its static type.
+    {
+      let value: v8::Local<v8::Value> = v8::Number::new(scope, 1.5).into();
+      assert!(matches!(
+        v8::Local::<v8::Object>::try_from(value),
+        Err(v8::DataError::BadType { expected, actual })
+          if expected == type_name::<v8::Object>()
+            && actual == type_name::<v8::Number>()
+      ));
+      let value: v8::Local<v8::Value> = eval(scope, "[]").unwrap();
+      let object = v8::Local::<v8::Object>::try_from(value).unwrap();
+      assert!(matches!(
+        v8::Local::<v8::Function>::try_from(object),
+        Err(v8::DataError::BadType { expected, actual })
+          if expected == type_name::<v8::Function>()
+            && actual == type_name::<v8::Array>()
+      ));
+      let value: v8::Local<v8::Value> = v8::null(scope).into();
+      let err = v8::Local::<v8::String>::try_from(value).unwrap_err();
+      assert_eq!(
+        err.to_string(),
+        format!(
+          "expected type `{}`, got `{}`",
+          type_name::<v8::String>(),
+          type_name::<v8::Primitive>()
+        )
+      );
+    }
   }
 }
 
@@ -4305,97 +6548,403 @@ fn inspector_schedule_pause_on_next_statement() {
   assert_eq!(client.count_quit_message_loop_on_pause, 0);
   assert_eq!(client.count_run_if_waiting_for_debugger, 0);
 
-  let r = eval(scope, "1+2").unwrap();
-  assert!(r.is_number());
+  let r = eval(scope, "1+2").unwrap();
+  assert!(r.is_number());
+
+  assert_eq!(channel.count_send_response, 1);
+  assert_eq!(channel.count_send_notification, 3);
+  assert_eq!(channel.count_flush_protocol_notifications, 1);
+  assert_eq!(client.count_run_message_loop_on_pause, 1);
+  assert_eq!(client.count_quit_message_loop_on_pause, 0);
+  assert_eq!(client.count_run_if_waiting_for_debugger, 0);
+  assert_ne!(client.count_generate_unique_id, 0);
+}
+
+#[test]
+fn inspector_console_api_message() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+
+  use v8::inspector::*;
+
+  struct Client {
+    base: V8InspectorClientBase,
+    messages: Vec<String>,
+  }
+
+  impl Client {
+    fn new() -> Self {
+      Self {
+        base: V8InspectorClientBase::new::<Self>(),
+        messages: Vec::new(),
+      }
+    }
+  }
+
+  impl V8InspectorClientImpl for Client {
+    fn base(&self) -> &V8InspectorClientBase {
+      &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut V8InspectorClientBase {
+      &mut self.base
+    }
+
+    fn console_api_message(
+      &mut self,
+      _context_group_id: i32,
+      _level: i32,
+      message: &StringView,
+      _url: &StringView,
+      _line_number: u32,
+      _column_number: u32,
+      _stack_trace: &mut V8StackTrace,
+    ) {
+      self.messages.push(message.to_string());
+    }
+  }
+
+  let mut client = Client::new();
+  let mut inspector = V8Inspector::create(isolate, &mut client);
+
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let name = b"";
+  let name_view = StringView::from(&name[..]);
+  inspector.context_created(context, 1, name_view);
+
+  let source = r#"
+    console.log("one");
+    console.error("two");
+    console.trace("three");
+  "#;
+  let _ = eval(scope, source).unwrap();
+  assert_eq!(client.messages, vec!["one", "two", "three"]);
+}
+
+#[test]
+fn context_from_object_template() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let object_templ = v8::ObjectTemplate::new(scope);
+    let function_templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
+    let name = v8::String::new(scope, "f").unwrap();
+    object_templ.set(name.into(), function_templ.into());
+    let context = v8::Context::new_from_template(scope, object_templ);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let actual = eval(scope, "f()").unwrap();
+    let expected = v8::Integer::new(scope, 42);
+    assert!(expected.strict_equals(actual));
+  }
+}
+
+#[test]
+fn context_with_options() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context =
+      v8::Context::new_with_options(scope, v8::ContextOptions::default());
+    let scope = &mut v8::ContextScope::new(scope, context);
+    assert!(eval(scope, "typeof f")
+      .unwrap()
+      .strict_equals(v8::String::new(scope, "undefined").unwrap().into()));
+  }
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let object_templ = v8::ObjectTemplate::new(scope);
+    let function_templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
+    let name = v8::String::new(scope, "f").unwrap();
+    object_templ.set(name.into(), function_templ.into());
+    let context = v8::Context::new_with_options(
+      scope,
+      v8::ContextOptions {
+        global_template: Some(object_templ),
+        ..Default::default()
+      },
+    );
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let actual = eval(scope, "f()").unwrap();
+    let expected = v8::Integer::new(scope, 42);
+    assert!(expected.strict_equals(actual));
+  }
+}
+
+#[test]
+fn context_detach_global() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context1 = v8::Context::new(scope);
+    let global = context1.global(scope);
+    {
+      let scope = &mut v8::ContextScope::new(scope, context1);
+      eval(scope, "var leaked = 'secret'; Array.prototype.tainted = 1")
+        .unwrap();
+    }
+
+    context1.detach_global();
+    let context2 = v8::Context::new_with_options(
+      scope,
+      v8::ContextOptions {
+        global_object: Some(global.into()),
+        ..Default::default()
+      },
+    );
+    assert_eq!(context2.global(scope), global);
+    let scope = &mut v8::ContextScope::new(scope, context2);
+    let result = eval(
+      scope,
+      "typeof leaked === 'undefined' && [].tainted === undefined",
+    )
+    .unwrap();
+    assert!(result.is_true());
+  }
+}
+
+#[test]
+fn context_extras_binding_object() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let extras = context.get_extras_binding_object(scope);
+    assert!(
+      extras.strict_equals(context.get_extras_binding_object(scope).into())
+    );
+    assert!(!extras.strict_equals(context.global(scope).into()));
+
+    let name = v8::String::new(scope, "privileged").unwrap();
+    let value = v8::Integer::new(scope, 42).into();
+    extras.set(scope, name.into(), value).unwrap();
+    assert!(extras.get(scope, name.into()).unwrap().strict_equals(value));
+    assert!(eval(scope, "typeof privileged === 'undefined'")
+      .unwrap()
+      .is_true());
+  }
+}
+
+#[test]
+fn context_security_token() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context1 = v8::Context::new(scope);
+    let context2 = v8::Context::new(scope);
+
+    let token = v8::String::new(scope, "token").unwrap();
+    context1.set_security_token(token.into());
+    context2.set_security_token(token.into());
+    assert!(context1
+      .get_security_token(scope)
+      .strict_equals(token.into()));
 
-  assert_eq!(channel.count_send_response, 1);
-  assert_eq!(channel.count_send_notification, 3);
-  assert_eq!(channel.count_flush_protocol_notifications, 1);
-  assert_eq!(client.count_run_message_loop_on_pause, 1);
-  assert_eq!(client.count_quit_message_loop_on_pause, 0);
-  assert_eq!(client.count_run_if_waiting_for_debugger, 0);
-  assert_ne!(client.count_generate_unique_id, 0);
+    let global2 = context2.global(scope);
+    {
+      let scope = &mut v8::ContextScope::new(scope, context1);
+      let name = v8::String::new(scope, "other").unwrap();
+      context1
+        .global(scope)
+        .set(scope, name.into(), global2.into());
+      eval(scope, "other.x = 42").unwrap();
+    }
+    {
+      let scope = &mut v8::ContextScope::new(scope, context2);
+      let x = eval(scope, "x").unwrap();
+      assert_eq!(x.int32_value(scope), Some(42));
+    }
+
+    context2.use_default_security_token();
+    assert!(!context2
+      .get_security_token(scope)
+      .strict_equals(token.into()));
+  }
+}
+
+/// Installs a global `current()` function that returns the continuation
+/// preserved embedder data of the current context, and an empty global
+/// `result` array to collect what it returns.
+fn install_current_embedder_data(
+  scope: &mut v8::HandleScope,
+  context: v8::Local<v8::Context>,
+) {
+  let current = v8::Function::new(
+    scope,
+    |scope: &mut v8::HandleScope,
+     _: v8::FunctionCallbackArguments,
+     mut rv: v8::ReturnValue| {
+      let context = scope.get_current_context();
+      rv.set(context.get_continuation_preserved_embedder_data(scope));
+    },
+  )
+  .unwrap();
+  let name = v8::String::new(scope, "current").unwrap();
+  context
+    .global(scope)
+    .set(scope, name.into(), current.into())
+    .unwrap();
+  eval(scope, "result = []").unwrap();
 }
 
 #[test]
-fn inspector_console_api_message() {
+fn context_continuation_preserved_embedder_data() {
   let _setup_guard = setup();
   let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
 
-  use v8::inspector::*;
+    assert!(context
+      .get_continuation_preserved_embedder_data(scope)
+      .is_undefined());
 
-  struct Client {
-    base: V8InspectorClientBase,
-    messages: Vec<String>,
-  }
+    install_current_embedder_data(scope, context);
 
-  impl Client {
-    fn new() -> Self {
-      Self {
-        base: V8InspectorClientBase::new::<Self>(),
-        messages: Vec::new(),
-      }
-    }
+    let first = v8::String::new(scope, "first").unwrap();
+    context.set_continuation_preserved_embedder_data(first.into());
+    assert!(context
+      .get_continuation_preserved_embedder_data(scope)
+      .strict_equals(first.into()));
+    eval(
+      scope,
+      r#"
+      (async () => {
+        result.push(current());
+        await null;
+        result.push(current());
+      })();
+      Promise.resolve().then(() => result.push(current()));
+    "#,
+    )
+    .unwrap();
+
+    // Reactions scheduled above keep seeing the data that was set when they
+    // were scheduled.
+    let second = v8::String::new(scope, "second").unwrap();
+    context.set_continuation_preserved_embedder_data(second.into());
+    scope.perform_microtask_checkpoint();
+    let result = eval(scope, "result.join()").unwrap();
+    assert_eq!(result.to_rust_string_lossy(scope), "first,first,first");
   }
+}
 
-  impl V8InspectorClientImpl for Client {
-    fn base(&self) -> &V8InspectorClientBase {
-      &self.base
-    }
+#[test]
+fn continuation_preserved_embedder_data_scope() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
 
-    fn base_mut(&mut self) -> &mut V8InspectorClientBase {
-      &mut self.base
-    }
+    install_current_embedder_data(scope, context);
 
-    fn console_api_message(
-      &mut self,
-      _context_group_id: i32,
-      _level: i32,
-      message: &StringView,
-      _url: &StringView,
-      _line_number: u32,
-      _column_number: u32,
-      _stack_trace: &mut V8StackTrace,
-    ) {
-      self.messages.push(message.to_string());
+    let outer = v8::String::new(scope, "outer").unwrap();
+    context.set_continuation_preserved_embedder_data(outer.into());
+    {
+      let store = v8::String::new(scope, "store").unwrap();
+      let scope = &mut v8::ContinuationPreservedEmbedderDataScope::new(
+        scope,
+        context,
+        store.into(),
+      );
+      eval(
+        scope,
+        r#"
+        result.push(current());
+        Promise.resolve().then(() => result.push(current()));
+      "#,
+      )
+      .unwrap();
     }
-  }
-
-  let mut client = Client::new();
-  let mut inspector = V8Inspector::create(isolate, &mut client);
+    assert!(context
+      .get_continuation_preserved_embedder_data(scope)
+      .strict_equals(outer.into()));
+    eval(scope, "result.push(current())").unwrap();
 
-  let scope = &mut v8::HandleScope::new(isolate);
-  let context = v8::Context::new(scope);
-  let scope = &mut v8::ContextScope::new(scope, context);
+    scope.perform_microtask_checkpoint();
+    let result = eval(scope, "result.join()").unwrap();
+    assert_eq!(result.to_rust_string_lossy(scope), "store,outer,store");
+  }
+}
 
-  let name = b"";
-  let name_view = StringView::from(&name[..]);
-  inspector.context_created(context, 1, name_view);
+#[test]
+fn context_allow_code_generation_from_strings() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    assert!(context.is_code_generation_from_strings_allowed());
+    assert!(eval(scope, "eval('1 + 1')").is_some());
 
-  let source = r#"
-    console.log("one");
-    console.error("two");
-    console.trace("three");
-  "#;
-  let _ = eval(scope, source).unwrap();
-  assert_eq!(client.messages, vec!["one", "two", "three"]);
+    context.allow_code_generation_from_strings(false);
+    assert!(!context.is_code_generation_from_strings_allowed());
+    let scope = &mut v8::TryCatch::new(scope);
+    assert!(eval(scope, "eval('1 + 1')").is_none());
+    assert!(scope.has_caught());
+    scope.reset();
+    assert!(eval(scope, "new Function('return 1')").is_none());
+    assert!(scope.has_caught());
+  }
 }
 
 #[test]
-fn context_from_object_template() {
+fn modify_code_generation_from_strings_callback() {
+  fn callback<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    source: v8::Local<'s, v8::Value>,
+    _is_code_like: bool,
+  ) -> v8::ModifyCodeGenerationFromStringsResult<'s> {
+    let source = source.to_rust_string_lossy(scope);
+    if source == "rewrite()" {
+      v8::ModifyCodeGenerationFromStringsResult {
+        codegen_allowed: true,
+        modified_source: v8::String::new(scope, "40 + 2"),
+      }
+    } else {
+      v8::ModifyCodeGenerationFromStringsResult {
+        codegen_allowed: source.contains("/* signed */"),
+        modified_source: None,
+      }
+    }
+  }
+
   let _setup_guard = setup();
   let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_modify_code_generation_from_strings_callback(callback);
   {
     let scope = &mut v8::HandleScope::new(isolate);
-    let object_templ = v8::ObjectTemplate::new(scope);
-    let function_templ = v8::FunctionTemplate::new(scope, fortytwo_callback);
-    let name = v8::String::new(scope, "f").unwrap();
-    object_templ.set(name.into(), function_templ.into());
-    let context = v8::Context::new_from_template(scope, object_templ);
+    let context = v8::Context::new(scope);
     let scope = &mut v8::ContextScope::new(scope, context);
-    let actual = eval(scope, "f()").unwrap();
-    let expected = v8::Integer::new(scope, 42);
-    assert!(expected.strict_equals(actual));
+    context.allow_code_generation_from_strings(false);
+
+    let result = eval(scope, "eval('/* signed */ 1 + 1')").unwrap();
+    assert_eq!(result.int32_value(scope), Some(2));
+    let result = eval(scope, "eval('rewrite()')").unwrap();
+    assert_eq!(result.int32_value(scope), Some(42));
+    let result = eval(scope, "new Function('/* signed */ return 7')()");
+    assert_eq!(result.unwrap().int32_value(scope), Some(7));
+
+    let scope = &mut v8::TryCatch::new(scope);
+    assert!(eval(scope, "eval('1 + 1')").is_none());
+    let exception = scope.exception().unwrap();
+    let exception = exception.to_rust_string_lossy(scope);
+    assert!(exception.starts_with("EvalError"));
   }
 }
 
@@ -4425,6 +6974,36 @@ fn take_heap_snapshot() {
   }
 }
 
+#[test]
+fn heap_object_id() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let smi = v8::Integer::new(scope, 42).into();
+    assert_eq!(scope.get_heap_object_id(smi), None);
+
+    let kept = eval(scope, "globalThis.kept = { kept: true }").unwrap();
+    let dropped_id = {
+      let scope = &mut v8::HandleScope::new(scope);
+      let dropped = eval(scope, "({ dropped: true })").unwrap();
+      scope.take_heap_snapshot(|_| true);
+      scope.get_heap_object_id(dropped).unwrap()
+    };
+
+    let kept_id = scope.get_heap_object_id(kept).unwrap();
+    assert_ne!(kept_id, dropped_id);
+    assert_eq!(scope.get_heap_object_id(kept), Some(kept_id));
+    let found = scope.find_heap_object_by_id(kept_id).unwrap();
+    assert!(found.strict_equals(kept));
+
+    assert!(scope.find_heap_object_by_id(dropped_id).is_none());
+  }
+}
+
 #[test]
 fn test_prototype_api() {
   let _setup_guard = setup();
@@ -4732,6 +7311,12 @@ fn heap_statistics() {
   assert_ne!(s.used_global_handles_size(), 0);
   assert_ne!(s.total_global_handles_size(), 0);
   assert_ne!(s.number_of_native_contexts(), 0);
+
+  // Keeping a large array alive grows the used heap size.
+  let used_before = s.used_heap_size();
+  eval(scope, "globalThis.big = new Array(1e6).fill(1.5)").unwrap();
+  scope.get_heap_statistics(&mut s);
+  assert!(s.used_heap_size() > used_before);
 }
 
 #[test]
@@ -4740,6 +7325,73 @@ fn low_memory_notification() {
   isolate.low_memory_notification();
 }
 
+#[test]
+fn external_memory() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+
+  let base = isolate.get_external_memory();
+  let total = isolate.adjust_amount_of_external_allocated_memory(1024);
+  assert_eq!(total, base + 1024);
+  assert_eq!(isolate.get_external_memory(), base + 1024);
+  assert_eq!(isolate.get_external_memory(), base + 1024);
+
+  isolate.adjust_amount_of_external_allocated_memory(-1024);
+  assert_eq!(isolate.get_external_memory(), base);
+}
+
+#[test]
+fn install_conditional_features() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  // SharedArrayBuffer is installed conditionally. Once it has been removed
+  // from the global, installing the conditional features brings it back.
+  let original = eval(scope, "globalThis.SharedArrayBuffer").unwrap();
+  assert!(original.is_function());
+  eval(scope, "delete globalThis.SharedArrayBuffer").unwrap();
+  assert!(eval(scope, "typeof SharedArrayBuffer === 'undefined'")
+    .unwrap()
+    .is_true());
+
+  scope.install_conditional_features(context);
+  let restored = eval(scope, "globalThis.SharedArrayBuffer").unwrap();
+  assert!(restored.strict_equals(original));
+
+  // Features that are already present are left alone.
+  scope.install_conditional_features(context);
+  let again = eval(scope, "globalThis.SharedArrayBuffer").unwrap();
+  assert!(again.strict_equals(original));
+}
+
+#[test]
+fn date_time_configuration_change_notification() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let offset = eval(scope, "new Date(0).getTimezoneOffset()").unwrap();
+  let offset = offset.int32_value(scope).unwrap();
+
+  // Nothing changed on the host, so both variants must leave the offset as
+  // it was while still allowing Date to be used afterwards.
+  scope
+    .date_time_configuration_change_notification(v8::TimeZoneDetection::Skip);
+  let result = eval(scope, "new Date(0).getTimezoneOffset()").unwrap();
+  assert_eq!(result.int32_value(scope).unwrap(), offset);
+
+  scope.date_time_configuration_change_notification(
+    v8::TimeZoneDetection::Redetect,
+  );
+  let result = eval(scope, "new Date(0).getTimezoneOffset()").unwrap();
+  assert_eq!(result.int32_value(scope).unwrap(), offset);
+}
+
 // Clippy thinks the return value doesn't need to be an Option, it's unaware
 // of the mapping that MapFnFrom<F> does for ResolveModuleCallback.
 #[allow(clippy::unnecessary_wraps)]
@@ -4875,6 +7527,38 @@ fn symbol() {
   assert!(s == v8::Symbol::get_async_iterator(scope));
 }
 
+#[test]
+fn name() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let string: v8::Local<v8::Name> = v8::String::new(scope, "s").unwrap().into();
+  let symbol: v8::Local<v8::Name> = v8::Symbol::new(scope, None).into();
+  assert!(string.is_string());
+  assert!(symbol.is_symbol());
+
+  let number: v8::Local<v8::Value> = v8::Number::new(scope, 1.0).into();
+  assert!(v8::Local::<v8::Name>::try_from(number).is_err());
+  let value: v8::Local<v8::Value> = symbol.into();
+  let name = v8::Local::<v8::Name>::try_from(value).unwrap();
+  assert!(name == symbol);
+  assert!(v8::Local::<v8::Symbol>::try_from(name).is_ok());
+  assert!(v8::Local::<v8::String>::try_from(name).is_err());
+
+  let object = v8::Object::new(scope);
+  let one = v8::Integer::new(scope, 1).into();
+  for key in [string, symbol] {
+    assert!(!object.has_own_property(scope, key).unwrap());
+    assert!(object
+      .define_own_property(scope, key, one, v8::NONE)
+      .unwrap());
+    assert!(object.has_own_property(scope, key).unwrap());
+  }
+}
+
 #[test]
 fn private() {
   let _setup_guard = setup();
@@ -4917,6 +7601,47 @@ fn private() {
   assert!(object.get_private(scope, p).unwrap().is_undefined());
 }
 
+#[test]
+fn private_is_hidden_from_scripts() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let name = v8::String::new(scope, "Framework#wrapped").unwrap();
+  let marker = v8::Private::for_api(scope, Some(name));
+  let object = eval(scope, "globalThis.obj = { visible: 1 }; obj").unwrap();
+  let object = v8::Local::<v8::Object>::try_from(object).unwrap();
+  let value = v8::Boolean::new(scope, true).into();
+  assert!(object.set_private(scope, marker, value).unwrap());
+
+  let names = object.get_own_property_names(scope).unwrap();
+  assert_eq!(names.length(), 1);
+  for script in [
+    "Object.keys(obj).join()",
+    "Object.getOwnPropertyNames(obj).join()",
+    "Reflect.ownKeys(obj).map(String).join()",
+    "JSON.stringify(obj)",
+    "Object.getOwnPropertySymbols(obj).length",
+    "Object.entries(Object.getOwnPropertyDescriptors(obj)).length",
+  ] {
+    let result = eval(scope, script).unwrap();
+    assert!(!result.to_rust_string_lossy(scope).contains("wrapped"));
+  }
+  let result = eval(scope, "Object.getOwnPropertySymbols(obj).length").unwrap();
+  assert_eq!(result.int32_value(scope), Some(0));
+
+  // Scripts can't remove the marker either.
+  eval(
+    scope,
+    "for (const key of Reflect.ownKeys(obj)) delete obj[key]",
+  )
+  .unwrap();
+  assert!(object.has_private(scope, marker).unwrap());
+  assert!(object.get_private(scope, marker).unwrap().is_true());
+}
+
 #[test]
 fn bigint() {
   let _setup_guard = setup();
@@ -4935,28 +7660,128 @@ fn bigint() {
   let b = v8::BigInt::new_from_words(scope, false, &words).unwrap();
   assert_eq!(b.i64_value(), (10, false));
 
-  let raw_b = eval(scope, "184467440737095516170n").unwrap();
-  assert!(b == raw_b);
+  let raw_b = eval(scope, "184467440737095516170n").unwrap();
+  assert!(b == raw_b);
+
+  let b = v8::BigInt::new_from_words(scope, true, &words).unwrap();
+  assert_eq!(b.i64_value(), (-10, false));
+
+  let raw_b = eval(scope, "-184467440737095516170n").unwrap();
+  assert!(b == raw_b);
+
+  let raw_b = v8::Local::<v8::BigInt>::try_from(raw_b).unwrap();
+
+  let mut vec = Vec::new();
+  vec.resize(raw_b.word_count(), 0);
+  assert_eq!(raw_b.to_words_array(&mut vec), (true, &mut [10, 10][..]));
+
+  let mut vec = Vec::new();
+  vec.resize(1, 0);
+  assert_eq!(raw_b.to_words_array(&mut vec), (true, &mut [10][..]));
+
+  let mut vec = Vec::new();
+  vec.resize(20, 1337);
+  assert_eq!(raw_b.to_words_array(&mut vec), (true, &mut [10, 10][..]));
+}
+
+#[test]
+fn value_ext() {
+  use v8::ValueExt;
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  fn sum_lengths(
+    scope: &mut v8::HandleScope,
+    value: v8::Local<v8::Value>,
+  ) -> Result<usize, v8::DataError> {
+    let array = value.as_array()?;
+    let mut total = 0;
+    for i in 0..array.length() {
+      let item = array.get_index(scope, i).unwrap();
+      total += item.to_rust_string(scope)?.len();
+    }
+    Ok(total)
+  }
+
+  let v = eval(scope, "['a', 'bc', 'def']").unwrap();
+  assert_eq!(sum_lengths(scope, v).unwrap(), 6);
+  let v = eval(scope, "['a', 2]").unwrap();
+  let err = sum_lengths(scope, v).unwrap_err();
+  assert!(matches!(
+    err,
+    v8::DataError::BadType { expected, actual }
+      if expected.ends_with("String") && actual.ends_with("Number")
+  ));
+
+  let v = eval(scope, "1.5").unwrap();
+  assert_eq!(v.as_f64().unwrap(), 1.5);
+  assert!(v.as_i64().is_err());
+  assert!(v.to_rust_string(scope).is_err());
+  let v = eval(scope, "-7").unwrap();
+  assert_eq!(v.as_i64().unwrap(), -7);
+  let v = eval(scope, "2n ** 40n").unwrap();
+  assert_eq!(v.as_i64().unwrap(), 1 << 40);
+  assert!(v.as_f64().is_err());
+  let v = eval(scope, "2n ** 64n").unwrap();
+  assert!(v.as_i64().is_err());
+
+  let v = eval(scope, "true").unwrap();
+  assert!(v.as_bool().unwrap());
+  // No coercion takes place.
+  let v = eval(scope, "1").unwrap();
+  assert!(v.as_bool().is_err());
+  let v = eval(scope, "'1'").unwrap();
+  assert!(v.as_f64().is_err());
+
+  let v = eval(scope, "({})").unwrap();
+  assert!(v.as_object().is_ok());
+  assert!(v.as_function().is_err());
+  let v = eval(scope, "(() => {})").unwrap();
+  assert!(v.as_object().is_ok());
+  assert!(v.as_function().is_ok());
+  let v = eval(scope, "null").unwrap();
+  let err = v.as_object().unwrap_err();
+  assert!(err.to_string().contains("Primitive"));
+}
+
+#[test]
+fn value_int64_value() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let v = eval(scope, "-42").unwrap();
+  assert_eq!(v.int64_value(scope), Some((-42, true)));
+  assert!(!v.uint64_value(scope).unwrap().1);
 
-  let b = v8::BigInt::new_from_words(scope, true, &words).unwrap();
-  assert_eq!(b.i64_value(), (-10, false));
+  let v = eval(scope, "-42n").unwrap();
+  assert_eq!(v.int64_value(scope), Some((-42, true)));
+  assert!(!v.uint64_value(scope).unwrap().1);
 
-  let raw_b = eval(scope, "-184467440737095516170n").unwrap();
-  assert!(b == raw_b);
+  let v = eval(scope, "2n ** 63n").unwrap();
+  assert_eq!(v.int64_value(scope), Some((i64::MIN, false)));
+  assert_eq!(v.uint64_value(scope), Some((1 << 63, true)));
 
-  let raw_b = v8::Local::<v8::BigInt>::try_from(raw_b).unwrap();
+  let v = eval(scope, "2 ** 63").unwrap();
+  assert!(!v.int64_value(scope).unwrap().1);
+  assert_eq!(v.uint64_value(scope), Some((1 << 63, true)));
 
-  let mut vec = Vec::new();
-  vec.resize(raw_b.word_count(), 0);
-  assert_eq!(raw_b.to_words_array(&mut vec), (true, &mut [10, 10][..]));
+  let v = eval(scope, "1.5").unwrap();
+  assert_eq!(v.int64_value(scope), Some((1, false)));
 
-  let mut vec = Vec::new();
-  vec.resize(1, 0);
-  assert_eq!(raw_b.to_words_array(&mut vec), (true, &mut [10][..]));
+  let v = eval(scope, "'123'").unwrap();
+  assert_eq!(v.int64_value(scope), Some((123, true)));
 
-  let mut vec = Vec::new();
-  vec.resize(20, 1337);
-  assert_eq!(raw_b.to_words_array(&mut vec), (true, &mut [10, 10][..]));
+  let v = eval(scope, "undefined").unwrap();
+  let scope = &mut v8::TryCatch::new(scope);
+  assert!(v.int64_value(scope).is_none());
+  assert!(scope.has_caught());
 }
 
 // SerDes testing
@@ -5264,6 +8089,74 @@ fn value_serializer_and_deserializer_array_buffers() {
   }
 }
 
+#[test]
+fn value_serializer_and_deserializer_cyclic_typed_arrays() {
+  use v8::ValueDeserializerHelper;
+  use v8::ValueSerializerHelper;
+
+  let buffer;
+  let mut array_buffers = ArrayBuffers::new();
+  {
+    let _setup_guard = setup();
+    let isolate = &mut v8::Isolate::new(Default::default());
+
+    let scope = &mut v8::HandleScope::new(isolate);
+
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let value = eval(
+      scope,
+      r#"
+      {
+        const root = { bytes: new Uint8Array([1, 2, 3]) };
+        root.self = root;
+        root.view = new Uint8Array(root.bytes.buffer, 1, 2);
+        root
+      }
+      "#,
+    )
+    .unwrap();
+    let mut value_serializer =
+      Custom1Value::serializer(scope, &mut array_buffers);
+    value_serializer.write_header();
+    assert_eq!(value_serializer.write_value(context, value), Some(true));
+    buffer = value_serializer.release();
+  }
+
+  {
+    let _setup_guard = setup();
+    let isolate = &mut v8::Isolate::new(Default::default());
+
+    let scope = &mut v8::HandleScope::new(isolate);
+
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let mut value_deserializer =
+      Custom1Value::deserializer(scope, &buffer, &mut array_buffers);
+    assert_eq!(value_deserializer.read_header(context), Some(true));
+    let value = value_deserializer.read_value(context).unwrap();
+    drop(value_deserializer);
+
+    let name = v8::String::new(scope, "root").unwrap();
+    context.global(scope).set(scope, name.into(), value);
+
+    let result = eval(
+      scope,
+      r#"
+      root.self === root &&
+        root.bytes instanceof Uint8Array &&
+        root.bytes.join() === "1,2,3" &&
+        root.view.buffer === root.bytes.buffer &&
+        root.view.join() === "2,3"
+      "#,
+    )
+    .unwrap();
+    assert!(result.is_true());
+  }
+}
+
 struct Custom2Value {}
 
 impl<'a> Custom2Value {
@@ -5431,6 +8324,133 @@ fn wasm_streaming_callback() {
   assert!(global.get(scope, name).unwrap().strict_equals(exception));
 }
 
+#[test]
+fn wasm_code_generation_callbacks() {
+  static MODULE_CALLS: AtomicUsize = AtomicUsize::new(0);
+  static INSTANCE_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn deny_wasm_code_generation(
+    _context: v8::Local<v8::Context>,
+    _source: v8::Local<v8::String>,
+  ) -> bool {
+    false
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_wasm_module_callback(
+    |_: &mut v8::HandleScope,
+     _: v8::FunctionCallbackArguments,
+     _: v8::ReturnValue| {
+      MODULE_CALLS.fetch_add(1, Ordering::SeqCst);
+      false
+    },
+  );
+  isolate.set_wasm_instance_callback(
+    |_: &mut v8::HandleScope,
+     _: v8::FunctionCallbackArguments,
+     _: v8::ReturnValue| {
+      INSTANCE_CALLS.fetch_add(1, Ordering::SeqCst);
+      false
+    },
+  );
+
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  // MVP of WASM modules: contains only the magic marker and the version (1).
+  let source = r#"
+    const bytes = new Uint8Array([0x00, 0x61, 0x73, 0x6d, 0x01, 0, 0, 0]);
+    new WebAssembly.Instance(new WebAssembly.Module(bytes));
+  "#;
+  let result = eval(scope, source).unwrap();
+  assert!(result.is_object());
+  assert_eq!(MODULE_CALLS.load(Ordering::SeqCst), 1);
+  assert_eq!(INSTANCE_CALLS.load(Ordering::SeqCst), 1);
+
+  scope.set_allow_wasm_code_generation_callback(deny_wasm_code_generation);
+  let scope = &mut v8::TryCatch::new(scope);
+  assert!(eval(scope, source).is_none());
+  assert!(scope.has_caught());
+  assert_eq!(MODULE_CALLS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn add_crash_key_callback() {
+  static ISOLATE_ADDRESS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_add_crash_key_callback(|id: v8::CrashKeyId, value: &str| {
+    if id == v8::CrashKeyId::IsolateAddress {
+      assert!(!value.is_empty());
+      ISOLATE_ADDRESS_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+  });
+  // The initial set of crash keys is reported synchronously.
+  assert_eq!(ISOLATE_ADDRESS_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn use_counter_callback() {
+  static STRICT_MODE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn callback(
+    _isolate: &mut v8::Isolate,
+    feature: v8::UseCounterFeature,
+  ) {
+    if feature == v8::UseCounterFeature::StrictMode {
+      STRICT_MODE_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_use_counter_callback(callback);
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    eval(scope, "'use strict'; 1 + 1").unwrap();
+    assert!(STRICT_MODE_COUNT.load(Ordering::SeqCst) > 0);
+  }
+}
+
+#[test]
+fn callback_scope_with_current_context() {
+  static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn callback(
+    isolate: &mut v8::Isolate,
+    feature: v8::UseCounterFeature,
+  ) {
+    if feature != v8::UseCounterFeature::StrictMode {
+      return;
+    }
+    let scope =
+      &mut unsafe { v8::CallbackScope::with_current_context(isolate) }.unwrap();
+    let scope = &mut v8::HandleScope::new(scope);
+    let global = scope.get_current_context().global(scope);
+    let key = v8::String::new(scope, "marker").unwrap();
+    let marker = global.get(scope, key.into()).unwrap();
+    assert_eq!(marker.to_rust_string_lossy(scope), "current");
+    CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  assert!(unsafe { v8::CallbackScope::with_current_context(isolate) }.is_none());
+  isolate.set_use_counter_callback(callback);
+
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  eval(scope, "globalThis.marker = 'current'").unwrap();
+  eval(scope, "'use strict'; 1 + 1").unwrap();
+  assert!(CALL_COUNT.load(Ordering::SeqCst) > 0);
+}
+
 #[test]
 fn unbound_script_conversion() {
   let _setup_guard = setup();
@@ -5459,6 +8479,46 @@ fn unbound_script_conversion() {
   }
 }
 
+#[test]
+fn unbound_script_id_and_name() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let resource_name = v8::String::new(scope, "foo.js").unwrap();
+  let source_map_url = v8::undefined(scope);
+  let origin = v8::ScriptOrigin::new(
+    scope,
+    resource_name.into(),
+    0,
+    0,
+    false,
+    -1,
+    source_map_url.into(),
+    false,
+    false,
+    false,
+  );
+  let source = v8::String::new(scope, "1 + 1").unwrap();
+  let script = v8::Script::compile(scope, source, Some(&origin)).unwrap();
+  let unbound_script = script.get_unbound_script(scope);
+  assert!(unbound_script.get_id() > 0);
+  assert_eq!(
+    unbound_script
+      .get_script_name(scope)
+      .to_rust_string_lossy(scope),
+    "foo.js"
+  );
+
+  let source = v8::String::new(scope, "2 + 2").unwrap();
+  let other_script = v8::Script::compile(scope, source, None).unwrap();
+  let other_unbound_script = other_script.get_unbound_script(scope);
+  assert_ne!(unbound_script.get_id(), other_unbound_script.get_id());
+  assert!(other_unbound_script.get_script_name(scope).is_undefined());
+}
+
 #[test]
 fn run_with_rust_allocator() {
   use std::sync::Arc;
@@ -5636,6 +8696,33 @@ fn prepare_stack_trace_callback() {
   }
 }
 
+#[test]
+fn prepare_stack_trace_callback_ignores_js_override() {
+  fn callback<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    _error: v8::Local<v8::Value>,
+    sites: v8::Local<v8::Array>,
+  ) -> v8::Local<'s, v8::Value> {
+    let stack = format!("native: {} frames", sites.length());
+    v8::String::new(scope, &stack).unwrap().into()
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_prepare_stack_trace_callback(callback);
+
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let script = r#"
+    Error.prepareStackTrace = () => "overridden";
+    (function f() { return new Error("boom").stack })()
+  "#;
+  let result = eval(scope, script).unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "native: 2 frames");
+}
+
 #[test]
 fn icu_date() {
   let _setup_guard = setup();
@@ -5761,6 +8848,27 @@ fn unbound_module_script_conversion() {
   create_unbound_module_script(&mut scope, "'Hello ' + value", None);
 }
 
+#[test]
+#[should_panic(
+  expected = "get_unbound_module_script() requires a source text module"
+)]
+fn synthetic_module_has_no_unbound_module_script() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let module_name = v8::String::new(scope, "synthetic module").unwrap();
+  let module = v8::Module::create_synthetic_module(
+    scope,
+    module_name,
+    &[],
+    synthetic_evaluation_steps,
+  );
+  module.get_unbound_module_script(scope);
+}
+
 #[test]
 fn cached_data_version_tag() {
   // The value is unpredictable/unstable, as it is generated from a combined
@@ -5816,16 +8924,70 @@ fn code_cache() {
   let top =
     v8::Local::<v8::Object>::try_from(module.get_module_namespace()).unwrap();
 
-  let key = v8::String::new(&mut scope, "hello").unwrap();
-  let value =
-    v8::Local::<v8::String>::try_from(top.get(&mut scope, key.into()).unwrap())
-      .unwrap();
-  assert_eq!(&value.to_rust_string_lossy(&mut scope), "world");
+  let key = v8::String::new(&mut scope, "hello").unwrap();
+  let value =
+    v8::Local::<v8::String>::try_from(top.get(&mut scope, key.into()).unwrap())
+      .unwrap();
+  assert_eq!(&value.to_rust_string_lossy(&mut scope), "world");
+}
+
+#[test]
+fn function_code_cache() {
+  const CODE: &str = "return word.split('').reverse().join('');";
+  let _setup_guard = setup();
+
+  let code_cache = {
+    let isolate = &mut v8::Isolate::new(Default::default());
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let source = v8::script_compiler::Source::new(
+      v8::String::new(scope, CODE).unwrap(),
+      None,
+    );
+    let word = v8::String::new(scope, "word").unwrap();
+    let function = v8::script_compiler::compile_function(
+      scope,
+      source,
+      &[word],
+      &[],
+      v8::script_compiler::CompileOptions::EagerCompile,
+      v8::script_compiler::NoCacheReason::NoReason,
+    )
+    .unwrap();
+    function.create_code_cache().unwrap()
+  };
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let source = v8::script_compiler::Source::new_with_cached_data(
+    v8::String::new(scope, CODE).unwrap(),
+    None,
+    code_cache,
+  );
+  let word = v8::String::new(scope, "word").unwrap();
+  let function = v8::script_compiler::compile_function(
+    scope,
+    source,
+    &[word],
+    &[],
+    v8::script_compiler::CompileOptions::EagerCompile,
+    v8::script_compiler::NoCacheReason::NoReason,
+  )
+  .unwrap();
+
+  let input = v8::String::new(scope, "input").unwrap().into();
+  let expected = v8::String::new(scope, "tupni").unwrap();
+  let undefined = v8::undefined(scope).into();
+  assert_eq!(expected, function.call(scope, undefined, &[input]).unwrap());
 }
 
 #[test]
-fn function_code_cache() {
-  const CODE: &str = "return word.split('').reverse().join('');";
+fn function_code_cache_with_context_extensions() {
+  const CODE: &str = "return `${greeting}, ${name}!`;";
   let _setup_guard = setup();
 
   let code_cache = {
@@ -5837,18 +8999,20 @@ fn function_code_cache() {
       v8::String::new(scope, CODE).unwrap(),
       None,
     );
-    let word = v8::String::new(scope, "word").unwrap();
+    let name = v8::String::new(scope, "name").unwrap();
+    let extension = v8::Object::new(scope);
     let function = v8::script_compiler::compile_function(
       scope,
       source,
-      &[word],
-      &[],
+      &[name],
+      &[extension],
       v8::script_compiler::CompileOptions::EagerCompile,
       v8::script_compiler::NoCacheReason::NoReason,
     )
     .unwrap();
-    function.create_code_cache().unwrap()
+    function.create_code_cache().unwrap().to_vec()
   };
+  assert!(!code_cache.is_empty());
 
   let isolate = &mut v8::Isolate::new(Default::default());
   let scope = &mut v8::HandleScope::new(isolate);
@@ -5858,21 +9022,25 @@ fn function_code_cache() {
   let source = v8::script_compiler::Source::new_with_cached_data(
     v8::String::new(scope, CODE).unwrap(),
     None,
-    code_cache,
+    v8::CachedData::new(&code_cache),
   );
-  let word = v8::String::new(scope, "word").unwrap();
+  let name = v8::String::new(scope, "name").unwrap();
+  let extension = v8::Object::new(scope);
+  let key = v8::String::new(scope, "greeting").unwrap();
+  let value = v8::String::new(scope, "Hello").unwrap();
+  extension.set(scope, key.into(), value.into()).unwrap();
   let function = v8::script_compiler::compile_function(
     scope,
     source,
-    &[word],
-    &[],
-    v8::script_compiler::CompileOptions::EagerCompile,
+    &[name],
+    &[extension],
+    v8::script_compiler::CompileOptions::ConsumeCodeCache,
     v8::script_compiler::NoCacheReason::NoReason,
   )
   .unwrap();
 
-  let input = v8::String::new(scope, "input").unwrap().into();
-  let expected = v8::String::new(scope, "tupni").unwrap();
+  let input = v8::String::new(scope, "world").unwrap().into();
+  let expected = v8::String::new(scope, "Hello, world!").unwrap();
   let undefined = v8::undefined(scope).into();
   assert_eq!(expected, function.call(scope, undefined, &[input]).unwrap());
 }
@@ -5976,6 +9144,95 @@ fn compile_function() {
   assert_eq!(42 * 1337, result.int32_value(scope).unwrap());
 }
 
+#[test]
+fn streaming_compile() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let code = "const greeting = 'h\u{20ac}llo';\n\
+              function add(a, b) { return a + b; }\n\
+              `${greeting} ${add(40, 2)}`";
+  // Split in the middle of the three byte euro sign.
+  let (head, tail) = code.as_bytes().split_at(21);
+
+  let (sender, receiver) = std::sync::mpsc::channel::<Vec<u8>>();
+  let source = v8::script_compiler::StreamedSource::new(
+    move || receiver.recv().ok(),
+    v8::script_compiler::StreamedSourceEncoding::Utf8,
+  );
+  // SAFETY: the isolate outlives the task, which is joined and compiled
+  // below.
+  let mut task = unsafe { v8::script_compiler::start_streaming(scope, source) };
+  let thread = std::thread::spawn(move || {
+    task.run();
+    task
+  });
+  sender.send(head.to_vec()).unwrap();
+  sender.send(tail.to_vec()).unwrap();
+  drop(sender);
+  let task = thread.join().unwrap();
+
+  let full_source = v8::String::new(scope, code).unwrap();
+  let resource_name = v8::String::new(scope, "streamed.js").unwrap();
+  let source_map_url = v8::undefined(scope);
+  let origin = v8::ScriptOrigin::new(
+    scope,
+    resource_name.into(),
+    0,
+    0,
+    false,
+    0,
+    source_map_url.into(),
+    false,
+    false,
+    false,
+  );
+  let script =
+    v8::script_compiler::compile_streamed(scope, task, full_source, &origin)
+      .unwrap();
+  let result = script.run(scope).unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "h\u{20ac}llo 42");
+
+  // A task that was never run is run when the script is compiled.
+  let chunks = vec![b"6 * ".to_vec(), b"7".to_vec()];
+  let mut chunks = chunks.into_iter();
+  let source = v8::script_compiler::StreamedSource::new(
+    move || chunks.next(),
+    v8::script_compiler::StreamedSourceEncoding::OneByte,
+  );
+  let task = unsafe { v8::script_compiler::start_streaming(scope, source) };
+  let full_source = v8::String::new(scope, "6 * 7").unwrap();
+  let script =
+    v8::script_compiler::compile_streamed(scope, task, full_source, &origin)
+      .unwrap();
+  let result = script.run(scope).unwrap();
+  assert_eq!(result.int32_value(scope), Some(42));
+}
+
+#[test]
+#[should_panic(expected = "streaming task was started on a different isolate")]
+fn streaming_compile_on_other_isolate() {
+  let _setup_guard = setup();
+  let other_isolate = &mut v8::Isolate::new(Default::default());
+  let source = v8::script_compiler::StreamedSource::new(
+    || None,
+    v8::script_compiler::StreamedSourceEncoding::OneByte,
+  );
+  let task =
+    unsafe { v8::script_compiler::start_streaming(other_isolate, source) };
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let full_source = v8::String::new(scope, "").unwrap();
+  let origin = mock_script_origin(scope, "streamed.js");
+  v8::script_compiler::compile_streamed(scope, task, full_source, &origin);
+}
+
 #[test]
 fn external_strings() {
   let _setup_guard = setup();
@@ -6034,6 +9291,22 @@ fn external_strings() {
   assert!(!gradients.is_external_twobyte());
   assert!(!gradients.is_onebyte());
   assert!(!gradients.contains_only_onebyte());
+
+  // one-byte "internal" test
+  let latin1 = v8::String::new(scope, "caf\u{e9}").unwrap();
+  assert!(!latin1.is_external());
+  assert!(!latin1.is_external_onebyte());
+  assert!(!latin1.is_external_twobyte());
+  assert!(latin1.is_onebyte());
+  assert!(latin1.contains_only_onebyte());
+
+  // A two-byte representation of one-byte data is only detected by
+  // contains_only_onebyte().
+  let two_byte_latin1 =
+    v8::String::new_external_twobyte_static(scope, &[0x0068, 0x0069]).unwrap();
+  assert!(two_byte_latin1.is_external_twobyte());
+  assert!(!two_byte_latin1.is_onebyte());
+  assert!(two_byte_latin1.contains_only_onebyte());
 }
 
 #[test]
@@ -6085,6 +9358,53 @@ fn counter_lookup_callback() {
   assert_ne!(count, 0);
 }
 
+#[test]
+fn isolate_counter_and_histogram_functions() {
+  static COUNTER_COUNT: AtomicUsize = AtomicUsize::new(0);
+  static HISTOGRAM_COUNT: AtomicUsize = AtomicUsize::new(0);
+  static SAMPLE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn counter_function(_name: *const c_char) -> *mut i32 {
+    COUNTER_COUNT.fetch_add(1, Ordering::SeqCst);
+    Box::leak(Box::new(0))
+  }
+
+  extern "C" fn create_histogram_function(
+    name: *const c_char,
+    min: i32,
+    max: i32,
+    _buckets: usize,
+  ) -> *mut c_void {
+    assert!(!name.is_null());
+    assert!(min <= max);
+    HISTOGRAM_COUNT.fetch_add(1, Ordering::SeqCst);
+    // Any non-null pointer identifies the histogram.
+    NonNull::<u8>::dangling().as_ptr() as *mut c_void
+  }
+
+  extern "C" fn add_histogram_sample_function(
+    histogram: *mut c_void,
+    _sample: i32,
+  ) {
+    assert_eq!(histogram, NonNull::<u8>::dangling().as_ptr() as *mut c_void);
+    SAMPLE_COUNT.fetch_add(1, Ordering::SeqCst);
+  }
+
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_counter_function(counter_function);
+  isolate.set_create_histogram_function(create_histogram_function);
+  isolate.set_add_histogram_sample_function(add_histogram_sample_function);
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  eval(scope, "JSON.parse('[1, 2, 3]'); gc()").unwrap();
+
+  assert_ne!(COUNTER_COUNT.load(Ordering::SeqCst), 0);
+  assert_ne!(HISTOGRAM_COUNT.load(Ordering::SeqCst), 0);
+  assert_ne!(SAMPLE_COUNT.load(Ordering::SeqCst), 0);
+}
+
 #[cfg(not(target_os = "android"))]
 #[test]
 fn compiled_wasm_module() {
@@ -6145,6 +9465,40 @@ fn compiled_wasm_module() {
   }
 }
 
+#[cfg(not(target_os = "android"))]
+#[test]
+fn compiled_wasm_module_cross_thread() {
+  let _setup_guard = setup();
+
+  let compiled_module = {
+    let isolate = &mut v8::Isolate::new(Default::default());
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let wire_bytes = &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+    let module = v8::WasmModuleObject::compile(scope, wire_bytes).unwrap();
+    module.get_compiled_module()
+  };
+
+  std::thread::spawn(move || {
+    let isolate = &mut v8::Isolate::new(Default::default());
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    let module =
+      v8::WasmModuleObject::from_compiled_module(scope, &compiled_module)
+        .unwrap();
+    let key = v8::String::new(scope, "module").unwrap().into();
+    context.global(scope).set(scope, key, module.into());
+    let instance = eval(scope, "new WebAssembly.Instance(module)").unwrap();
+    assert!(instance.is_object());
+  })
+  .join()
+  .unwrap();
+}
+
 #[test]
 fn function_names() {
   // Setup isolate
@@ -6209,6 +9563,33 @@ fn function_names() {
     let v8_name = func.get_name(scope);
     assert_eq!(v8_name.to_rust_string_lossy(scope), "");
   }
+
+  // anon js function, named after where it was assigned
+  {
+    let func: v8::Local<v8::Function> = eval(
+      scope,
+      "const obj = {}; obj.method = function() {}; obj.method",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let v8_name = func.get_name(scope);
+    assert_eq!(v8_name.to_rust_string_lossy(scope), "");
+    let inferred_name = func.get_inferred_name(scope);
+    assert_eq!(inferred_name.to_rust_string_lossy(scope), "obj.method");
+    let debug_name = func.get_debug_name(scope);
+    assert_eq!(debug_name.to_rust_string_lossy(scope), "obj.method");
+  }
+
+  // v8 function named through its template
+  {
+    let name = v8::String::new(scope, "hostCallback").unwrap();
+    let tmpl = v8::FunctionTemplate::new(scope, callback);
+    tmpl.set_class_name(name);
+    let func = tmpl.get_function(scope).unwrap();
+    let debug_name = func.get_debug_name(scope);
+    assert_eq!(debug_name.to_rust_string_lossy(scope), "hostCallback");
+  }
 }
 
 // https://github.com/denoland/rusty_v8/issues/849
@@ -6287,6 +9668,51 @@ fn current_stack_trace() {
   assert_eq!(too_deep, 5);
 }
 
+#[test]
+fn print_current_stack_trace() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  fn print_stack(
+    scope: &mut v8::HandleScope,
+    _args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+  ) {
+    let mut out = Vec::new();
+    scope.print_current_stack_trace(&mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    rv.set(v8::String::new(scope, &text).unwrap().into());
+  }
+
+  let key = v8::String::new(scope, "printStack").unwrap();
+  let func = v8::Function::new(scope, print_stack).unwrap();
+  let global = context.global(scope);
+  global.set(scope, key.into(), func.into());
+
+  let trace = eval(
+    scope,
+    "function inner() { return printStack(); }
+     function outer() { return inner(); }
+     outer()",
+  )
+  .unwrap()
+  .to_rust_string_lossy(scope);
+  let inner = trace.find("inner").unwrap();
+  let outer = trace.find("outer").unwrap();
+  assert!(inner < outer);
+  assert!(trace.lines().count() >= 3);
+  assert!(trace.lines().all(|line| line.starts_with("    at ")));
+  assert!(trace.lines().next().unwrap().starts_with("    at inner ("));
+
+  // Without JavaScript on the stack, nothing is printed.
+  let mut out = Vec::new();
+  scope.print_current_stack_trace(&mut out).unwrap();
+  assert!(out.is_empty());
+}
+
 #[test]
 fn instance_of() {
   let _setup_guard = setup();
@@ -6422,6 +9848,131 @@ fn weak_from_global() {
   assert!(weak.is_empty());
 }
 
+#[test]
+fn global_wrapper_class_id() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let object = v8::Object::new(scope);
+  let global = v8::Global::new(scope, object);
+  assert_eq!(global.get_wrapper_class_id(), 0);
+  global.set_wrapper_class_id(42);
+  assert_eq!(global.get_wrapper_class_id(), 42);
+
+  // The class id belongs to the handle, not to the object.
+  let other = v8::Global::new(scope, object);
+  assert_eq!(other.get_wrapper_class_id(), 0);
+  let clone = global.clone();
+  assert_eq!(clone.get_wrapper_class_id(), 0);
+  assert_eq!(global.get_wrapper_class_id(), 42);
+}
+
+#[test]
+fn visit_handles_with_class_ids() {
+  let _setup_guard = setup();
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let globals = (0..4)
+    .map(|_| {
+      let object = v8::Object::new(scope);
+      v8::Global::new(scope, object)
+    })
+    .collect::<Vec<_>>();
+  globals[0].set_wrapper_class_id(1);
+  globals[1].set_wrapper_class_id(1);
+  globals[2].set_wrapper_class_id(2);
+
+  let mut visited = Vec::new();
+  scope.visit_handles_with_class_ids(
+    &mut |value: v8::Local<v8::Value>, class_id: u16| {
+      let index = globals.iter().position(|global| *global == value);
+      visited.push((index, class_id));
+    },
+  );
+  visited.sort_unstable();
+  assert_eq!(visited, vec![(Some(0), 1), (Some(1), 1), (Some(2), 2)]);
+}
+
+#[test]
+fn global_downgrade_weak_upgrade() {
+  let _setup_guard = setup();
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let global = {
+    let scope = &mut v8::HandleScope::new(scope);
+    let object = v8::Object::new(scope);
+    v8::Global::new(scope, object)
+  };
+
+  let weak = global.downgrade();
+  assert!(!weak.is_empty());
+  let upgraded = weak.upgrade(scope).unwrap();
+  assert_eq!(upgraded, global);
+
+  drop(global);
+  eval(scope, "gc()").unwrap();
+  assert!(!weak.is_empty());
+
+  drop(upgraded);
+  eval(scope, "gc()").unwrap();
+  assert!(weak.is_empty());
+  assert!(weak.upgrade(scope).is_none());
+}
+
+#[test]
+fn weak_key() {
+  use std::collections::HashMap;
+
+  let _setup_guard = setup();
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let mut map = HashMap::new();
+  let global = {
+    let scope = &mut v8::HandleScope::new(scope);
+    let collected = v8::Object::new(scope);
+    let kept = v8::Object::new(scope);
+    let collected_key = v8::WeakKey::new(scope, collected);
+    map.insert(collected_key.clone(), "collected");
+    map.insert(v8::WeakKey::new(scope, kept), "kept");
+    assert_eq!(map.len(), 2);
+
+    // A new key for the same object finds the existing entry.
+    assert_eq!(
+      map.get(&v8::WeakKey::new(scope, collected)),
+      Some(&"collected")
+    );
+    map.insert(v8::WeakKey::new(scope, collected), "replaced");
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&collected_key), Some(&"replaced"));
+
+    v8::Global::new(scope, kept)
+  };
+
+  let scope = &mut v8::HandleScope::new(scope);
+  eval(scope, "gc()").unwrap();
+
+  // Keys of collected objects can still be purged.
+  assert_eq!(map.keys().filter(|key| key.is_empty()).count(), 1);
+  map.retain(|key, _| !key.is_empty());
+  assert_eq!(map.len(), 1);
+  let kept = v8::Local::new(scope, &global);
+  assert_eq!(map.get(&v8::WeakKey::new(scope, kept)), Some(&"kept"));
+}
+
 #[test]
 fn weak_from_into_raw() {
   use std::cell::Cell;
@@ -6572,6 +10123,60 @@ fn drop_weak_from_raw_in_finalizer() {
   assert!(finalized.get());
 }
 
+#[test]
+fn drop_weak_between_finalization_passes() {
+  use std::cell::Cell;
+  use std::rc::Rc;
+
+  let _setup_guard = setup();
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let finalized = Rc::new(Cell::new(false));
+  let weak = {
+    let scope = &mut v8::HandleScope::new(scope);
+    let local = v8::Object::new(scope);
+    v8::Weak::with_finalizer(
+      scope,
+      &local,
+      Box::new({
+        let finalized = finalized.clone();
+        move |_| finalized.set(true)
+      }),
+    )
+  };
+
+  // Unlike forced collections such as `gc()`, collections triggered by
+  // allocation pressure don't run second pass callbacks synchronously: V8
+  // posts them as a foreground task instead. Allocating enough to fill the
+  // young generation triggers a scavenge that collects the object and runs
+  // the first pass callback, which empties the `Weak`.
+  for _ in 0..100 {
+    if weak.is_empty() {
+      break;
+    }
+    let scope = &mut v8::HandleScope::new(scope);
+    eval(scope, "for (let i = 0; i < 10000; i++) new Array(100)").unwrap();
+  }
+  // The first pass has run, but the second pass, which calls the finalizer,
+  // hasn't run yet.
+  assert!(weak.is_empty());
+  assert!(!finalized.get());
+
+  // Dropping the `Weak` now must neither free the data the second pass
+  // callback uses nor let the finalizer run.
+  drop(weak);
+
+  let platform = v8::V8::get_current_platform();
+  while v8::Platform::pump_message_loop(&platform, scope, false) {}
+  let scope = &mut v8::HandleScope::new(scope);
+  eval(scope, "gc()").unwrap();
+  assert!(!finalized.get());
+}
+
 #[test]
 fn finalizer_on_global_object() {
   use std::cell::Cell;
@@ -0,0 +1,41 @@
+// Tests from the same file run in a single process. That's why this test
+// is in its own file, because --abort-on-uncaught-exception affects the whole
+// process.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+#[test]
+fn abort_on_uncaught_exception_callback() {
+  static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn callback(_isolate: &mut v8::Isolate) -> bool {
+    CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+    // Returning false keeps V8 from aborting the process.
+    false
+  }
+
+  v8::V8::set_flags_from_string("--abort-on-uncaught-exception");
+  v8::V8::initialize_platform(v8::new_default_platform(0, false).make_shared());
+  v8::V8::initialize();
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.set_abort_on_uncaught_exception_callback(callback);
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  // An exception caught in JavaScript is not reported to the callback.
+  let source = "try { throw new Error('caught') } catch (e) { 42 }";
+  let source = v8::String::new(scope, source).unwrap();
+  let script = v8::Script::compile(scope, source, None).unwrap();
+  let result = script.run(scope).unwrap();
+  assert_eq!(result.int32_value(scope), Some(42));
+  assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 0);
+
+  // An uncaught one is, and the process survives because it returned false.
+  let source = v8::String::new(scope, "throw new Error('uncaught')").unwrap();
+  let script = v8::Script::compile(scope, source, None).unwrap();
+  assert!(script.run(scope).is_none());
+  assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,48 @@
+// Tests from the same file run in a single process. That's why this test
+// is in its own file, because resizable array buffers are behind a V8 flag
+// that affects the whole process.
+
+#[test]
+fn resizable_array_buffer() {
+  v8::V8::set_flags_from_string("--harmony-rab-gsab");
+  v8::V8::initialize_platform(v8::new_default_platform(0, false).make_shared());
+  v8::V8::initialize();
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  let fixed = v8::ArrayBuffer::new(scope, 8);
+  assert!(!fixed.is_resizable_by_user_javascript());
+  assert_eq!(fixed.max_byte_length(), 8);
+  let backing_store = fixed.get_backing_store();
+  assert!(!backing_store.is_resizable_by_user_javascript());
+  assert_eq!(backing_store.max_byte_length(), 8);
+
+  let buffer = v8::ArrayBuffer::new_resizable(scope, 4, 16).unwrap();
+  assert!(buffer.is_resizable_by_user_javascript());
+  assert_eq!(buffer.byte_length(), 4);
+  assert_eq!(buffer.max_byte_length(), 16);
+  let backing_store = buffer.get_backing_store();
+  assert!(backing_store.is_resizable_by_user_javascript());
+  assert_eq!(backing_store.max_byte_length(), 16);
+
+  // The buffer grows in place.
+  let data = backing_store.data();
+  let key = v8::String::new(scope, "buffer").unwrap();
+  context
+    .global(scope)
+    .set(scope, key.into(), buffer.into())
+    .unwrap();
+  let source = v8::String::new(scope, "buffer.resize(12)").unwrap();
+  let script = v8::Script::compile(scope, source, None).unwrap();
+  script.run(scope).unwrap();
+  assert_eq!(buffer.byte_length(), 12);
+  assert_eq!(buffer.max_byte_length(), 16);
+  assert_eq!(buffer.get_backing_store().data(), data);
+
+  let scope = &mut v8::TryCatch::new(scope);
+  assert!(v8::ArrayBuffer::new_resizable(scope, 8, 4).is_none());
+  assert!(scope.has_caught());
+}
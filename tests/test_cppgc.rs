@@ -0,0 +1,95 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+static TAG: u16 = v8::cppgc::DEFAULT_CPP_GC_EMBEDDER_ID;
+static TRACE_COUNT: AtomicUsize = AtomicUsize::new(0);
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+struct Child;
+
+impl v8::cppgc::GarbageCollected for Child {}
+
+impl Drop for Child {
+  fn drop(&mut self) {
+    DROP_COUNT.fetch_add(1, Ordering::SeqCst);
+  }
+}
+
+struct Wrappable {
+  value: u32,
+  child: v8::cppgc::Member<Child>,
+}
+
+impl v8::cppgc::GarbageCollected for Wrappable {
+  fn trace(&self, visitor: &v8::cppgc::Visitor) {
+    TRACE_COUNT.fetch_add(1, Ordering::SeqCst);
+    // `child` is only ever reachable through this object, which reports it
+    // on every trace.
+    unsafe { visitor.trace(&self.child) };
+  }
+}
+
+impl Drop for Wrappable {
+  fn drop(&mut self) {
+    DROP_COUNT.fetch_add(1, Ordering::SeqCst);
+  }
+}
+
+#[test]
+fn cppgc_object_wrap() {
+  v8::V8::set_flags_from_string("--expose-gc");
+  v8::V8::initialize_platform(v8::new_default_platform(0, false).make_shared());
+  v8::V8::initialize();
+  let platform = v8::V8::get_current_platform();
+  v8::cppgc::initialize_process(&platform);
+
+  let mut heap = v8::cppgc::Heap::create(&platform, Default::default());
+  let isolate = &mut v8::Isolate::new(Default::default());
+  isolate.attach_cpp_heap(&mut heap);
+
+  {
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    {
+      let scope = &mut v8::HandleScope::new(scope);
+      let templ = v8::ObjectTemplate::new(scope);
+      templ.set_internal_field_count(2);
+      let obj = templ.new_instance(scope).unwrap();
+
+      // Both objects become reachable through `obj` below, before the first
+      // garbage collection.
+      unsafe {
+        let child =
+          v8::cppgc::make_garbage_collected(&mut heap, Box::new(Child));
+        let member = v8::cppgc::make_garbage_collected(
+          &mut heap,
+          Box::new(Wrappable { value: 42, child }),
+        );
+        assert!(obj.set_aligned_pointer_in_internal_field(
+          0,
+          &TAG as *const u16 as *mut _,
+        ));
+        assert!(obj.set_aligned_pointer_in_internal_field(1, member.handle()));
+
+        let handle = obj.get_aligned_pointer_from_internal_field(1);
+        let unwrapped = v8::cppgc::Member::<Wrappable>::from_handle(handle);
+        assert_eq!(unwrapped.borrow().value, 42);
+      }
+
+      heap.collect_garbage_for_testing(
+        v8::cppgc::EmbedderStackState::NoHeapPointers,
+      );
+      assert!(TRACE_COUNT.load(Ordering::SeqCst) > 0);
+      assert_eq!(DROP_COUNT.load(Ordering::SeqCst), 0);
+    }
+
+    heap.collect_garbage_for_testing(
+      v8::cppgc::EmbedderStackState::NoHeapPointers,
+    );
+    assert_eq!(DROP_COUNT.load(Ordering::SeqCst), 2);
+  }
+
+  isolate.detach_cpp_heap();
+}
@@ -0,0 +1,54 @@
+#[test]
+fn pump_message_loop_runs_foreground_tasks() {
+  v8::V8::set_flags_from_string("--expose-gc");
+  v8::V8::initialize_platform(v8::new_default_platform(0, true).make_shared());
+  v8::V8::initialize();
+  let platform = v8::V8::get_current_platform();
+
+  let isolate = &mut v8::Isolate::new(Default::default());
+  let scope = &mut v8::HandleScope::new(isolate);
+  let context = v8::Context::new(scope);
+  let scope = &mut v8::ContextScope::new(scope, context);
+
+  // V8 posts a foreground task to run FinalizationRegistry cleanup callbacks
+  // after a garbage collection. It only runs when the embedder pumps the
+  // message loop.
+  let source = r#"
+    globalThis.cleaned = [];
+    const registry = new FinalizationRegistry((held) => cleaned.push(held));
+    (function() {
+      registry.register({}, "held");
+    })();
+    gc();
+    cleaned.length
+  "#;
+  let source = v8::String::new(scope, source).unwrap();
+  let script = v8::Script::compile(scope, source, None).unwrap();
+  let result = script.run(scope).unwrap();
+  assert_eq!(result.int32_value(scope), Some(0));
+
+  while v8::Platform::pump_message_loop(&platform, scope, false) {
+    // do nothing
+  }
+  assert!(!v8::Platform::pump_message_loop(&platform, scope, false));
+
+  let source = v8::String::new(scope, "cleaned.join()").unwrap();
+  let script = v8::Script::compile(scope, source, None).unwrap();
+  let result = script.run(scope).unwrap();
+  assert_eq!(result.to_rust_string_lossy(scope), "held");
+
+  // The platform was created with idle task support, so idle tasks can be
+  // run too. This must not block when there is no idle work.
+  v8::Platform::run_idle_tasks(&platform, scope, 0.01);
+
+  // Drain all outstanding work before the isolate is dropped.
+  loop {
+    let wait_for_work = scope.has_pending_background_tasks();
+    if !v8::Platform::pump_message_loop(&platform, scope, wait_for_work)
+      && !wait_for_work
+    {
+      break;
+    }
+  }
+  assert!(!scope.has_pending_background_tasks());
+}